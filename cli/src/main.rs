@@ -0,0 +1,453 @@
+mod output;
+
+use std::{error::Error, fs::File, process::exit};
+
+use clap::{Args, Parser, Subcommand};
+use premint_airdrop::{instruction::InitializeAirdropArgs, state::ConfigLine};
+use premint_airdrop_client::AirdropClient;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair},
+    signer::Signer,
+};
+
+use serde::Deserialize;
+
+use crate::output::{
+    print, ConfigView, OutputFormat, QuoteView, TransactionView, UploadView, UserView,
+};
+
+#[derive(Parser)]
+#[clap(name = "premint-airdrop", version, about = "Operate premint airdrops")]
+struct Cli {
+    /// JSON RPC URL. Defaults to the Solana CLI config
+    #[clap(long, short = 'u', global = true)]
+    url: Option<String>,
+
+    /// Fee payer keypair file. Defaults to the Solana CLI config
+    #[clap(long, short = 'k', global = true)]
+    keypair: Option<String>,
+
+    /// Output format: `human` or `json`
+    #[clap(long, global = true, default_value = "human")]
+    output: OutputFormat,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// Metadata of minted tokens
+#[derive(Args)]
+struct MetadataArgs {
+    /// Base metadata URI. Token URI is `{base_uri}{number}{uri_extension}`, up to 200 bytes
+    #[clap(long, required_unless_present = "config-lines", default_value = "")]
+    base_uri: String,
+
+    /// Extension appended to token URI after the number, may be empty
+    #[clap(long, default_value = ".json")]
+    uri_extension: String,
+
+    /// Token name with `{}` replaced by the token number, up to 32 bytes
+    #[clap(long, required_unless_present = "config-lines", default_value = "")]
+    name_template: String,
+
+    /// Create a config lines account holding the name and URI of every token
+    /// instead of building them from templates. Upload them with `upload-lines`
+    #[clap(long, conflicts_with_all = &["hidden-uri", "base-uri", "name-template"])]
+    config_lines: bool,
+
+    /// Number of the first token
+    #[clap(long, default_value_t = 0)]
+    index_offset: u64,
+
+    /// Minimal digits of token number, zero padded
+    #[clap(long, default_value_t = 0)]
+    index_padding: u8,
+
+    /// Token name until reveal with `{}` replaced by the token number.
+    /// Set together with `--hidden-uri` to mint hidden tokens
+    #[clap(long, default_value = "")]
+    hidden_name_template: String,
+
+    /// Token URI until reveal. Empty if tokens are minted with final metadata
+    #[clap(long, default_value = "")]
+    hidden_uri: String,
+
+    /// Token symbol, up to 10 bytes
+    #[clap(long, default_value = "")]
+    symbol: String,
+
+    /// Base58 hash of metadata files in their original order.
+    /// Set together with `--seed-commitment` and hidden metadata for a fair mint
+    #[clap(long)]
+    provenance_hash: Option<Hash>,
+
+    /// Base58 SHA-256 of the seed revealed after the sale
+    #[clap(long)]
+    seed_commitment: Option<Hash>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create and initialize a new airdrop
+    Initialize {
+        /// Amount of tokens in the drop
+        #[clap(long)]
+        amount: u64,
+
+        /// Price of one token in lamports
+        #[clap(long)]
+        price: u64,
+
+        /// Assign airdrop indices at random instead of sequentially
+        #[clap(long)]
+        shuffled: bool,
+
+        /// Derive mint addresses from the airdrop and the amount of minted tokens
+        /// instead of signing every mint with a new keypair
+        #[clap(long)]
+        pda_mints: bool,
+
+        /// Require recipients to sign registration and every mint
+        #[clap(long)]
+        require_user_signature: bool,
+
+        /// Require recipients to pay for registration and every mint themselves
+        #[clap(long)]
+        require_user_payer: bool,
+
+        #[clap(flatten)]
+        metadata: Box<MetadataArgs>,
+
+        /// Keypair file of the new airdrop account. Random if omitted
+        #[clap(long)]
+        airdrop_keypair: Option<String>,
+
+        /// Authority approving every mint. Defaults to fee payer
+        #[clap(long)]
+        airdrop_authority: Option<Pubkey>,
+
+        /// Wallet receiving mint payments. Defaults to fee payer
+        #[clap(long)]
+        revenues_wallet: Option<Pubkey>,
+
+        /// Update authority of minted tokens. Defaults to fee payer
+        #[clap(long)]
+        admin: Option<Pubkey>,
+    },
+
+    /// Show airdrop config and remaining supply
+    ShowConfig { airdrop: Pubkey },
+
+    /// Show user data of a wallet. Defaults to fee payer
+    ShowUser {
+        airdrop: Pubkey,
+        user: Option<Pubkey>,
+    },
+
+    /// Register a wallet for the airdrop. Defaults to fee payer
+    RegisterUser {
+        airdrop: Pubkey,
+        user: Option<Pubkey>,
+
+        /// Keypair file of the wallet, which then signs the transaction as well
+        #[clap(long, conflicts_with = "user")]
+        user_keypair: Option<String>,
+    },
+
+    /// Mint one token to a wallet. Defaults to fee payer
+    Mint {
+        airdrop: Pubkey,
+        user: Option<Pubkey>,
+
+        /// Keypair file of the wallet, which then signs the transaction as well
+        #[clap(long, conflicts_with = "user")]
+        user_keypair: Option<String>,
+
+        /// Keypair file of the airdrop authority. Defaults to fee payer
+        #[clap(long)]
+        authority_keypair: Option<String>,
+    },
+
+    /// Check whether a wallet is able to mint right now. Defaults to fee payer
+    Quote {
+        airdrop: Pubkey,
+        user: Option<Pubkey>,
+    },
+
+    /// Replace hidden metadata of minted tokens with the final one. Fee payer must be admin
+    Reveal {
+        airdrop: Pubkey,
+
+        /// Tokens to reveal as `<airdrop index>:<mint>`
+        #[clap(required = true, parse(try_from_str = parse_token))]
+        tokens: Vec<(u64, Pubkey)>,
+    },
+
+    /// Upload token names and URIs to the config lines account before the sale.
+    /// Fee payer must be admin
+    UploadLines {
+        airdrop: Pubkey,
+
+        /// JSON file with an array of `{"name": ..., "uri": ...}` objects
+        file: String,
+
+        /// Airdrop index of the first line in the file
+        #[clap(long, default_value_t = 0)]
+        first_index: u64,
+    },
+
+    /// End the fair mint sale, remaining tokens are never minted. Fee payer must be admin
+    EndSale { airdrop: Pubkey },
+
+    /// Reveal the fair mint seed, ending the sale first unless it is ended.
+    /// Fee payer must be admin
+    RevealSeed {
+        airdrop: Pubkey,
+
+        /// Base58 seed matching the seed commitment
+        seed: Hash,
+    },
+}
+
+fn main() {
+    if let Err(error) = run(Cli::parse()) {
+        eprintln!("error: {}", error);
+        exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let cli_config = match solana_cli_config::CONFIG_FILE.as_ref() {
+        Some(config_file) => solana_cli_config::Config::load(config_file).unwrap_or_default(),
+        None => solana_cli_config::Config::default(),
+    };
+
+    let url = cli.url.unwrap_or(cli_config.json_rpc_url);
+    // Read-only commands only need the fee payer keypair when user is omitted
+    let keypair_path = cli.keypair.unwrap_or(cli_config.keypair_path);
+    let payer = || read_keypair(&keypair_path);
+    let user_or_payer = |user: Option<Pubkey>| match user {
+        Some(user) => Ok(user),
+        None => payer().map(|payer| payer.pubkey()),
+    };
+    let client = AirdropClient::new(RpcClient::new_with_commitment(
+        url,
+        CommitmentConfig::confirmed(),
+    ));
+
+    match cli.command {
+        Command::Initialize {
+            amount,
+            price,
+            shuffled,
+            pda_mints,
+            require_user_signature,
+            require_user_payer,
+            metadata,
+            airdrop_keypair,
+            airdrop_authority,
+            revenues_wallet,
+            admin,
+        } => {
+            let payer = payer()?;
+            let airdrop = match airdrop_keypair {
+                Some(path) => read_keypair(&path)?,
+                None => Keypair::new(),
+            };
+
+            let MetadataArgs {
+                base_uri,
+                uri_extension,
+                name_template,
+                index_offset,
+                index_padding,
+                hidden_name_template,
+                hidden_uri,
+                symbol,
+                provenance_hash,
+                seed_commitment,
+                config_lines,
+            } = *metadata;
+            let config_lines = match config_lines {
+                true => {
+                    let config_lines = Keypair::new();
+                    client.create_config_lines(&payer, &config_lines, amount)?;
+                    Some(config_lines.pubkey())
+                }
+                false => None,
+            };
+            let args = InitializeAirdropArgs {
+                airdrop_amount: amount,
+                price,
+                index_offset,
+                index_padding,
+                shuffled,
+                provenance_hash: provenance_hash.unwrap_or_default(),
+                seed_commitment: seed_commitment.unwrap_or_default(),
+                config_lines: config_lines.unwrap_or_default(),
+                pda_mints,
+                require_user_signature,
+                require_user_payer,
+                base_uri,
+                uri_extension,
+                name_template,
+                symbol,
+                hidden_name_template,
+                hidden_uri,
+            };
+
+            let signature = client.create_airdrop(
+                &payer,
+                &airdrop,
+                &airdrop_authority.unwrap_or_else(|| payer.pubkey()),
+                &revenues_wallet.unwrap_or_else(|| payer.pubkey()),
+                &admin.unwrap_or_else(|| payer.pubkey()),
+                args,
+            )?;
+
+            let mut view = TransactionView::new(&signature);
+            view.airdrop = Some(airdrop.pubkey().to_string());
+            view.config_lines = config_lines.map(|config_lines| config_lines.to_string());
+            print(cli.output, &view);
+        }
+        Command::ShowConfig { airdrop } => {
+            let config = client.get_airdrop_config(&airdrop)?;
+            print(cli.output, &ConfigView::new(&airdrop, &config));
+        }
+        Command::ShowUser { airdrop, user } => {
+            let user = user_or_payer(user)?;
+            let user_data = client.get_user_data(&airdrop, &user)?;
+            print(
+                cli.output,
+                &UserView::new(&airdrop, &user, user_data.as_ref()),
+            );
+        }
+        Command::RegisterUser {
+            airdrop,
+            user,
+            user_keypair,
+        } => {
+            let payer = payer()?;
+            let signature = match user_keypair {
+                Some(path) => {
+                    let user = read_keypair(&path)?;
+                    client.register_user_with_user_signer(&payer, &airdrop, &user)?
+                }
+                None => {
+                    let user = user.unwrap_or_else(|| payer.pubkey());
+                    client.register_user(&payer, &airdrop, &user)?
+                }
+            };
+            print(cli.output, &TransactionView::new(&signature));
+        }
+        Command::Mint {
+            airdrop,
+            user,
+            user_keypair,
+            authority_keypair,
+        } => {
+            let payer = payer()?;
+            let user_keypair = user_keypair.map(|path| read_keypair(&path)).transpose()?;
+            let authority = authority_keypair
+                .map(|path| read_keypair(&path))
+                .transpose()?;
+            let authority: &dyn Signer = match &authority {
+                Some(authority) => authority,
+                None => &payer,
+            };
+
+            let (signature, mint) = match &user_keypair {
+                Some(user) => {
+                    client.mint_one_with_user_signer(&payer, authority, &airdrop, user)?
+                }
+                None => {
+                    let user = user.unwrap_or_else(|| payer.pubkey());
+                    client.mint_one(&payer, authority, &airdrop, &user)?
+                }
+            };
+
+            let mut view = TransactionView::new(&signature);
+            view.mint = Some(mint.to_string());
+            print(cli.output, &view);
+        }
+        Command::Quote { airdrop, user } => {
+            let user = user_or_payer(user)?;
+            let quote = client.quote(&airdrop, &user, &user)?;
+            print(cli.output, &QuoteView::from(quote));
+        }
+        Command::Reveal { airdrop, tokens } => {
+            let payer = payer()?;
+            let signature = client.reveal(&payer, &payer, &airdrop, &tokens)?;
+            print(cli.output, &TransactionView::new(&signature));
+        }
+        Command::UploadLines {
+            airdrop,
+            file,
+            first_index,
+        } => {
+            let payer = payer()?;
+            let lines = read_config_lines(&file)?;
+            let signatures =
+                client.add_config_lines(&payer, &payer, &airdrop, first_index, &lines)?;
+            print(cli.output, &UploadView::new(lines.len(), &signatures));
+        }
+        Command::EndSale { airdrop } => {
+            let payer = payer()?;
+            let signature = client.end_sale(&payer, &payer, &airdrop)?;
+            print(cli.output, &TransactionView::new(&signature));
+        }
+        Command::RevealSeed { airdrop, seed } => {
+            let payer = payer()?;
+            let signature = client.reveal_seed(&payer, &payer, &airdrop, seed.to_bytes())?;
+            print(cli.output, &TransactionView::new(&signature));
+        }
+    }
+
+    Ok(())
+}
+
+fn read_keypair(path: &str) -> Result<Keypair, Box<dyn Error>> {
+    read_keypair_file(path)
+        .map_err(|error| format!("unable to read keypair {}: {}", path, error).into())
+}
+
+/// Config line as written in the `upload-lines` file
+#[derive(Deserialize)]
+struct ConfigLineEntry {
+    name: String,
+    uri: String,
+}
+
+fn read_config_lines(path: &str) -> Result<Vec<ConfigLine>, Box<dyn Error>> {
+    let file = File::open(path).map_err(|error| format!("unable to open {}: {}", path, error))?;
+    let entries: Vec<ConfigLineEntry> = serde_json::from_reader(file)
+        .map_err(|error| format!("unable to parse {}: {}", path, error))?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| ConfigLine {
+            name: entry.name,
+            uri: entry.uri,
+        })
+        .collect())
+}
+
+/// Parses `<airdrop index>:<mint>`
+fn parse_token(value: &str) -> Result<(u64, Pubkey), String> {
+    let (airdrop_index, mint) = value
+        .split_once(':')
+        .ok_or_else(|| format!("expected <airdrop index>:<mint>, got {}", value))?;
+
+    let airdrop_index = airdrop_index
+        .parse()
+        .map_err(|error| format!("bad airdrop index {}: {}", airdrop_index, error))?;
+    let mint = mint
+        .parse()
+        .map_err(|error| format!("bad mint {}: {:?}", mint, error))?;
+
+    Ok((airdrop_index, mint))
+}
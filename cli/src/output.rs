@@ -0,0 +1,330 @@
+use std::fmt::{self, Display};
+
+use premint_airdrop::{
+    return_data::{QuoteResult, QuoteStatus, UNKNOWN_INDEX},
+    state::{AirdropConfig, AirdropUserData},
+};
+use serde::Serialize;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OutputFormat {
+    Human,
+    Json,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(OutputFormat::Human),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("unknown output format `{}`", s)),
+        }
+    }
+}
+
+pub fn print<T: Serialize + Display>(format: OutputFormat, value: &T) {
+    match format {
+        OutputFormat::Human => println!("{}", value),
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(value).expect("output is serializable")
+        ),
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigView {
+    pub address: String,
+    pub airdrop_authority: String,
+    pub revenues_wallet: String,
+    pub admin_account: String,
+    pub base_uri: String,
+    pub uri_extension: String,
+    pub name_template: String,
+    pub symbol: String,
+    pub index_offset: u64,
+    pub index_padding: u8,
+    pub shuffled: bool,
+    pub pda_mints: bool,
+    pub require_user_signature: bool,
+    pub require_user_payer: bool,
+    pub hidden_name_template: String,
+    pub hidden_uri: String,
+    pub provenance_hash: Option<String>,
+    pub seed_commitment: Option<String>,
+    pub sale_end_slot: Option<u64>,
+    pub starting_offset: Option<u64>,
+    pub config_lines: Option<String>,
+    pub price: u64,
+    pub airdrop_amount: u64,
+    pub minted: u64,
+    pub remaining: u64,
+    pub users: u64,
+}
+
+impl ConfigView {
+    pub fn new(address: &Pubkey, config: &AirdropConfig) -> ConfigView {
+        ConfigView {
+            address: address.to_string(),
+            airdrop_authority: config.airdrop_authority.to_string(),
+            revenues_wallet: config.revenues_wallet.to_string(),
+            admin_account: config.admin_account.to_string(),
+            base_uri: config.base_uri.clone(),
+            uri_extension: config.uri_extension.clone(),
+            name_template: config.name_template.clone(),
+            symbol: config.symbol.clone(),
+            index_offset: config.index_offset,
+            index_padding: config.index_padding,
+            shuffled: config.shuffled,
+            pda_mints: config.pda_mints,
+            require_user_signature: config.require_user_signature,
+            require_user_payer: config.require_user_payer,
+            hidden_name_template: config.hidden_name_template.clone(),
+            hidden_uri: config.hidden_uri.clone(),
+            provenance_hash: Some(config.provenance_hash.to_string())
+                .filter(|_| config.is_fair_mint()),
+            seed_commitment: Some(config.seed_commitment.to_string())
+                .filter(|_| config.is_fair_mint()),
+            sale_end_slot: Some(config.sale_end_slot).filter(|_| config.is_sale_ended()),
+            starting_offset: Some(config.starting_offset).filter(|_| config.seed_revealed),
+            config_lines: Some(config.config_lines.to_string())
+                .filter(|_| config.has_config_lines()),
+            price: config.price,
+            airdrop_amount: config.airdrop_amount,
+            minted: config.airdrop_index,
+            remaining: config.airdrop_amount.saturating_sub(config.airdrop_index),
+            users: config.airdrop_users,
+        }
+    }
+}
+
+impl Display for ConfigView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Airdrop:           {}", self.address)?;
+        writeln!(f, "Airdrop authority: {}", self.airdrop_authority)?;
+        writeln!(f, "Revenues wallet:   {}", self.revenues_wallet)?;
+        writeln!(f, "Admin account:     {}", self.admin_account)?;
+        match &self.config_lines {
+            Some(config_lines) => writeln!(f, "Config lines:      {}", config_lines)?,
+            None => {
+                writeln!(f, "Base URI:          {}", self.base_uri)?;
+                writeln!(f, "URI extension:     {}", self.uri_extension)?;
+                writeln!(f, "Name template:     {}", self.name_template)?;
+            }
+        }
+        writeln!(f, "Symbol:            {}", self.symbol)?;
+        writeln!(
+            f,
+            "First number:      {} (padded to {} digits)",
+            self.index_offset, self.index_padding
+        )?;
+        writeln!(
+            f,
+            "Index assignment:  {}",
+            if self.shuffled {
+                "shuffled"
+            } else {
+                "sequential"
+            }
+        )?;
+        writeln!(
+            f,
+            "Mint addresses:    {}",
+            if self.pda_mints {
+                "derived from minted amount"
+            } else {
+                "new keypairs"
+            }
+        )?;
+        writeln!(
+            f,
+            "Recipient consent: {}",
+            match (self.require_user_signature, self.require_user_payer) {
+                (false, false) => "not required",
+                (true, false) => "signature",
+                (false, true) => "payment",
+                (true, true) => "signature and payment",
+            }
+        )?;
+        if !self.hidden_uri.is_empty() {
+            writeln!(
+                f,
+                "Hidden metadata:   {} at {}",
+                self.hidden_name_template, self.hidden_uri
+            )?;
+        }
+        if let (Some(provenance_hash), Some(seed_commitment)) =
+            (&self.provenance_hash, &self.seed_commitment)
+        {
+            writeln!(f, "Provenance hash:   {}", provenance_hash)?;
+            writeln!(f, "Seed commitment:   {}", seed_commitment)?;
+            match self.sale_end_slot {
+                Some(sale_end_slot) => writeln!(f, "Sale ended:        at slot {}", sale_end_slot)?,
+                None => writeln!(f, "Sale ended:        no")?,
+            }
+            match self.starting_offset {
+                Some(starting_offset) => writeln!(f, "Starting offset:   {}", starting_offset)?,
+                None => writeln!(f, "Starting offset:   seed not revealed")?,
+            }
+        }
+        writeln!(f, "Price:             {} lamports", self.price)?;
+        writeln!(
+            f,
+            "Minted:            {} of {}",
+            self.minted, self.airdrop_amount
+        )?;
+        writeln!(f, "Remaining:         {}", self.remaining)?;
+        write!(f, "Users:             {}", self.users)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserView {
+    pub airdrop: String,
+    pub user: String,
+    pub registered: bool,
+    pub mints_amount: u64,
+    pub locked_till: i64,
+}
+
+impl UserView {
+    pub fn new(airdrop: &Pubkey, user: &Pubkey, user_data: Option<&AirdropUserData>) -> UserView {
+        UserView {
+            airdrop: airdrop.to_string(),
+            user: user.to_string(),
+            registered: user_data.is_some(),
+            mints_amount: user_data.map_or(0, |user_data| user_data.mints_amount),
+            locked_till: user_data.map_or(0, |user_data| user_data.locked_till),
+        }
+    }
+}
+
+impl Display for UserView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Airdrop:     {}", self.airdrop)?;
+        writeln!(f, "User:        {}", self.user)?;
+        if !self.registered {
+            return write!(f, "Registered:  no");
+        }
+        writeln!(f, "Registered:  yes")?;
+        writeln!(f, "Mints:       {}", self.mints_amount)?;
+        write!(f, "Locked till: {}", self.locked_till)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteView {
+    pub eligible: bool,
+    pub status: String,
+    pub price: u64,
+    pub remaining_supply: u64,
+    /// `None` if the index is drawn at mint
+    pub next_index: Option<u64>,
+    pub locked_till: i64,
+}
+
+impl From<QuoteResult> for QuoteView {
+    fn from(quote: QuoteResult) -> QuoteView {
+        let status = match quote.status {
+            QuoteStatus::Eligible => "eligible",
+            QuoteStatus::OutOfSupply => "out of supply",
+            QuoteStatus::UserNotRegistered => "user not registered",
+            QuoteStatus::UserTimeout => "user timeout",
+            QuoteStatus::SaleEnded => "sale ended",
+            QuoteStatus::DrawNotCommitted => "draw not committed",
+            QuoteStatus::DrawNotReady => "draw not ready",
+        };
+
+        QuoteView {
+            eligible: quote.is_eligible(),
+            status: status.to_string(),
+            price: quote.price,
+            remaining_supply: quote.remaining_supply,
+            next_index: Some(quote.next_index).filter(|&index| index != UNKNOWN_INDEX),
+            locked_till: quote.locked_till,
+        }
+    }
+}
+
+impl Display for QuoteView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Status:           {}", self.status)?;
+        writeln!(f, "Price:            {} lamports", self.price)?;
+        writeln!(f, "Remaining supply: {}", self.remaining_supply)?;
+        match self.next_index {
+            Some(next_index) => writeln!(f, "Next index:       {}", next_index)?,
+            None => writeln!(f, "Next index:       drawn at mint")?,
+        }
+        write!(f, "Locked till:      {}", self.locked_till)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionView {
+    pub signature: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub airdrop: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_lines: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mint: Option<String>,
+}
+
+impl TransactionView {
+    pub fn new(signature: &Signature) -> TransactionView {
+        TransactionView {
+            signature: signature.to_string(),
+            airdrop: None,
+            config_lines: None,
+            mint: None,
+        }
+    }
+}
+
+impl Display for TransactionView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(airdrop) = &self.airdrop {
+            writeln!(f, "Airdrop:   {}", airdrop)?;
+        }
+        if let Some(config_lines) = &self.config_lines {
+            writeln!(f, "Config lines: {}", config_lines)?;
+        }
+        if let Some(mint) = &self.mint {
+            writeln!(f, "Mint:      {}", mint)?;
+        }
+        write!(f, "Signature: {}", self.signature)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadView {
+    pub lines: usize,
+    pub signatures: Vec<String>,
+}
+
+impl UploadView {
+    pub fn new(lines: usize, signatures: &[Signature]) -> UploadView {
+        UploadView {
+            lines,
+            signatures: signatures.iter().map(ToString::to_string).collect(),
+        }
+    }
+}
+
+impl Display for UploadView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Uploaded {} lines", self.lines)?;
+        for signature in &self.signatures {
+            write!(f, "\nSignature: {}", signature)?;
+        }
+        Ok(())
+    }
+}
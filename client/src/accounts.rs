@@ -0,0 +1,53 @@
+use premint_airdrop::state::{AccountType, AirdropConfig, AirdropUserData};
+use solana_client::rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType};
+use solana_sdk::{
+    account::Account, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey,
+};
+
+use crate::error::{ClientError, Result};
+
+/// Offset of the airdrop address in `AirdropUserData` account data
+pub const USER_DATA_AIRDROP_OFFSET: usize = 1;
+
+/// Offset of the user address in `AirdropUserData` account data
+pub const USER_DATA_USER_OFFSET: usize = 1 + 32;
+
+/// Decodes initialized `AirdropConfig` from account fetched over RPC
+pub fn decode_airdrop_config(address: &Pubkey, account: &Account) -> Result<AirdropConfig> {
+    assert_program_owned(address, account)?;
+    let config = AirdropConfig::unpack_from_slice(&account.data)?;
+    match config.initialized {
+        true => Ok(config),
+        false => Err(ProgramError::UninitializedAccount.into()),
+    }
+}
+
+/// Decodes initialized `AirdropUserData` from account fetched over RPC
+pub fn decode_user_data(address: &Pubkey, account: &Account) -> Result<AirdropUserData> {
+    assert_program_owned(address, account)?;
+    Ok(AirdropUserData::unpack(&account.data)?)
+}
+
+/// Program account filters that select all user data accounts of an airdrop
+pub fn user_data_filters(airdrop: &Pubkey) -> Vec<RpcFilterType> {
+    vec![
+        RpcFilterType::DataSize(AirdropUserData::LEN as u64),
+        RpcFilterType::Memcmp(Memcmp {
+            offset: 0,
+            bytes: MemcmpEncodedBytes::Bytes(vec![AccountType::AirdropUserData as u8]),
+            encoding: None,
+        }),
+        RpcFilterType::Memcmp(Memcmp {
+            offset: USER_DATA_AIRDROP_OFFSET,
+            bytes: MemcmpEncodedBytes::Bytes(airdrop.to_bytes().to_vec()),
+            encoding: None,
+        }),
+    ]
+}
+
+fn assert_program_owned(address: &Pubkey, account: &Account) -> Result<()> {
+    match account.owner == premint_airdrop::id() {
+        true => Ok(()),
+        false => Err(ClientError::WrongAccountOwner(*address)),
+    }
+}
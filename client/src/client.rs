@@ -0,0 +1,406 @@
+use std::{thread, time::Duration};
+
+use premint_airdrop::{
+    instruction::{
+        commit_draw, end_sale, initialize_airdrop_user, mint_one, mint_one_with_pda_mint, quote,
+        reveal, reveal_seed, with_config_lines, with_user_signer, InitializeAirdropArgs,
+    },
+    pda::find_airdrop_user_data,
+    return_data::QuoteResult,
+    state::{AirdropConfig, AirdropUserData, ConfigLine, ConfigLines},
+};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSimulateTransactionConfig},
+};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
+};
+
+use crate::{
+    accounts::{decode_airdrop_config, decode_user_data, user_data_filters},
+    error::{ClientError, Result},
+    instructions::{create_airdrop, create_config_lines, upload_config_lines},
+    logs::parse_return_data,
+};
+
+/// Interval of polling the current slot while waiting for the hash of a committed slot
+const SLOT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Reads airdrop accounts and sends airdrop program transactions over RPC
+pub struct AirdropClient {
+    rpc: RpcClient,
+}
+
+impl AirdropClient {
+    pub fn new(rpc: RpcClient) -> AirdropClient {
+        AirdropClient { rpc }
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    pub fn get_airdrop_config(&self, airdrop: &Pubkey) -> Result<AirdropConfig> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(airdrop, self.rpc.commitment())?
+            .value
+            .ok_or(ClientError::AccountNotFound(*airdrop))?;
+
+        decode_airdrop_config(airdrop, &account)
+    }
+
+    /// Returns `None` if user is not registered for the airdrop
+    pub fn get_user_data(
+        &self,
+        airdrop: &Pubkey,
+        user: &Pubkey,
+    ) -> Result<Option<AirdropUserData>> {
+        let (user_data, _) = find_airdrop_user_data(airdrop, user);
+
+        self.rpc
+            .get_account_with_commitment(&user_data, self.rpc.commitment())?
+            .value
+            .map(|account| decode_user_data(&user_data, &account))
+            .transpose()
+    }
+
+    /// Lists all user data accounts of the airdrop with their addresses
+    pub fn get_airdrop_users(&self, airdrop: &Pubkey) -> Result<Vec<(Pubkey, AirdropUserData)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(user_data_filters(airdrop)),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(self.rpc.commitment()),
+                ..RpcAccountInfoConfig::default()
+            },
+            with_context: None,
+        };
+
+        self.rpc
+            .get_program_accounts_with_config(&premint_airdrop::id(), config)?
+            .into_iter()
+            .map(|(address, account)| Ok((address, decode_user_data(&address, &account)?)))
+            .collect()
+    }
+
+    /// Creates and initializes airdrop config in `airdrop` account
+    pub fn create_airdrop(
+        &self,
+        payer: &dyn Signer,
+        airdrop: &dyn Signer,
+        airdrop_authority: &Pubkey,
+        revenues_wallet: &Pubkey,
+        admin_account: &Pubkey,
+        args: InitializeAirdropArgs,
+    ) -> Result<Signature> {
+        let rent_lamports = self
+            .rpc
+            .get_minimum_balance_for_rent_exemption(args.config_space())?;
+
+        let instructions = create_airdrop(
+            &payer.pubkey(),
+            &airdrop.pubkey(),
+            airdrop_authority,
+            revenues_wallet,
+            admin_account,
+            args,
+            rent_lamports,
+        )?;
+
+        self.send(&instructions, payer, &[payer, airdrop])
+    }
+
+    /// Creates config lines account for an airdrop of `airdrop_amount` tokens.
+    /// Its address is passed to `create_airdrop` as `args.config_lines`
+    pub fn create_config_lines(
+        &self,
+        payer: &dyn Signer,
+        config_lines: &dyn Signer,
+        airdrop_amount: u64,
+    ) -> Result<Signature> {
+        let rent_lamports = self
+            .rpc
+            .get_minimum_balance_for_rent_exemption(ConfigLines::space(airdrop_amount))?;
+
+        let instruction = create_config_lines(
+            &payer.pubkey(),
+            &config_lines.pubkey(),
+            airdrop_amount,
+            rent_lamports,
+        );
+
+        self.send(&[instruction], payer, &[payer, config_lines])
+    }
+
+    /// Uploads `lines` from airdrop index `first_index`, one transaction per chunk
+    pub fn add_config_lines(
+        &self,
+        payer: &dyn Signer,
+        admin: &dyn Signer,
+        airdrop: &Pubkey,
+        first_index: u64,
+        lines: &[ConfigLine],
+    ) -> Result<Vec<Signature>> {
+        let config = self.get_airdrop_config(airdrop)?;
+
+        upload_config_lines(
+            airdrop,
+            &config.config_lines,
+            &admin.pubkey(),
+            first_index,
+            lines,
+        )?
+        .into_iter()
+        .map(|instruction| self.send(&[instruction], payer, &[payer, admin]))
+        .collect()
+    }
+
+    /// Creates user data account so `user` is able to mint.
+    /// Airdrops requiring recipient consent only accept `user` equal to payer,
+    /// see `register_user_with_user_signer`
+    pub fn register_user(
+        &self,
+        payer: &dyn Signer,
+        airdrop: &Pubkey,
+        user: &Pubkey,
+    ) -> Result<Signature> {
+        let instruction = initialize_airdrop_user(airdrop, user, &payer.pubkey());
+        self.send(&[instruction], payer, &[payer])
+    }
+
+    /// Creates user data account of `user`, who signs the transaction
+    pub fn register_user_with_user_signer(
+        &self,
+        payer: &dyn Signer,
+        airdrop: &Pubkey,
+        user: &dyn Signer,
+    ) -> Result<Signature> {
+        let instruction = with_user_signer(
+            initialize_airdrop_user(airdrop, &user.pubkey(), &payer.pubkey()),
+            &user.pubkey(),
+        );
+        self.send(&[instruction], payer, &[payer, user])
+    }
+
+    /// Commits the draw of the next shuffled mint to `user` and waits until its slot is over,
+    /// so the slot hash seeding the airdrop index is available to `MintOne`
+    pub fn commit_draw(
+        &self,
+        payer: &dyn Signer,
+        airdrop_authority: &dyn Signer,
+        airdrop: &Pubkey,
+        user: &Pubkey,
+    ) -> Result<Signature> {
+        let instruction = commit_draw(airdrop, user, &airdrop_authority.pubkey());
+        let signature = self.send(&[instruction], payer, &[payer, airdrop_authority])?;
+        self.wait_for_next_slot()?;
+
+        Ok(signature)
+    }
+
+    /// Waits until the current slot is over, so its hash is in the slot hashes sysvar
+    fn wait_for_next_slot(&self) -> Result<()> {
+        let committed = self.rpc.get_slot()?;
+        while self.rpc.get_slot()? <= committed {
+            thread::sleep(SLOT_POLL_INTERVAL);
+        }
+
+        Ok(())
+    }
+
+    /// Mints one token of the airdrop to `user`. Returns transaction signature and new mint.
+    /// Shuffled airdrops commit the draw in a separate transaction first.
+    /// Airdrops requiring recipient consent only accept `user` equal to payer,
+    /// see `mint_one_with_user_signer`
+    pub fn mint_one(
+        &self,
+        payer: &dyn Signer,
+        airdrop_authority: &dyn Signer,
+        airdrop: &Pubkey,
+        user: &Pubkey,
+    ) -> Result<(Signature, Pubkey)> {
+        self.mint_one_to(payer, airdrop_authority, airdrop, user, None)
+    }
+
+    /// Mints one token of the airdrop to `user`, who signs the transaction
+    pub fn mint_one_with_user_signer(
+        &self,
+        payer: &dyn Signer,
+        airdrop_authority: &dyn Signer,
+        airdrop: &Pubkey,
+        user: &dyn Signer,
+    ) -> Result<(Signature, Pubkey)> {
+        self.mint_one_to(
+            payer,
+            airdrop_authority,
+            airdrop,
+            &user.pubkey(),
+            Some(user),
+        )
+    }
+
+    fn mint_one_to(
+        &self,
+        payer: &dyn Signer,
+        airdrop_authority: &dyn Signer,
+        airdrop: &Pubkey,
+        user: &Pubkey,
+        user_signer: Option<&dyn Signer>,
+    ) -> Result<(Signature, Pubkey)> {
+        let config = self.get_airdrop_config(airdrop)?;
+        if config.shuffled {
+            self.commit_draw(payer, airdrop_authority, airdrop, user)?;
+        }
+
+        // Mints of `pda_mints` airdrops are derived from the amount of minted tokens,
+        // so the transaction fails if another token is minted first
+        let mint = match config.pda_mints {
+            true => None,
+            false => Some(Keypair::new()),
+        };
+        let mut instruction = match &mint {
+            Some(mint) => mint_one(
+                airdrop,
+                &mint.pubkey(),
+                user,
+                &payer.pubkey(),
+                &airdrop_authority.pubkey(),
+                &config.admin_account,
+                &config.revenues_wallet,
+                config.shuffled,
+            ),
+            None => mint_one_with_pda_mint(
+                airdrop,
+                config.airdrop_index,
+                user,
+                &payer.pubkey(),
+                &airdrop_authority.pubkey(),
+                &config.admin_account,
+                &config.revenues_wallet,
+                config.shuffled,
+            ),
+        };
+        if config.has_config_lines() {
+            instruction = with_config_lines(instruction, &config.config_lines);
+        }
+        if user_signer.is_some() {
+            instruction = with_user_signer(instruction, user);
+        }
+
+        let mint_address = instruction.accounts[2].pubkey;
+        let mut signers: Vec<&dyn Signer> = vec![payer, airdrop_authority];
+        if let Some(mint) = &mint {
+            signers.push(mint);
+        }
+        signers.extend(user_signer);
+
+        let signature = self.send(&[instruction], payer, &signers)?;
+        Ok((signature, mint_address))
+    }
+
+    /// Reveals hidden metadata of `tokens`, pairs of airdrop index and mint.
+    /// All tokens are revealed in one transaction
+    pub fn reveal(
+        &self,
+        payer: &dyn Signer,
+        admin: &dyn Signer,
+        airdrop: &Pubkey,
+        tokens: &[(u64, Pubkey)],
+    ) -> Result<Signature> {
+        let instruction = reveal(airdrop, &admin.pubkey(), tokens)?;
+        self.send(&[instruction], payer, &[payer, admin])
+    }
+
+    /// Ends the fair mint sale and waits until its slot is over,
+    /// so the slot hash mixed into the starting offset is available to `RevealSeed`
+    pub fn end_sale(
+        &self,
+        payer: &dyn Signer,
+        admin: &dyn Signer,
+        airdrop: &Pubkey,
+    ) -> Result<Signature> {
+        let instruction = end_sale(airdrop, &admin.pubkey());
+        let signature = self.send(&[instruction], payer, &[payer, admin])?;
+        self.wait_for_next_slot()?;
+
+        Ok(signature)
+    }
+
+    /// Reveals the fair mint seed, fixing the metadata starting offset.
+    /// Ends the sale in a separate transaction first unless it is already ended
+    pub fn reveal_seed(
+        &self,
+        payer: &dyn Signer,
+        admin: &dyn Signer,
+        airdrop: &Pubkey,
+        seed: [u8; 32],
+    ) -> Result<Signature> {
+        if !self.get_airdrop_config(airdrop)?.is_sale_ended() {
+            self.end_sale(payer, admin, airdrop)?;
+        }
+
+        let instruction = reveal_seed(airdrop, &admin.pubkey(), seed);
+        self.send(&[instruction], payer, &[payer, admin])
+    }
+
+    /// Simulates `Quote` instruction. `fee_payer` does not sign, but must exist
+    pub fn quote(
+        &self,
+        airdrop: &Pubkey,
+        user: &Pubkey,
+        fee_payer: &Pubkey,
+    ) -> Result<QuoteResult> {
+        let instructions = [quote(airdrop, user)];
+        let transaction = Transaction::new_with_payer(&instructions, Some(fee_payer));
+
+        let simulation = self
+            .rpc
+            .simulate_transaction_with_config(
+                &transaction,
+                RpcSimulateTransactionConfig {
+                    sig_verify: false,
+                    replace_recent_blockhash: true,
+                    commitment: Some(self.rpc.commitment()),
+                    ..RpcSimulateTransactionConfig::default()
+                },
+            )?
+            .value;
+
+        let logs = simulation.logs.unwrap_or_default();
+        if let Some(error) = simulation.err {
+            return Err(ClientError::simulation(error, &instructions, &logs));
+        }
+
+        let data = parse_return_data(&logs, &premint_airdrop::id())
+            .ok_or(ClientError::MissingReturnData)?;
+
+        Ok(QuoteResult::unpack(&data)?)
+    }
+
+    /// Signs transaction with `signers`, sends it and waits for confirmation
+    pub fn send(
+        &self,
+        instructions: &[Instruction],
+        payer: &dyn Signer,
+        signers: &[&dyn Signer],
+    ) -> Result<Signature> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &signers.to_vec(),
+            blockhash,
+        );
+
+        self.rpc
+            .send_and_confirm_transaction(&transaction)
+            .map_err(|error| ClientError::transaction(error, instructions))
+    }
+}
@@ -0,0 +1,114 @@
+use num_traits::FromPrimitive;
+use premint_airdrop::error::AirdropError;
+use solana_client::{
+    client_error::{ClientError as RpcClientError, ClientErrorKind},
+    rpc_request::{RpcError, RpcResponseErrorData},
+    rpc_response::RpcSimulateTransactionResult,
+};
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    transaction::TransactionError,
+};
+use thiserror::Error;
+
+use crate::logs::failed_program;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("RPC request failed: {0}")]
+    Rpc(Box<RpcClientError>),
+
+    #[error("Unable to decode program data: {0}")]
+    Program(#[from] ProgramError),
+
+    #[error("Account {0} not found")]
+    AccountNotFound(Pubkey),
+
+    #[error("Account {0} is not owned by airdrop program")]
+    WrongAccountOwner(Pubkey),
+
+    #[error("Transaction simulation failed: {0}")]
+    Simulation(TransactionError),
+
+    #[error("Airdrop program failed: {0}")]
+    Airdrop(AirdropError),
+
+    #[error("Program did not set return data")]
+    MissingReturnData,
+}
+
+impl From<RpcClientError> for ClientError {
+    fn from(e: RpcClientError) -> Self {
+        ClientError::Rpc(Box::new(e))
+    }
+}
+
+impl ClientError {
+    /// Error of a failed transaction made of `instructions`, airdrop program errors are decoded
+    /// from preflight simulation logs
+    pub fn transaction(error: RpcClientError, instructions: &[Instruction]) -> ClientError {
+        let logs = match error.kind() {
+            ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                data:
+                    RpcResponseErrorData::SendTransactionPreflightFailure(
+                        RpcSimulateTransactionResult {
+                            logs: Some(logs), ..
+                        },
+                    ),
+                ..
+            }) => logs.as_slice(),
+            _ => &[],
+        };
+        let airdrop_error = error
+            .get_transaction_error()
+            .and_then(|error| airdrop_error(&error, instructions, logs));
+        match airdrop_error {
+            Some(error) => ClientError::Airdrop(error),
+            None => ClientError::Rpc(Box::new(error)),
+        }
+    }
+
+    /// Error of a failed simulation of `instructions` with `logs`,
+    /// airdrop program errors are decoded
+    pub fn simulation(
+        error: TransactionError,
+        instructions: &[Instruction],
+        logs: &[String],
+    ) -> ClientError {
+        match airdrop_error(&error, instructions, logs) {
+            Some(error) => ClientError::Airdrop(error),
+            None => ClientError::Simulation(error),
+        }
+    }
+}
+
+/// Airdrop program error of a failed transaction made of `instructions` with `logs`.
+/// Custom codes are decoded only when the failed instruction belongs to the airdrop program
+/// and `logs` show it is the airdrop program that failed, not a program it invokes.
+/// Other codes are left to `TransactionError`
+pub fn airdrop_error(
+    error: &TransactionError,
+    instructions: &[Instruction],
+    logs: &[String],
+) -> Option<AirdropError> {
+    match error {
+        TransactionError::InstructionError(index, InstructionError::Custom(code))
+            if instructions
+                .get(*index as usize)
+                .is_some_and(|instruction| instruction.program_id == premint_airdrop::id())
+                && failed_program(logs) == Some(premint_airdrop::id()) =>
+        {
+            AirdropError::from_u32(*code)
+        }
+        _ => None,
+    }
+}
+
+/// Message of airdrop program error code, as printed in program logs
+pub fn error_message(code: u32) -> Option<String> {
+    AirdropError::from_u32(code).map(|error| error.to_string())
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
@@ -0,0 +1,108 @@
+use premint_airdrop::{
+    instruction::{add_config_lines, initialize_airdrop, InitializeAirdropArgs},
+    state::{ConfigLine, ConfigLines},
+};
+use solana_sdk::{
+    instruction::Instruction, program_error::ProgramError, pubkey::Pubkey, system_instruction,
+};
+
+/// Instruction data budget of one `AddConfigLines` instruction, so that the transaction
+/// with admin and fee payer signatures fits the packet size
+pub const CONFIG_LINES_CHUNK_LEN: usize = 800;
+
+/// Instructions that create airdrop config account and initialize it.
+/// `rent_lamports` is the rent exempt minimum for `args.config_space()` bytes.
+/// Fails on metadata strings longer than 255 bytes
+pub fn create_airdrop(
+    payer: &Pubkey,
+    airdrop: &Pubkey,
+    airdrop_authority: &Pubkey,
+    revenues_wallet: &Pubkey,
+    admin_account: &Pubkey,
+    args: InitializeAirdropArgs,
+    rent_lamports: u64,
+) -> Result<Vec<Instruction>, ProgramError> {
+    Ok(vec![
+        system_instruction::create_account(
+            payer,
+            airdrop,
+            rent_lamports,
+            args.config_space() as u64,
+            &premint_airdrop::id(),
+        ),
+        initialize_airdrop(
+            airdrop,
+            airdrop_authority,
+            revenues_wallet,
+            admin_account,
+            payer,
+            args,
+        )?,
+    ])
+}
+
+/// Instruction that creates the config lines account of an airdrop of `airdrop_amount` tokens.
+/// `rent_lamports` is the rent exempt minimum for `ConfigLines::space(airdrop_amount)` bytes
+pub fn create_config_lines(
+    payer: &Pubkey,
+    config_lines: &Pubkey,
+    airdrop_amount: u64,
+    rent_lamports: u64,
+) -> Instruction {
+    system_instruction::create_account(
+        payer,
+        config_lines,
+        rent_lamports,
+        ConfigLines::space(airdrop_amount) as u64,
+        &premint_airdrop::id(),
+    )
+}
+
+/// `AddConfigLines` instructions uploading `lines` from airdrop index `first_index`,
+/// each small enough to be sent in its own transaction. Fails on names and URIs
+/// longer than 255 bytes
+pub fn upload_config_lines(
+    airdrop: &Pubkey,
+    config_lines: &Pubkey,
+    admin_account: &Pubkey,
+    first_index: u64,
+    lines: &[ConfigLine],
+) -> Result<Vec<Instruction>, ProgramError> {
+    let mut instructions = vec![];
+    let mut chunk: Vec<ConfigLine> = vec![];
+    let mut chunk_start = first_index;
+    let mut chunk_len = 0;
+
+    for line in lines {
+        let line_len = 2 + line.name.len() + line.uri.len();
+        if !chunk.is_empty()
+            && (chunk_len + line_len > CONFIG_LINES_CHUNK_LEN || chunk.len() == u8::MAX as usize)
+        {
+            let count = chunk.len() as u64;
+            instructions.push(add_config_lines(
+                airdrop,
+                config_lines,
+                admin_account,
+                chunk_start,
+                std::mem::take(&mut chunk),
+            )?);
+            chunk_start += count;
+            chunk_len = 0;
+        }
+
+        chunk.push(line.clone());
+        chunk_len += line_len;
+    }
+
+    if !chunk.is_empty() {
+        instructions.push(add_config_lines(
+            airdrop,
+            config_lines,
+            admin_account,
+            chunk_start,
+            chunk,
+        )?);
+    }
+
+    Ok(instructions)
+}
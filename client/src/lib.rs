@@ -0,0 +1,7 @@
+pub mod accounts;
+pub mod client;
+pub mod error;
+pub mod instructions;
+pub mod logs;
+
+pub use client::AirdropClient;
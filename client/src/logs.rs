@@ -0,0 +1,77 @@
+use premint_airdrop::event::AirdropEvent;
+use solana_sdk::{program_error::ProgramError, pubkey::Pubkey};
+
+const PROGRAM_DATA: &str = "Program data: ";
+const PROGRAM_RETURN: &str = "Program return: ";
+
+/// Decodes events emitted by the airdrop program from transaction logs.
+/// Data logged by other programs, including ones invoked by the airdrop program, is skipped
+pub fn parse_events(logs: &[String]) -> Result<Vec<AirdropEvent>, ProgramError> {
+    let program_id = premint_airdrop::id().to_string();
+    let mut invoke_stack: Vec<&str> = vec![];
+    let mut events = vec![];
+
+    for log in logs {
+        if let Some(data) = log.strip_prefix(PROGRAM_DATA) {
+            if invoke_stack.last() == Some(&program_id.as_str()) {
+                for field in data.split(' ') {
+                    let bytes = base64::decode(field).or(Err(ProgramError::InvalidArgument))?;
+                    events.push(AirdropEvent::unpack(&bytes)?);
+                }
+            }
+        } else if let Some(program) = invoked_program(log) {
+            invoke_stack.push(program);
+        } else if is_program_exit(log) {
+            invoke_stack.pop();
+        }
+    }
+
+    Ok(events)
+}
+
+/// Finds the last return data set by `program_id` in transaction logs
+pub fn parse_return_data(logs: &[String], program_id: &Pubkey) -> Option<Vec<u8>> {
+    let program_id = program_id.to_string();
+
+    logs.iter().rev().find_map(|log| {
+        let (program, data) = log.strip_prefix(PROGRAM_RETURN)?.split_once(' ')?;
+
+        match program == program_id {
+            true => base64::decode(data).ok(),
+            false => None,
+        }
+    })
+}
+
+/// Program whose own instruction failed: the first one logging `Program <id> failed`.
+/// Programs invoking it fail after it with the same error
+pub fn failed_program(logs: &[String]) -> Option<Pubkey> {
+    logs.iter().find_map(|log| {
+        let mut parts = log.split(' ');
+
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some("Program"), Some(program), Some("failed:")) => program.parse().ok(),
+            _ => None,
+        }
+    })
+}
+
+/// `Program <id> invoke [<depth>]`
+fn invoked_program(log: &str) -> Option<&str> {
+    let mut parts = log.split(' ');
+
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some("Program"), Some(program), Some("invoke")) => Some(program),
+        _ => None,
+    }
+}
+
+/// `Program <id> success` or `Program <id> failed: <error>`
+fn is_program_exit(log: &str) -> bool {
+    let mut parts = log.split(' ');
+
+    matches!(
+        (parts.next(), parts.next(), parts.next()),
+        (Some("Program"), Some(_), Some("success")) | (Some("Program"), Some(_), Some("failed:"))
+    )
+}
@@ -0,0 +1,108 @@
+use premint_airdrop::{error::AirdropError, instruction::quote};
+use premint_airdrop_client::error::{airdrop_error, error_message, ClientError};
+use solana_sdk::{
+    instruction::InstructionError, pubkey::Pubkey, system_instruction,
+    transaction::TransactionError,
+};
+
+/// Logs of the airdrop program failing with `code`, after `inner` logs of invoked programs
+fn failure_logs(code: u32, inner: &[String]) -> Vec<String> {
+    let program_id = premint_airdrop::id();
+    let mut logs = vec![format!("Program {} invoke [1]", program_id)];
+    logs.extend_from_slice(inner);
+    logs.push(format!(
+        "Program {} failed: custom program error: {:#x}",
+        program_id, code
+    ));
+    logs
+}
+
+#[test]
+fn error_message_maps_program_codes() {
+    let code = AirdropError::UserTimeout as u32;
+    assert_eq!(
+        error_message(code).unwrap(),
+        AirdropError::UserTimeout.to_string()
+    );
+    assert!(error_message(u32::MAX).is_none());
+}
+
+#[test]
+fn simulation_errors_decode_program_codes() {
+    let instructions = [quote(&Pubkey::new_unique(), &Pubkey::new_unique())];
+    let code = AirdropError::OutOfSupply as u32;
+    let logs = failure_logs(code, &[]);
+    let error = TransactionError::InstructionError(0, InstructionError::Custom(code));
+
+    assert!(matches!(
+        airdrop_error(&error, &instructions, &logs),
+        Some(AirdropError::OutOfSupply)
+    ));
+    assert!(matches!(
+        ClientError::simulation(error, &instructions, &logs),
+        ClientError::Airdrop(AirdropError::OutOfSupply)
+    ));
+
+    let error = TransactionError::InstructionError(0, InstructionError::InvalidAccountData);
+    assert!(matches!(
+        ClientError::simulation(error, &instructions, &logs),
+        ClientError::Simulation(_)
+    ));
+}
+
+#[test]
+fn custom_codes_of_other_programs_are_not_decoded() {
+    let instructions = [
+        system_instruction::transfer(&Pubkey::new_unique(), &Pubkey::new_unique(), 1),
+        quote(&Pubkey::new_unique(), &Pubkey::new_unique()),
+    ];
+    let code = AirdropError::OutOfSupply as u32;
+    let logs = failure_logs(code, &[]);
+
+    let error = TransactionError::InstructionError(0, InstructionError::Custom(code));
+    assert!(airdrop_error(&error, &instructions, &logs).is_none());
+    assert!(matches!(
+        ClientError::simulation(error, &instructions, &logs),
+        ClientError::Simulation(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(c)
+        )) if c == code
+    ));
+
+    let error = TransactionError::InstructionError(1, InstructionError::Custom(code));
+    assert!(matches!(
+        airdrop_error(&error, &instructions, &logs),
+        Some(AirdropError::OutOfSupply)
+    ));
+    // Failed program is unknown without logs
+    assert!(airdrop_error(&error, &instructions, &[]).is_none());
+
+    let error = TransactionError::InstructionError(2, InstructionError::Custom(code));
+    assert!(airdrop_error(&error, &instructions, &logs).is_none());
+}
+
+#[test]
+fn custom_codes_of_invoked_programs_are_not_decoded() {
+    let instructions = [quote(&Pubkey::new_unique(), &Pubkey::new_unique())];
+    // Token program `InsufficientFunds` returned through a CPI of the airdrop program
+    let code = 1;
+    let token_program = spl_token::id();
+    let logs = failure_logs(
+        code,
+        &[
+            format!("Program {} invoke [2]", token_program),
+            "Program log: Error: insufficient funds".to_string(),
+            format!(
+                "Program {} failed: custom program error: {:#x}",
+                token_program, code
+            ),
+        ],
+    );
+    let error = TransactionError::InstructionError(0, InstructionError::Custom(code));
+
+    assert!(airdrop_error(&error, &instructions, &logs).is_none());
+    assert!(matches!(
+        ClientError::simulation(error, &instructions, &logs),
+        ClientError::Simulation(_)
+    ));
+}
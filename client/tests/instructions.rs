@@ -0,0 +1,86 @@
+use premint_airdrop::{
+    instruction::{deserialize_instruction_data, AddConfigLinesArgs, AirdropInstruction},
+    state::ConfigLine,
+};
+use premint_airdrop_client::instructions::{upload_config_lines, CONFIG_LINES_CHUNK_LEN};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+fn lines(count: u64, uri_len: usize) -> Vec<ConfigLine> {
+    (0..count)
+        .map(|index| ConfigLine {
+            name: format!("Test #{}", index),
+            uri: "u".repeat(uri_len),
+        })
+        .collect()
+}
+
+fn args(instruction: &Instruction) -> AddConfigLinesArgs {
+    match deserialize_instruction_data(&instruction.data).unwrap() {
+        AirdropInstruction::AddConfigLines(args) => args,
+        _ => panic!("expected AddConfigLines"),
+    }
+}
+
+/// Uploads `lines` from index 5 and checks that chunks cover them in order
+fn check_chunks(lines: &[ConfigLine]) -> Vec<AddConfigLinesArgs> {
+    let (airdrop, config_lines, admin) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let chunks: Vec<AddConfigLinesArgs> =
+        upload_config_lines(&airdrop, &config_lines, &admin, 5, lines)
+            .unwrap()
+            .iter()
+            .map(args)
+            .collect();
+
+    let mut next_index = 5;
+    for chunk in &chunks {
+        assert_eq!(chunk.first_index, next_index);
+        assert!(!chunk.lines.is_empty() && chunk.lines.len() <= u8::MAX as usize);
+        next_index += chunk.lines.len() as u64;
+    }
+    let uploaded: Vec<ConfigLine> = chunks
+        .iter()
+        .flat_map(|chunk| chunk.lines.clone())
+        .collect();
+    assert_eq!(uploaded, lines);
+
+    chunks
+}
+
+#[test]
+fn upload_config_lines_splits_by_size() {
+    let chunks = check_chunks(&lines(20, 200));
+
+    assert!(chunks.len() > 1);
+    for chunk in &chunks {
+        let len: usize = chunk
+            .lines
+            .iter()
+            .map(|line| 2 + line.name.len() + line.uri.len())
+            .sum();
+        assert!(len <= CONFIG_LINES_CHUNK_LEN);
+    }
+}
+
+#[test]
+fn upload_config_lines_splits_by_count() {
+    // Three bytes per line, so the count limit is reached before the size one
+    let lines: Vec<ConfigLine> = (0..300)
+        .map(|_| ConfigLine {
+            name: "n".to_string(),
+            uri: String::new(),
+        })
+        .collect();
+    let chunks = check_chunks(&lines);
+
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].lines.len(), u8::MAX as usize);
+}
+
+#[test]
+fn upload_config_lines_skips_empty_input() {
+    assert!(check_chunks(&[]).is_empty());
+}
@@ -0,0 +1,86 @@
+use premint_airdrop::{
+    event::{AirdropEvent, MintedEvent, UserRegisteredEvent},
+    return_data::MintOneResult,
+};
+use premint_airdrop_client::logs::{parse_events, parse_return_data};
+use solana_sdk::pubkey::Pubkey;
+
+fn minted_event() -> AirdropEvent {
+    AirdropEvent::Minted(MintedEvent {
+        airdrop: Pubkey::new_unique(),
+        recipient: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        payer: Pubkey::new_unique(),
+        airdrop_index: 7,
+        price: 1_000_000,
+        timestamp: 1_650_000_000,
+    })
+}
+
+#[test]
+fn parse_events_skips_data_of_other_programs() {
+    let program_id = premint_airdrop::id();
+    let event = minted_event();
+    let foreign_event = AirdropEvent::UserRegistered(UserRegisteredEvent {
+        airdrop: Pubkey::new_unique(),
+        user: Pubkey::new_unique(),
+        timestamp: 0,
+    });
+
+    let logs = vec![
+        format!("Program {} invoke [1]", program_id),
+        "Program log: Initialize metadata".to_string(),
+        "Program metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s invoke [2]".to_string(),
+        format!("Program data: {}", base64::encode(foreign_event.pack())),
+        "Program metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s consumed 100 of 200000 compute units"
+            .to_string(),
+        "Program metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s success".to_string(),
+        format!("Program data: {}", base64::encode(event.pack())),
+        format!("Program {} success", program_id),
+    ];
+
+    assert_eq!(parse_events(&logs).unwrap(), vec![event]);
+}
+
+#[test]
+fn parse_events_rejects_unknown_version() {
+    let mut data = minted_event().pack();
+    data[0] += 1;
+
+    let logs = vec![
+        format!("Program {} invoke [1]", premint_airdrop::id()),
+        format!("Program data: {}", base64::encode(data)),
+        format!("Program {} success", premint_airdrop::id()),
+    ];
+
+    assert!(parse_events(&logs).is_err());
+}
+
+#[test]
+fn parse_return_data_matches_program() {
+    let program_id = premint_airdrop::id();
+    let result = MintOneResult {
+        airdrop_index: 3,
+        mint: Pubkey::new_unique(),
+        price: 5,
+    };
+
+    let logs = vec![
+        format!("Program {} invoke [1]", program_id),
+        format!(
+            "Program return: {} {}",
+            spl_token::id(),
+            base64::encode([1, 2, 3])
+        ),
+        format!(
+            "Program return: {} {}",
+            program_id,
+            base64::encode(result.pack())
+        ),
+        format!("Program {} success", program_id),
+    ];
+
+    let data = parse_return_data(&logs, &program_id).unwrap();
+    assert_eq!(MintOneResult::unpack(&data).unwrap(), result);
+    assert!(parse_return_data(&logs[..1], &program_id).is_none());
+}
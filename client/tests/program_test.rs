@@ -0,0 +1,257 @@
+use premint_airdrop::{
+    instruction::InitializeAirdropArgs, pda::find_airdrop_user_data, state::AirdropUserData,
+};
+use premint_airdrop_client::{
+    accounts::{decode_airdrop_config, decode_user_data, user_data_filters},
+    instructions::create_airdrop,
+};
+use solana_client::rpc_filter::RpcFilterType;
+use solana_program_test::{processor, BanksClient, ProgramTest};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    hash::Hash,
+    instruction::Instruction,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+#[cfg(feature = "test-bpf")]
+use {
+    premint_airdrop::instruction::{initialize_airdrop_user, mint_one},
+    spl_associated_token_account::get_associated_token_address,
+};
+
+const PRICE: u64 = 1_000_000;
+
+struct Env {
+    banks_client: BanksClient,
+    payer: Keypair,
+    blockhash: Hash,
+    airdrop: Keypair,
+    airdrop_authority: Keypair,
+    admin: Pubkey,
+    revenues_wallet: Pubkey,
+}
+
+impl Env {
+    async fn start() -> Env {
+        Env::start_with_accounts(vec![]).await
+    }
+
+    async fn start_with_accounts(accounts: Vec<(Pubkey, Account)>) -> Env {
+        let mut program_test = ProgramTest::new(
+            "premint_airdrop",
+            premint_airdrop::id(),
+            processor!(premint_airdrop::processor::process_instruction),
+        );
+        #[cfg(feature = "test-bpf")]
+        program_test.add_program(
+            "metaplex_token_metadata",
+            metaplex_token_metadata::id(),
+            None,
+        );
+        for (address, account) in accounts {
+            program_test.add_account(address, account);
+        }
+
+        let (mut banks_client, payer, blockhash) = program_test.start().await;
+
+        let airdrop = Keypair::new();
+        let airdrop_authority = Keypair::new();
+        let admin = Pubkey::new_unique();
+        let revenues_wallet = Pubkey::new_unique();
+
+        let rent = banks_client.get_rent().await.unwrap();
+        let args = InitializeAirdropArgs {
+            airdrop_amount: 10,
+            price: PRICE,
+            index_offset: 0,
+            index_padding: 0,
+            shuffled: false,
+            provenance_hash: Hash::default(),
+            seed_commitment: Hash::default(),
+            config_lines: Pubkey::default(),
+            pda_mints: false,
+            require_user_signature: false,
+            require_user_payer: false,
+            base_uri: "https://example.com/".to_string(),
+            uri_extension: ".json".to_string(),
+            name_template: "Test #{}".to_string(),
+            symbol: "TEST".to_string(),
+            hidden_name_template: String::new(),
+            hidden_uri: String::new(),
+        };
+        let rent_lamports = rent.minimum_balance(args.config_space());
+
+        let instructions = create_airdrop(
+            &payer.pubkey(),
+            &airdrop.pubkey(),
+            &airdrop_authority.pubkey(),
+            &revenues_wallet,
+            &admin,
+            args,
+            rent_lamports,
+        )
+        .unwrap();
+
+        let mut env = Env {
+            banks_client,
+            payer,
+            blockhash,
+            airdrop,
+            airdrop_authority,
+            admin,
+            revenues_wallet,
+        };
+
+        let airdrop = Keypair::from_bytes(&env.airdrop.to_bytes()).unwrap();
+        env.send(&instructions, &[&airdrop]).await;
+        env
+    }
+
+    async fn send(&mut self, instructions: &[Instruction], signers: &[&Keypair]) {
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            self.blockhash,
+        );
+
+        self.banks_client
+            .process_transaction(transaction)
+            .await
+            .unwrap();
+    }
+
+    async fn account(&mut self, address: &Pubkey) -> Account {
+        self.banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+}
+
+#[tokio::test]
+async fn create_airdrop_decodes_config() {
+    let mut env = Env::start().await;
+
+    let airdrop = env.airdrop.pubkey();
+    let account = env.account(&airdrop).await;
+    let config = decode_airdrop_config(&airdrop, &account).unwrap();
+
+    assert_eq!(config.airdrop_authority, env.airdrop_authority.pubkey());
+    assert_eq!(config.admin_account, env.admin);
+    assert_eq!(config.revenues_wallet, env.revenues_wallet);
+    assert_eq!(config.airdrop_amount, 10);
+    assert_eq!(config.airdrop_index, 0);
+    assert_eq!(config.price, PRICE);
+    assert!(decode_user_data(&airdrop, &account).is_err());
+}
+
+#[tokio::test]
+async fn user_data_filters_select_users_of_airdrop() {
+    let airdrop = Pubkey::new_unique();
+    let other_airdrop = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+
+    let user_data_account = |airdrop: &Pubkey| {
+        let mut data = vec![0; AirdropUserData::LEN];
+        AirdropUserData::pack(
+            AirdropUserData {
+                initialized: true,
+                airdrop: *airdrop,
+                user,
+                mints_amount: 2,
+                locked_till: 0,
+                draw_slot: 0,
+            },
+            &mut data,
+        )
+        .unwrap();
+
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: premint_airdrop::id(),
+            ..Account::default()
+        }
+    };
+
+    let (user_data, _) = find_airdrop_user_data(&airdrop, &user);
+    let (other_user_data, _) = find_airdrop_user_data(&other_airdrop, &user);
+    let mut env = Env::start_with_accounts(vec![
+        (user_data, user_data_account(&airdrop)),
+        (other_user_data, user_data_account(&other_airdrop)),
+    ])
+    .await;
+
+    let account = env.account(&user_data).await;
+    let decoded = decode_user_data(&user_data, &account).unwrap();
+    assert_eq!(decoded.airdrop, airdrop);
+    assert_eq!(decoded.user, user);
+    assert_eq!(decoded.mints_amount, 2);
+
+    let matches = |filters: &[RpcFilterType], account: Account| {
+        let account = AccountSharedData::from(account);
+        filters.iter().all(|filter| filter.allows(&account))
+    };
+
+    let filters = user_data_filters(&airdrop);
+    assert!(matches(&filters, account));
+    assert!(!matches(&filters, env.account(&other_user_data).await));
+
+    let config_address = env.airdrop.pubkey();
+    assert!(!matches(&filters, env.account(&config_address).await));
+}
+
+// Native processors are unable to create accounts through CPI, so flows that create user data
+// and mint accounts need the BPF build of the program. Run them with `cargo test-bpf` after
+// dumping the Token Metadata program into `tests/fixtures/metaplex_token_metadata.so`
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn mint_one_updates_counters() {
+    let mut env = Env::start().await;
+
+    let airdrop = env.airdrop.pubkey();
+    let user = Pubkey::new_unique();
+    let instruction = initialize_airdrop_user(&airdrop, &user, &env.payer.pubkey());
+    env.send(&[instruction], &[]).await;
+
+    let mint = Keypair::new();
+    let airdrop_authority = Keypair::from_bytes(&env.airdrop_authority.to_bytes()).unwrap();
+    let instruction = mint_one(
+        &airdrop,
+        &mint.pubkey(),
+        &user,
+        &env.payer.pubkey(),
+        &airdrop_authority.pubkey(),
+        &env.admin,
+        &env.revenues_wallet,
+        false,
+    );
+    env.send(&[instruction], &[&mint, &airdrop_authority]).await;
+
+    let config = decode_airdrop_config(&airdrop, &env.account(&airdrop).await).unwrap();
+    assert_eq!(config.airdrop_index, 1);
+    assert_eq!(config.airdrop_users, 1);
+
+    let (user_data, _) = find_airdrop_user_data(&airdrop, &user);
+    let user_data = decode_user_data(&user_data, &env.account(&user_data).await).unwrap();
+    assert_eq!(user_data.mints_amount, 1);
+
+    let token_account = get_associated_token_address(&user, &mint.pubkey());
+    let token_account =
+        spl_token::state::Account::unpack(&env.account(&token_account).await.data).unwrap();
+    assert_eq!(token_account.amount, 1);
+    assert_eq!(token_account.owner, user);
+
+    assert_eq!(
+        env.account(&env.revenues_wallet.clone()).await.lamports,
+        PRICE
+    );
+}
@@ -0,0 +1,64 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use premint_airdrop::{
+    error::AirdropError,
+    state::{AirdropConfig, AirdropUserData},
+};
+use solana_program::{program_error::ProgramError, program_pack::Pack};
+
+/// Checks `unpack_from_slice` result against the documented errors.
+/// Valid initialized config must pack back to the same bytes
+fn check_config(data: &[u8]) {
+    let result = AirdropConfig::unpack_from_slice(data);
+
+    // Account type is checked before data length
+    match data.first() {
+        Some(2 | 3) => assert_eq!(result.err(), Some(AirdropError::NotAirdropConfig.into())),
+        Some(0 | 1) if data.len() < AirdropConfig::HEADER_LEN => {
+            assert_eq!(result.err(), Some(ProgramError::InvalidAccountData))
+        }
+        Some(0) => assert!(!result.unwrap().initialized),
+        Some(1) => match result {
+            // Index map is not packed
+            Ok(config) => {
+                let mut packed = vec![0; config.data_len()];
+                config.pack_into_slice(&mut packed).unwrap();
+                let len = packed.len() - config.index_map(&mut packed).unwrap().len();
+                assert_eq!(packed[..len], data[..len]);
+            }
+            Err(error) => assert_eq!(error, ProgramError::InvalidAccountData),
+        },
+        _ => assert_eq!(result.err(), Some(ProgramError::InvalidAccountData)),
+    }
+}
+
+/// Checks `unpack_unchecked` result against the documented errors.
+/// Valid data must pack back to the same bytes
+fn check<T: Pack>(data: &[u8], account_type: u8, wrong_type: AirdropError) {
+    let result = T::unpack_unchecked(data);
+
+    // Account type is checked before data length
+    match data.first() {
+        Some(&byte) if byte != account_type && (1..=3).contains(&byte) => {
+            assert_eq!(result.err(), Some(wrong_type.into()))
+        }
+        Some(&byte) if (byte == 0 || byte == account_type) && data.len() != T::LEN => {
+            assert_eq!(result.err(), Some(ProgramError::InvalidAccountData))
+        }
+        Some(0) => {
+            result.unwrap();
+        }
+        Some(&byte) if byte == account_type => {
+            let mut packed = vec![0; T::LEN];
+            T::pack_into_slice(&result.unwrap(), &mut packed);
+            assert_eq!(packed, data);
+        }
+        _ => assert_eq!(result.err(), Some(ProgramError::InvalidAccountData)),
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    check_config(data);
+    check::<AirdropUserData>(data, 2, AirdropError::NotAirdropUserData);
+});
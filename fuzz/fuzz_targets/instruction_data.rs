@@ -0,0 +1,45 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use premint_airdrop::{error::AirdropError, instruction::deserialize_instruction_data};
+use solana_program::program_error::ProgramError;
+
+fuzz_target!(|data: &[u8]| {
+    let result = deserialize_instruction_data(data);
+
+    match data.split_first() {
+        None => assert_eq!(result, Err(ProgramError::InvalidInstructionData)),
+        Some((1, body)) if body.len() < 125 => {
+            assert_eq!(result, Err(AirdropError::BadInstructionArgument.into()))
+        }
+        // Valid arguments must pack back to the same bytes
+        Some((1, _)) => match result {
+            Ok(instruction) => assert_eq!(instruction.pack().unwrap(), data),
+            Err(error) => assert!(
+                error == AirdropError::BadInstructionArgument.into()
+                    || error == AirdropError::BadMetadataString.into()
+            ),
+        },
+        // Arguments of other instructions are empty, trailing bytes are ignored
+        Some((1..=4 | 8 | 9, _)) => assert_eq!(result.unwrap().pack().unwrap()[0], data[0]),
+        // Reveal takes a non-empty list of indices prefixed with its length
+        Some((5, body)) => match body.split_first() {
+            Some((&count, indices)) if count > 0 && indices.len() == count as usize * 8 => {
+                assert_eq!(result.unwrap().pack().unwrap(), data)
+            }
+            _ => assert_eq!(result, Err(AirdropError::BadInstructionArgument.into())),
+        },
+        // RevealSeed takes exactly the seed
+        Some((6, body)) if body.len() == 32 => assert_eq!(result.unwrap().pack().unwrap(), data),
+        Some((6, _)) => assert_eq!(result, Err(AirdropError::BadInstructionArgument.into())),
+        // AddConfigLines takes the first index and a non-empty list of name and uri pairs
+        Some((7, _)) => match result {
+            Ok(instruction) => assert_eq!(instruction.pack().unwrap(), data),
+            Err(error) => assert!(
+                error == AirdropError::BadInstructionArgument.into()
+                    || error == AirdropError::BadMetadataString.into()
+            ),
+        },
+        Some(_) => assert_eq!(result, Err(AirdropError::BadInstructionId.into())),
+    }
+});
@@ -0,0 +1,29 @@
+use solana_client::client_error::ClientError;
+use solana_sdk::program_error::ProgramError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum IndexerError {
+    #[error("Database error: {0}")]
+    Db(#[from] sled::Error),
+
+    #[error("Unable to decode stored record: {0}")]
+    Record(#[from] bincode::Error),
+
+    #[error("Unable to decode event in transaction {signature}: {error}")]
+    Event {
+        signature: String,
+        error: ProgramError,
+    },
+
+    #[error("RPC request failed: {0}")]
+    Rpc(Box<ClientError>),
+}
+
+impl From<ClientError> for IndexerError {
+    fn from(e: ClientError) -> Self {
+        IndexerError::Rpc(Box::new(e))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, IndexerError>;
@@ -0,0 +1,105 @@
+use premint_airdrop::event::AirdropEvent;
+use premint_airdrop_client::logs::parse_events;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{IndexerError, Result},
+    store::{AirdropRecord, MintRecord, RevealRecord, SeedRecord, Store, UserRecord},
+};
+
+/// Transaction as recorded from RPC or a log dump
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub failed: bool,
+    pub logs: Vec<String>,
+}
+
+/// Rebuilds airdrop history from program events
+pub struct Indexer {
+    store: Store,
+}
+
+impl Indexer {
+    pub fn new(store: Store) -> Indexer {
+        Indexer { store }
+    }
+
+    pub fn store(&self) -> &Store {
+        &self.store
+    }
+
+    /// Applies events of the transaction to the store. Returns amount of applied events.
+    /// Failed transactions are skipped, their events were rolled back
+    pub fn ingest(&self, transaction: &RecordedTransaction) -> Result<usize> {
+        if transaction.failed {
+            return Ok(0);
+        }
+
+        let events = parse_events(&transaction.logs).map_err(|error| IndexerError::Event {
+            signature: transaction.signature.clone(),
+            error,
+        })?;
+
+        for event in &events {
+            self.apply(event, transaction)?;
+        }
+
+        Ok(events.len())
+    }
+
+    fn apply(&self, event: &AirdropEvent, transaction: &RecordedTransaction) -> Result<()> {
+        let signature = transaction.signature.clone();
+        let slot = transaction.slot;
+
+        match event {
+            AirdropEvent::AirdropInitialized(event) => self.store.put_airdrop(&AirdropRecord {
+                airdrop: event.airdrop,
+                airdrop_authority: event.airdrop_authority,
+                revenues_wallet: event.revenues_wallet,
+                admin_account: event.admin_account,
+                airdrop_amount: event.airdrop_amount,
+                price: event.price,
+                slot,
+                timestamp: event.timestamp,
+                signature,
+            }),
+            AirdropEvent::UserRegistered(event) => self.store.put_user(&UserRecord {
+                airdrop: event.airdrop,
+                user: event.user,
+                slot,
+                timestamp: event.timestamp,
+                signature,
+            }),
+            AirdropEvent::Minted(event) => self.store.put_mint(&MintRecord {
+                airdrop: event.airdrop,
+                airdrop_index: event.airdrop_index,
+                mint: event.mint,
+                recipient: event.recipient,
+                payer: event.payer,
+                price: event.price,
+                slot,
+                timestamp: event.timestamp,
+                signature,
+            }),
+            AirdropEvent::Revealed(event) => self.store.put_reveal(&RevealRecord {
+                airdrop: event.airdrop,
+                airdrop_index: event.airdrop_index,
+                mint: event.mint,
+                slot,
+                timestamp: event.timestamp,
+                signature,
+            }),
+            AirdropEvent::SeedRevealed(event) => self.store.put_seed(&SeedRecord {
+                airdrop: event.airdrop,
+                seed: event.seed,
+                slot_hash: event.slot_hash,
+                starting_offset: event.starting_offset,
+                slot,
+                timestamp: event.timestamp,
+                signature,
+            }),
+        }
+    }
+}
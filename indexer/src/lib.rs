@@ -0,0 +1,6 @@
+pub mod error;
+pub mod indexer;
+pub mod source;
+pub mod store;
+
+pub use crate::{indexer::Indexer, store::Store};
@@ -0,0 +1,165 @@
+use std::{error::Error, process::exit, thread::sleep, time::Duration};
+
+use clap::{Parser, Subcommand};
+use premint_airdrop_indexer::{
+    source::RpcSource,
+    store::{MintRecord, RevealRecord, UserRecord},
+    Indexer, Store,
+};
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{commitment_config::CommitmentConfig, hash::Hash, pubkey::Pubkey};
+
+#[derive(Parser)]
+#[clap(
+    name = "premint-airdrop-indexer",
+    version,
+    about = "Index premint airdrop history from program logs"
+)]
+struct Cli {
+    /// Path of the index database
+    #[clap(long, default_value = "airdrop-index")]
+    db: String,
+
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Ingest program transactions newer than the last synced one
+    Sync {
+        /// JSON RPC URL
+        #[clap(long, short = 'u', default_value = "http://127.0.0.1:8899")]
+        url: String,
+
+        /// Keep polling every given amount of seconds
+        #[clap(long)]
+        follow: Option<u64>,
+    },
+
+    /// Show indexed airdrop config
+    Airdrop { airdrop: Pubkey },
+
+    /// List mints of the airdrop ordered by index
+    Mints {
+        airdrop: Pubkey,
+
+        /// Only mints received by this wallet
+        #[clap(long)]
+        recipient: Option<Pubkey>,
+    },
+
+    /// List registered users of the airdrop
+    Users { airdrop: Pubkey },
+}
+
+fn main() {
+    if let Err(error) = run(Cli::parse()) {
+        eprintln!("error: {}", error);
+        exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let indexer = Indexer::new(Store::open(&cli.db)?);
+    let store = indexer.store();
+
+    match cli.command {
+        Command::Sync { url, follow } => {
+            let source = RpcSource::new(RpcClient::new_with_commitment(
+                url,
+                CommitmentConfig::confirmed(),
+            ));
+
+            loop {
+                let transactions = source.fetch_since(store.cursor()?.as_deref())?;
+                let mut events = 0;
+                for transaction in &transactions {
+                    events += indexer.ingest(transaction)?;
+                    store.set_cursor(&transaction.signature)?;
+                }
+                eprintln!(
+                    "ingested {} transactions, {} events",
+                    transactions.len(),
+                    events
+                );
+
+                match follow {
+                    Some(seconds) => sleep(Duration::from_secs(seconds)),
+                    None => break,
+                }
+            }
+        }
+        Command::Airdrop { airdrop } => {
+            let record = store
+                .airdrop(&airdrop)?
+                .ok_or_else(|| format!("airdrop {} is not indexed", airdrop))?;
+            let seed = store.seed(&airdrop)?;
+            print(json!({
+                "airdrop": record.airdrop.to_string(),
+                "airdropAuthority": record.airdrop_authority.to_string(),
+                "revenuesWallet": record.revenues_wallet.to_string(),
+                "adminAccount": record.admin_account.to_string(),
+                "airdropAmount": record.airdrop_amount,
+                "price": record.price,
+                "slot": record.slot,
+                "timestamp": record.timestamp,
+                "signature": record.signature,
+                "seed": seed.as_ref().map(|seed| Hash::new_from_array(seed.seed).to_string()),
+                "slotHash": seed
+                    .as_ref()
+                    .map(|seed| Hash::new_from_array(seed.slot_hash).to_string()),
+                "startingOffset": seed.as_ref().map(|seed| seed.starting_offset),
+                "seedSignature": seed.map(|seed| seed.signature),
+            }));
+        }
+        Command::Mints { airdrop, recipient } => {
+            let mints = match recipient {
+                Some(recipient) => store.mints_by_recipient(&airdrop, &recipient)?,
+                None => store.mints(&airdrop)?,
+            };
+            let mut values = vec![];
+            for record in &mints {
+                let reveal = store.reveal(&airdrop, record.airdrop_index)?;
+                values.push(mint_json(record, reveal.as_ref()));
+            }
+            print(Value::Array(values));
+        }
+        Command::Users { airdrop } => {
+            print(store.users(&airdrop)?.iter().map(user_json).collect());
+        }
+    }
+
+    Ok(())
+}
+
+fn print(value: Value) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&value).expect("output is serializable")
+    );
+}
+
+fn mint_json(record: &MintRecord, reveal: Option<&RevealRecord>) -> Value {
+    json!({
+        "airdropIndex": record.airdrop_index,
+        "mint": record.mint.to_string(),
+        "recipient": record.recipient.to_string(),
+        "payer": record.payer.to_string(),
+        "price": record.price,
+        "slot": record.slot,
+        "timestamp": record.timestamp,
+        "signature": record.signature,
+        "revealSignature": reveal.map(|reveal| reveal.signature.clone()),
+    })
+}
+
+fn user_json(record: &UserRecord) -> Value {
+    json!({
+        "user": record.user.to_string(),
+        "slot": record.slot,
+        "timestamp": record.timestamp,
+        "signature": record.signature,
+    })
+}
@@ -0,0 +1,70 @@
+use std::str::FromStr;
+
+use solana_client::{rpc_client::GetConfirmedSignaturesForAddress2Config, rpc_client::RpcClient};
+use solana_sdk::signature::Signature;
+use solana_transaction_status::UiTransactionEncoding;
+
+use crate::{error::Result, indexer::RecordedTransaction};
+
+const PAGE_SIZE: usize = 1000;
+
+/// Reads airdrop program transactions over RPC
+pub struct RpcSource {
+    rpc: RpcClient,
+}
+
+impl RpcSource {
+    pub fn new(rpc: RpcClient) -> RpcSource {
+        RpcSource { rpc }
+    }
+
+    /// Fetches program transactions newer than `until`, oldest first
+    pub fn fetch_since(&self, until: Option<&str>) -> Result<Vec<RecordedTransaction>> {
+        let until = until.and_then(|signature| Signature::from_str(signature).ok());
+        let mut before = None;
+        let mut statuses = vec![];
+
+        loop {
+            let page = self.rpc.get_signatures_for_address_with_config(
+                &premint_airdrop::id(),
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until,
+                    limit: Some(PAGE_SIZE),
+                    commitment: Some(self.rpc.commitment()),
+                },
+            )?;
+
+            let last = page.last().map(|status| status.signature.clone());
+            let page_len = page.len();
+            statuses.extend(page);
+
+            match last {
+                Some(last) if page_len == PAGE_SIZE => {
+                    before = Signature::from_str(&last).ok();
+                }
+                _ => break,
+            }
+        }
+
+        statuses
+            .into_iter()
+            .rev()
+            .map(|status| {
+                let signature =
+                    Signature::from_str(&status.signature).expect("RPC returns valid signatures");
+                let transaction = self
+                    .rpc
+                    .get_transaction(&signature, UiTransactionEncoding::Json)?;
+                let meta = transaction.transaction.meta;
+
+                Ok(RecordedTransaction {
+                    signature: status.signature,
+                    slot: transaction.slot,
+                    failed: status.err.is_some(),
+                    logs: meta.and_then(|meta| meta.log_messages).unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+}
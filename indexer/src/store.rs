@@ -0,0 +1,214 @@
+use std::path::Path;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::Result;
+
+/// Airdrop config as created by `InitializeAirdrop`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AirdropRecord {
+    pub airdrop: Pubkey,
+    pub airdrop_authority: Pubkey,
+    pub revenues_wallet: Pubkey,
+    pub admin_account: Pubkey,
+    pub airdrop_amount: u64,
+    pub price: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub signature: String,
+}
+
+/// User registered by `InitializeAirdropUser`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserRecord {
+    pub airdrop: Pubkey,
+    pub user: Pubkey,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub signature: String,
+}
+
+/// Token minted by `MintOne`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MintRecord {
+    pub airdrop: Pubkey,
+    pub airdrop_index: u64,
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub payer: Pubkey,
+    pub price: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub signature: String,
+}
+
+/// Hidden metadata of a minted token replaced by `Reveal`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RevealRecord {
+    pub airdrop: Pubkey,
+    pub airdrop_index: u64,
+    pub mint: Pubkey,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub signature: String,
+}
+
+/// Fair mint seed revealed by `RevealSeed`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeedRecord {
+    pub airdrop: Pubkey,
+    pub seed: [u8; 32],
+    pub slot_hash: [u8; 32],
+    pub starting_offset: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub signature: String,
+}
+
+/// Embedded database of indexed airdrop history.
+///
+/// Mints and reveals are keyed by airdrop and big endian airdrop index, so prefix scans return
+/// them in index order. That is mint order only if the airdrop is not shuffled.
+/// Writing the same record twice overwrites it, which makes re-ingesting transactions harmless
+pub struct Store {
+    db: sled::Db,
+    airdrops: sled::Tree,
+    users: sled::Tree,
+    mints: sled::Tree,
+    reveals: sled::Tree,
+    seeds: sled::Tree,
+}
+
+const CURSOR: &[u8] = b"cursor";
+
+impl Store {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Store> {
+        Store::from_db(sled::open(path)?)
+    }
+
+    /// Store removed when dropped. Used in tests
+    pub fn temporary() -> Result<Store> {
+        Store::from_db(sled::Config::new().temporary(true).open()?)
+    }
+
+    fn from_db(db: sled::Db) -> Result<Store> {
+        Ok(Store {
+            airdrops: db.open_tree("airdrops")?,
+            users: db.open_tree("users")?,
+            mints: db.open_tree("mints")?,
+            reveals: db.open_tree("reveals")?,
+            seeds: db.open_tree("seeds")?,
+            db,
+        })
+    }
+
+    pub fn put_airdrop(&self, record: &AirdropRecord) -> Result<()> {
+        self.airdrops
+            .insert(record.airdrop.as_ref(), bincode::serialize(record)?)?;
+        Ok(())
+    }
+
+    pub fn put_user(&self, record: &UserRecord) -> Result<()> {
+        let key = [record.airdrop.as_ref(), record.user.as_ref()].concat();
+        self.users.insert(key, bincode::serialize(record)?)?;
+        Ok(())
+    }
+
+    pub fn put_mint(&self, record: &MintRecord) -> Result<()> {
+        let key = mint_key(&record.airdrop, record.airdrop_index);
+        self.mints.insert(key, bincode::serialize(record)?)?;
+        Ok(())
+    }
+
+    pub fn put_reveal(&self, record: &RevealRecord) -> Result<()> {
+        let key = mint_key(&record.airdrop, record.airdrop_index);
+        self.reveals.insert(key, bincode::serialize(record)?)?;
+        Ok(())
+    }
+
+    pub fn put_seed(&self, record: &SeedRecord) -> Result<()> {
+        self.seeds
+            .insert(record.airdrop.as_ref(), bincode::serialize(record)?)?;
+        Ok(())
+    }
+
+    pub fn airdrop(&self, airdrop: &Pubkey) -> Result<Option<AirdropRecord>> {
+        self.airdrops
+            .get(airdrop.as_ref())?
+            .map(|value| decode(&value))
+            .transpose()
+    }
+
+    /// `None` if the seed is not revealed or the airdrop is not a fair mint
+    pub fn seed(&self, airdrop: &Pubkey) -> Result<Option<SeedRecord>> {
+        self.seeds
+            .get(airdrop.as_ref())?
+            .map(|value| decode(&value))
+            .transpose()
+    }
+
+    pub fn users(&self, airdrop: &Pubkey) -> Result<Vec<UserRecord>> {
+        scan(&self.users, airdrop.as_ref())
+    }
+
+    /// All mints of the airdrop ordered by index
+    pub fn mints(&self, airdrop: &Pubkey) -> Result<Vec<MintRecord>> {
+        scan(&self.mints, airdrop.as_ref())
+    }
+
+    pub fn mint(&self, airdrop: &Pubkey, airdrop_index: u64) -> Result<Option<MintRecord>> {
+        self.mints
+            .get(mint_key(airdrop, airdrop_index))?
+            .map(|value| decode(&value))
+            .transpose()
+    }
+
+    /// `None` if the token is not revealed or was minted with final metadata
+    pub fn reveal(&self, airdrop: &Pubkey, airdrop_index: u64) -> Result<Option<RevealRecord>> {
+        self.reveals
+            .get(mint_key(airdrop, airdrop_index))?
+            .map(|value| decode(&value))
+            .transpose()
+    }
+
+    pub fn mints_by_recipient(
+        &self,
+        airdrop: &Pubkey,
+        recipient: &Pubkey,
+    ) -> Result<Vec<MintRecord>> {
+        Ok(self
+            .mints(airdrop)?
+            .into_iter()
+            .filter(|record| record.recipient == *recipient)
+            .collect())
+    }
+
+    /// Signature of the newest transaction already ingested from RPC
+    pub fn cursor(&self) -> Result<Option<String>> {
+        Ok(self
+            .db
+            .get(CURSOR)?
+            .map(|value| String::from_utf8_lossy(&value).to_string()))
+    }
+
+    pub fn set_cursor(&self, signature: &str) -> Result<()> {
+        self.db.insert(CURSOR, signature.as_bytes())?;
+        self.db.flush()?;
+        Ok(())
+    }
+}
+
+fn mint_key(airdrop: &Pubkey, airdrop_index: u64) -> Vec<u8> {
+    [airdrop.as_ref(), &airdrop_index.to_be_bytes()].concat()
+}
+
+fn scan<T: DeserializeOwned>(tree: &sled::Tree, prefix: &[u8]) -> Result<Vec<T>> {
+    tree.scan_prefix(prefix)
+        .map(|entry| decode(&entry?.1))
+        .collect()
+}
+
+fn decode<T: DeserializeOwned>(value: &[u8]) -> Result<T> {
+    Ok(bincode::deserialize(value)?)
+}
@@ -0,0 +1,200 @@
+use premint_airdrop::event::{
+    AirdropEvent, AirdropInitializedEvent, MintedEvent, RevealedEvent, SeedRevealedEvent,
+    UserRegisteredEvent,
+};
+use premint_airdrop_indexer::{indexer::RecordedTransaction, Indexer, Store};
+use solana_sdk::pubkey::Pubkey;
+
+struct Env {
+    indexer: Indexer,
+    airdrop: Pubkey,
+    recipient: Pubkey,
+    slot: u64,
+}
+
+impl Env {
+    fn new() -> Env {
+        Env {
+            indexer: Indexer::new(Store::temporary().unwrap()),
+            airdrop: Pubkey::new_unique(),
+            recipient: Pubkey::new_unique(),
+            slot: 100,
+        }
+    }
+
+    /// Transaction logs as returned by `getTransaction`
+    fn record(&mut self, events: &[AirdropEvent], failed: bool) -> RecordedTransaction {
+        let program_id = premint_airdrop::id();
+        let mut logs = vec![format!("Program {} invoke [1]", program_id)];
+        logs.extend(
+            events
+                .iter()
+                .map(|event| format!("Program data: {}", base64::encode(event.pack()))),
+        );
+        logs.push(match failed {
+            true => format!("Program {} failed: custom program error: 0x8", program_id),
+            false => format!("Program {} success", program_id),
+        });
+
+        self.slot += 1;
+        RecordedTransaction {
+            signature: format!("signature-{}", self.slot),
+            slot: self.slot,
+            failed,
+            logs,
+        }
+    }
+
+    fn minted(&self, airdrop_index: u64, recipient: Pubkey) -> AirdropEvent {
+        AirdropEvent::Minted(MintedEvent {
+            airdrop: self.airdrop,
+            recipient,
+            mint: Pubkey::new_unique(),
+            payer: recipient,
+            airdrop_index,
+            price: 1_000,
+            timestamp: 1_650_000_000 + airdrop_index as i64,
+        })
+    }
+}
+
+#[test]
+fn ingest_builds_airdrop_history() {
+    let mut env = Env::new();
+    let airdrop_authority = Pubkey::new_unique();
+
+    let initialized = AirdropEvent::AirdropInitialized(AirdropInitializedEvent {
+        airdrop: env.airdrop,
+        airdrop_authority,
+        revenues_wallet: Pubkey::new_unique(),
+        admin_account: Pubkey::new_unique(),
+        airdrop_amount: 10,
+        price: 1_000,
+        timestamp: 1_650_000_000,
+    });
+    let registered = AirdropEvent::UserRegistered(UserRegisteredEvent {
+        airdrop: env.airdrop,
+        user: env.recipient,
+        timestamp: 1_650_000_000,
+    });
+    let other = Pubkey::new_unique();
+
+    let transactions = vec![
+        env.record(&[initialized], false),
+        env.record(&[registered], false),
+        env.record(&[env.minted(1, env.recipient)], false),
+        env.record(&[env.minted(0, other)], false),
+        env.record(&[env.minted(2, env.recipient)], false),
+    ];
+    for transaction in &transactions {
+        assert_eq!(env.indexer.ingest(transaction).unwrap(), 1);
+    }
+
+    let store = env.indexer.store();
+    let airdrop = store.airdrop(&env.airdrop).unwrap().unwrap();
+    assert_eq!(airdrop.airdrop_authority, airdrop_authority);
+    assert_eq!(airdrop.signature, transactions[0].signature);
+
+    let users = store.users(&env.airdrop).unwrap();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].user, env.recipient);
+
+    let mints = store.mints(&env.airdrop).unwrap();
+    let indices: Vec<u64> = mints.iter().map(|mint| mint.airdrop_index).collect();
+    assert_eq!(indices, vec![0, 1, 2]);
+
+    let mint = store.mint(&env.airdrop, 1).unwrap().unwrap();
+    assert_eq!(mint.recipient, env.recipient);
+    assert_eq!(mint.slot, transactions[2].slot);
+
+    let received = store
+        .mints_by_recipient(&env.airdrop, &env.recipient)
+        .unwrap();
+    assert_eq!(received.len(), 2);
+    assert!(store.mints(&Pubkey::new_unique()).unwrap().is_empty());
+}
+
+#[test]
+fn ingest_records_batched_reveals() {
+    let mut env = Env::new();
+    let minted = [env.minted(0, env.recipient), env.minted(1, env.recipient)];
+    let revealed: Vec<AirdropEvent> = minted
+        .iter()
+        .map(|event| match event {
+            AirdropEvent::Minted(event) => AirdropEvent::Revealed(RevealedEvent {
+                airdrop: event.airdrop,
+                mint: event.mint,
+                airdrop_index: event.airdrop_index,
+                timestamp: event.timestamp + 60,
+            }),
+            _ => unreachable!(),
+        })
+        .collect();
+
+    let minted = env.record(&minted, false);
+    assert_eq!(env.indexer.ingest(&minted).unwrap(), 2);
+    let store = env.indexer.store();
+    assert!(store.reveal(&env.airdrop, 0).unwrap().is_none());
+
+    let revealed = env.record(&revealed, false);
+    assert_eq!(env.indexer.ingest(&revealed).unwrap(), 2);
+
+    let store = env.indexer.store();
+    for airdrop_index in 0..2 {
+        let mint = store.mint(&env.airdrop, airdrop_index).unwrap().unwrap();
+        let reveal = store.reveal(&env.airdrop, airdrop_index).unwrap().unwrap();
+        assert_eq!(reveal.mint, mint.mint);
+        assert_eq!(reveal.signature, revealed.signature);
+    }
+}
+
+#[test]
+fn ingest_records_revealed_seed() {
+    let mut env = Env::new();
+    assert!(env.indexer.store().seed(&env.airdrop).unwrap().is_none());
+
+    let event = AirdropEvent::SeedRevealed(SeedRevealedEvent {
+        airdrop: env.airdrop,
+        seed: [7; 32],
+        slot_hash: [9; 32],
+        starting_offset: 3,
+        timestamp: 1_650_000_100,
+    });
+    let transaction = env.record(&[event], false);
+    assert_eq!(env.indexer.ingest(&transaction).unwrap(), 1);
+
+    let seed = env.indexer.store().seed(&env.airdrop).unwrap().unwrap();
+    assert_eq!(seed.seed, [7; 32]);
+    assert_eq!(seed.slot_hash, [9; 32]);
+    assert_eq!(seed.starting_offset, 3);
+    assert_eq!(seed.signature, transaction.signature);
+}
+
+#[test]
+fn ingest_skips_failed_transactions() {
+    let mut env = Env::new();
+    let transaction = env.record(&[env.minted(0, env.recipient)], true);
+
+    assert_eq!(env.indexer.ingest(&transaction).unwrap(), 0);
+    assert!(env.indexer.store().mint(&env.airdrop, 0).unwrap().is_none());
+}
+
+#[test]
+fn ingest_is_idempotent() {
+    let mut env = Env::new();
+    let transaction = env.record(&[env.minted(0, env.recipient)], false);
+
+    env.indexer.ingest(&transaction).unwrap();
+    env.indexer.ingest(&transaction).unwrap();
+
+    assert_eq!(env.indexer.store().mints(&env.airdrop).unwrap().len(), 1);
+}
+
+#[test]
+fn cursor_round_trips() {
+    let store = Store::temporary().unwrap();
+    assert_eq!(store.cursor().unwrap(), None);
+
+    store.set_cursor("signature").unwrap();
+    assert_eq!(store.cursor().unwrap().as_deref(), Some("signature"));
+}
@@ -0,0 +1,29 @@
+use solana_sdk::signer::SignerError;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CoSignError {
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+
+    #[error("Transaction rejected: {0}")]
+    Rejected(String),
+
+    #[error("Wallet is not eligible: {0}")]
+    NotEligible(String),
+
+    #[error("Unable to sign transaction: {0}")]
+    Signing(#[from] SignerError),
+}
+
+impl CoSignError {
+    /// HTTP status code reported to the caller
+    pub fn status_code(&self) -> u16 {
+        match self {
+            CoSignError::BadRequest(_) => 400,
+            CoSignError::Rejected(_) => 400,
+            CoSignError::NotEligible(_) => 403,
+            CoSignError::Signing(_) => 500,
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod error;
+pub mod rules;
+pub mod service;
+pub mod validation;
@@ -0,0 +1,137 @@
+use std::{error::Error, io::Read, process::exit};
+
+use clap::Parser;
+use premint_airdrop_client::AirdropClient;
+use premint_airdrop_signer::{
+    rules::{Allowlist, RpcMintCounter, WalletQuota},
+    service::CoSigner,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::read_keypair_file,
+};
+use tiny_http::{Header, Method, Response, Server};
+
+/// Largest accepted request body. Transactions are at most 1232 bytes, so their base64
+/// encoding fits with plenty of room
+const MAX_BODY: u64 = 4096;
+
+#[derive(Parser)]
+#[clap(
+    name = "premint-airdrop-signer",
+    version,
+    about = "Co-signs MintOne transactions with the airdrop authority"
+)]
+struct Args {
+    /// Airdrop config address
+    #[clap(long)]
+    airdrop: Pubkey,
+
+    /// Keypair file of the airdrop authority
+    #[clap(long)]
+    authority_keypair: String,
+
+    /// Airdrop is shuffled, so `MintOne` passes the slot hashes sysvar
+    #[clap(long)]
+    shuffled: bool,
+
+    /// Config lines account, required if the airdrop reads metadata from config lines
+    #[clap(long)]
+    config_lines: Option<Pubkey>,
+
+    /// Address to listen on
+    #[clap(long, default_value = "127.0.0.1:8080")]
+    bind: String,
+
+    /// File with allowed recipient wallets, one per line
+    #[clap(long)]
+    allowlist: Option<String>,
+
+    /// Maximum amount of tokens minted to one wallet
+    #[clap(long)]
+    max_mints_per_wallet: Option<u64>,
+
+    /// JSON RPC URL used to read user data for quotas
+    #[clap(long, short = 'u', default_value = "http://127.0.0.1:8899")]
+    url: String,
+}
+
+fn main() {
+    if let Err(error) = run(Args::parse()) {
+        eprintln!("error: {}", error);
+        exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    let authority = read_keypair_file(&args.authority_keypair)
+        .map_err(|error| format!("unable to read {}: {}", args.authority_keypair, error))?;
+
+    let mut co_signer = CoSigner::new(args.airdrop, authority);
+
+    if args.shuffled {
+        co_signer = co_signer.with_slot_hashes();
+    }
+
+    if let Some(config_lines) = args.config_lines {
+        co_signer = co_signer.with_config_lines(config_lines);
+    }
+
+    if let Some(path) = &args.allowlist {
+        co_signer = co_signer.with_rule(Allowlist::from_file(path)?);
+    }
+
+    if let Some(max_mints) = args.max_mints_per_wallet {
+        let client = AirdropClient::new(RpcClient::new_with_commitment(
+            args.url.clone(),
+            CommitmentConfig::confirmed(),
+        ));
+        co_signer = co_signer.with_rule(WalletQuota::new(
+            max_mints,
+            RpcMintCounter {
+                client,
+                airdrop: args.airdrop,
+            },
+        ));
+    }
+
+    let server = Server::http(&args.bind)
+        .map_err(|error| format!("unable to listen on {}: {}", args.bind, error))?;
+    println!("Listening on {}", args.bind);
+
+    let json = Header::from_bytes("Content-Type", "application/json").expect("valid header");
+
+    for mut request in server.incoming_requests() {
+        let (status, body) = match (request.method(), request.url()) {
+            (Method::Post, "/sign") => {
+                let mut body = vec![];
+                match request
+                    .as_reader()
+                    .take(MAX_BODY + 1)
+                    .read_to_end(&mut body)
+                {
+                    Ok(_) if body.len() as u64 > MAX_BODY => {
+                        (413, error_json("request body is too large"))
+                    }
+                    Ok(_) => co_signer.handle(&body),
+                    Err(error) => (400, error_json(&error.to_string())),
+                }
+            }
+            _ => (404, error_json("not found")),
+        };
+
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(json.clone());
+
+        if let Err(error) = request.respond(response) {
+            eprintln!("unable to respond: {}", error);
+        }
+    }
+
+    Ok(())
+}
+
+fn error_json(error: &str) -> String {
+    serde_json::json!({ "error": error }).to_string()
+}
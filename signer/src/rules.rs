@@ -0,0 +1,99 @@
+use std::{collections::HashSet, fs, io, path::Path, str::FromStr};
+
+use premint_airdrop_client::AirdropClient;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::validation::MintRequest;
+
+/// Decides whether a validated mint request should be co-signed
+pub trait EligibilityRule: Send + Sync {
+    /// Returns the reason of rejection if request is not eligible
+    fn check(&self, request: &MintRequest) -> Result<(), String>;
+}
+
+/// Only wallets from the list are able to receive tokens
+pub struct Allowlist {
+    wallets: HashSet<Pubkey>,
+}
+
+impl Allowlist {
+    pub fn new<I: IntoIterator<Item = Pubkey>>(wallets: I) -> Allowlist {
+        Allowlist {
+            wallets: wallets.into_iter().collect(),
+        }
+    }
+
+    /// Reads one base58 address per line. Empty lines and lines starting with `#` are skipped
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Allowlist> {
+        let wallets = fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                Pubkey::from_str(line).map_err(|error| {
+                    io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", line, error))
+                })
+            })
+            .collect::<io::Result<HashSet<Pubkey>>>()?;
+
+        Ok(Allowlist { wallets })
+    }
+}
+
+impl EligibilityRule for Allowlist {
+    fn check(&self, request: &MintRequest) -> Result<(), String> {
+        match self.wallets.contains(&request.recipient) {
+            true => Ok(()),
+            false => Err(format!("{} is not in allowlist", request.recipient)),
+        }
+    }
+}
+
+/// Source of the amount of tokens already minted to a wallet
+pub trait MintCounter: Send + Sync {
+    fn minted(&self, recipient: &Pubkey) -> Result<u64, String>;
+}
+
+/// Reads `AirdropUserData::mints_amount` over RPC
+pub struct RpcMintCounter {
+    pub client: AirdropClient,
+    pub airdrop: Pubkey,
+}
+
+impl MintCounter for RpcMintCounter {
+    fn minted(&self, recipient: &Pubkey) -> Result<u64, String> {
+        self.client
+            .get_user_data(&self.airdrop, recipient)
+            .map(|user_data| user_data.map_or(0, |user_data| user_data.mints_amount))
+            .map_err(|error| error.to_string())
+    }
+}
+
+/// Limits amount of tokens minted to a single wallet.
+///
+/// Counter is read before signing, so concurrent requests for one wallet may exceed the quota.
+/// On-chain user timeout limits how many of them can land
+pub struct WalletQuota<C: MintCounter> {
+    max_mints: u64,
+    counter: C,
+}
+
+impl<C: MintCounter> WalletQuota<C> {
+    pub fn new(max_mints: u64, counter: C) -> WalletQuota<C> {
+        WalletQuota { max_mints, counter }
+    }
+}
+
+impl<C: MintCounter> EligibilityRule for WalletQuota<C> {
+    fn check(&self, request: &MintRequest) -> Result<(), String> {
+        let minted = self.counter.minted(&request.recipient)?;
+
+        match minted < self.max_mints {
+            true => Ok(()),
+            false => Err(format!(
+                "{} already minted {} of {} tokens",
+                request.recipient, minted, self.max_mints
+            )),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, transaction::Transaction};
+
+use crate::{
+    error::CoSignError,
+    rules::EligibilityRule,
+    validation::{validate_transaction, MintRequest},
+};
+
+/// Body of `POST /sign` request and successful response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignPayload {
+    /// Base64 encoded bincode serialized transaction
+    pub transaction: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ErrorPayload {
+    pub error: String,
+}
+
+/// Co-signs `MintOne` transactions of one airdrop with its authority
+pub struct CoSigner {
+    airdrop: Pubkey,
+    authority: Keypair,
+    shuffled: bool,
+    config_lines: Option<Pubkey>,
+    rules: Vec<Box<dyn EligibilityRule>>,
+}
+
+impl CoSigner {
+    pub fn new(airdrop: Pubkey, authority: Keypair) -> CoSigner {
+        CoSigner {
+            airdrop,
+            authority,
+            shuffled: false,
+            config_lines: None,
+            rules: vec![],
+        }
+    }
+
+    /// Expects `MintOne` to pass the slot hashes sysvar of a shuffled airdrop
+    pub fn with_slot_hashes(mut self) -> CoSigner {
+        self.shuffled = true;
+        self
+    }
+
+    /// Expects `MintOne` to pass `config_lines` account of the airdrop
+    pub fn with_config_lines(mut self, config_lines: Pubkey) -> CoSigner {
+        self.config_lines = Some(config_lines);
+        self
+    }
+
+    pub fn with_rule<R: EligibilityRule + 'static>(mut self, rule: R) -> CoSigner {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// Validates transaction, applies eligibility rules and adds authority signature
+    pub fn co_sign(
+        &self,
+        mut transaction: Transaction,
+    ) -> Result<(Transaction, MintRequest), CoSignError> {
+        let request = validate_transaction(
+            &transaction,
+            &self.airdrop,
+            &self.authority.pubkey(),
+            self.shuffled,
+            self.config_lines.as_ref(),
+        )?;
+
+        for rule in &self.rules {
+            rule.check(&request).map_err(CoSignError::NotEligible)?;
+        }
+
+        let blockhash = transaction.message.recent_blockhash;
+        transaction.try_partial_sign(&[&self.authority], blockhash)?;
+
+        Ok((transaction, request))
+    }
+
+    /// Handles JSON body of `POST /sign`. Returns HTTP status code and JSON response
+    pub fn handle(&self, body: &[u8]) -> (u16, String) {
+        match self.handle_payload(body) {
+            Ok(payload) => (200, to_json(&payload)),
+            Err(error) => (
+                error.status_code(),
+                to_json(&ErrorPayload {
+                    error: error.to_string(),
+                }),
+            ),
+        }
+    }
+
+    fn handle_payload(&self, body: &[u8]) -> Result<SignPayload, CoSignError> {
+        let payload: SignPayload =
+            serde_json::from_slice(body).map_err(|error| bad_request(&error.to_string()))?;
+        let bytes = base64::decode(&payload.transaction)
+            .map_err(|error| bad_request(&error.to_string()))?;
+        let transaction: Transaction =
+            bincode::deserialize(&bytes).map_err(|error| bad_request(&error.to_string()))?;
+
+        let (transaction, _) = self.co_sign(transaction)?;
+
+        let bytes = bincode::serialize(&transaction).expect("transaction is serializable");
+        Ok(SignPayload {
+            transaction: base64::encode(bytes),
+        })
+    }
+}
+
+fn bad_request(reason: &str) -> CoSignError {
+    CoSignError::BadRequest(reason.to_string())
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("payload is serializable")
+}
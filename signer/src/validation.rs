@@ -0,0 +1,151 @@
+use premint_airdrop::instruction::{deserialize_instruction_data, AirdropInstruction};
+use solana_sdk::{compute_budget, pubkey::Pubkey, transaction::Transaction};
+
+use crate::error::CoSignError;
+
+/// Positions of `MintOne` accounts used for validation
+const MINT_ONE_AIRDROP: usize = 0;
+const MINT_ONE_MINT: usize = 2;
+const MINT_ONE_RECIPIENT: usize = 3;
+const MINT_ONE_PAYER: usize = 13;
+const MINT_ONE_AUTHORITY: usize = 14;
+/// Accounts of `MintOne` before the slot hashes sysvar of shuffled airdrops and config lines
+const MINT_ONE_ACCOUNTS: usize = 17;
+
+/// Positions of `CommitDraw` accounts used for validation
+const COMMIT_DRAW_AIRDROP: usize = 0;
+const COMMIT_DRAW_RECIPIENT: usize = 2;
+const COMMIT_DRAW_AUTHORITY: usize = 3;
+const COMMIT_DRAW_ACCOUNTS: usize = 5;
+
+/// Mint described by a validated transaction
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MintRequest {
+    pub recipient: Pubkey,
+    pub payer: Pubkey,
+    /// `None` if the transaction commits the draw of a shuffled mint
+    pub mint: Option<Pubkey>,
+}
+
+/// Checks that `transaction` only mints one token of `airdrop`, or commits the draw
+/// preceding the mint, and that `authority` signature is not usable for anything else.
+/// `MintOne` passes the slot hashes sysvar if the airdrop is `shuffled`
+/// and `config_lines` account if the airdrop has one
+pub fn validate_transaction(
+    transaction: &Transaction,
+    airdrop: &Pubkey,
+    authority: &Pubkey,
+    shuffled: bool,
+    config_lines: Option<&Pubkey>,
+) -> Result<MintRequest, CoSignError> {
+    let message = &transaction.message;
+    let keys = &message.account_keys;
+
+    let authority_index = keys
+        .iter()
+        .position(|key| key == authority)
+        .ok_or_else(|| rejected("airdrop authority is not a part of transaction"))?;
+
+    if authority_index == 0 {
+        return Err(rejected("airdrop authority can not be fee payer"));
+    }
+
+    if !message.is_signer(authority_index) || message.is_writable(authority_index) {
+        return Err(rejected("airdrop authority must be read-only signer"));
+    }
+
+    let mut mint_request = None;
+
+    for instruction in &message.instructions {
+        let program_id = keys
+            .get(instruction.program_id_index as usize)
+            .ok_or_else(|| rejected("program index out of bounds"))?;
+
+        if *program_id == compute_budget::id() {
+            continue;
+        }
+
+        if *program_id != premint_airdrop::id() {
+            return Err(rejected(&format!("unexpected program {}", program_id)));
+        }
+
+        let is_mint = match deserialize_instruction_data(&instruction.data) {
+            Ok(AirdropInstruction::MintOne(_)) => true,
+            Ok(AirdropInstruction::CommitDraw(_)) => false,
+            _ => {
+                return Err(rejected(
+                    "only MintOne or CommitDraw instruction is allowed",
+                ))
+            }
+        };
+
+        if mint_request.is_some() {
+            return Err(rejected(
+                "only one MintOne or CommitDraw instruction is allowed",
+            ));
+        }
+
+        let accounts = instruction
+            .accounts
+            .iter()
+            .map(|index| keys.get(*index as usize).copied())
+            .collect::<Option<Vec<Pubkey>>>()
+            .ok_or_else(|| rejected("account index out of bounds"))?;
+
+        if !is_mint {
+            if accounts.len() != COMMIT_DRAW_ACCOUNTS {
+                return Err(rejected("wrong number of CommitDraw accounts"));
+            }
+
+            if accounts[COMMIT_DRAW_AIRDROP] != *airdrop {
+                return Err(rejected("CommitDraw is for another airdrop"));
+            }
+
+            if accounts[COMMIT_DRAW_AUTHORITY] != *authority {
+                return Err(rejected("CommitDraw is approved by another authority"));
+            }
+
+            mint_request = Some(MintRequest {
+                recipient: accounts[COMMIT_DRAW_RECIPIENT],
+                payer: keys[0],
+                mint: None,
+            });
+            continue;
+        }
+
+        let config_lines_index = MINT_ONE_ACCOUNTS + usize::from(shuffled);
+        let expected_accounts = match config_lines {
+            Some(_) => config_lines_index + 1,
+            None => config_lines_index,
+        };
+        if accounts.len() != expected_accounts {
+            return Err(rejected("wrong number of MintOne accounts"));
+        }
+
+        if let Some(config_lines) = config_lines {
+            if accounts[config_lines_index] != *config_lines {
+                return Err(rejected("MintOne reads another config lines account"));
+            }
+        }
+
+        if accounts[MINT_ONE_AIRDROP] != *airdrop {
+            return Err(rejected("MintOne is for another airdrop"));
+        }
+
+        if accounts[MINT_ONE_AUTHORITY] != *authority {
+            return Err(rejected("MintOne is approved by another authority"));
+        }
+
+        mint_request = Some(MintRequest {
+            recipient: accounts[MINT_ONE_RECIPIENT],
+            payer: accounts[MINT_ONE_PAYER],
+            mint: Some(accounts[MINT_ONE_MINT]),
+        });
+    }
+
+    mint_request.ok_or_else(|| rejected("transaction has no MintOne or CommitDraw instruction"))
+}
+
+fn rejected(reason: &str) -> CoSignError {
+    CoSignError::Rejected(reason.to_string())
+}
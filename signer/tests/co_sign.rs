@@ -0,0 +1,226 @@
+use premint_airdrop::instruction::{
+    commit_draw, initialize_airdrop_user, mint_one, with_config_lines,
+};
+use premint_airdrop_signer::{
+    error::CoSignError,
+    rules::{Allowlist, MintCounter, WalletQuota},
+    service::{CoSigner, SignPayload},
+};
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction, hash::Hash, instruction::Instruction, pubkey::Pubkey,
+    signature::Keypair, signer::Signer, system_instruction, transaction::Transaction,
+};
+
+struct Env {
+    airdrop: Pubkey,
+    authority: Keypair,
+    payer: Keypair,
+    mint: Keypair,
+    recipient: Pubkey,
+}
+
+impl Env {
+    fn new() -> Env {
+        Env {
+            airdrop: Pubkey::new_unique(),
+            authority: Keypair::new(),
+            payer: Keypair::new(),
+            mint: Keypair::new(),
+            recipient: Pubkey::new_unique(),
+        }
+    }
+
+    fn co_signer(&self) -> CoSigner {
+        let authority = Keypair::from_bytes(&self.authority.to_bytes()).unwrap();
+        CoSigner::new(self.airdrop, authority)
+    }
+
+    fn mint_one(&self, airdrop: &Pubkey) -> Instruction {
+        self.mint_one_with(airdrop, false)
+    }
+
+    fn mint_one_with(&self, airdrop: &Pubkey, shuffled: bool) -> Instruction {
+        mint_one(
+            airdrop,
+            &self.mint.pubkey(),
+            &self.recipient,
+            &self.payer.pubkey(),
+            &self.authority.pubkey(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            shuffled,
+        )
+    }
+
+    /// Transaction signed by payer and mint, as sent by a frontend
+    fn transaction(&self, instructions: &[Instruction]) -> Transaction {
+        let mut transaction = Transaction::new_with_payer(instructions, Some(&self.payer.pubkey()));
+        transaction.partial_sign(&[&self.payer, &self.mint], Hash::new_unique());
+        transaction
+    }
+}
+
+struct FixedCounter(u64);
+
+impl MintCounter for FixedCounter {
+    fn minted(&self, _recipient: &Pubkey) -> Result<u64, String> {
+        Ok(self.0)
+    }
+}
+
+#[test]
+fn co_sign_completes_mint_transaction() {
+    let env = Env::new();
+    let transaction = env.transaction(&[
+        ComputeBudgetInstruction::set_compute_unit_limit(400_000),
+        env.mint_one(&env.airdrop),
+    ]);
+    assert!(transaction.verify().is_err());
+
+    let (transaction, request) = env.co_signer().co_sign(transaction).unwrap();
+
+    assert!(transaction.verify().is_ok());
+    assert_eq!(request.recipient, env.recipient);
+    assert_eq!(request.payer, env.payer.pubkey());
+    assert_eq!(request.mint, Some(env.mint.pubkey()));
+}
+
+#[test]
+fn co_sign_checks_config_lines_account() {
+    let env = Env::new();
+    let config_lines = Pubkey::new_unique();
+    let co_signer = env.co_signer().with_config_lines(config_lines);
+
+    let instruction = with_config_lines(env.mint_one(&env.airdrop), &config_lines);
+    assert!(co_signer.co_sign(env.transaction(&[instruction])).is_ok());
+
+    for instruction in [
+        env.mint_one(&env.airdrop),
+        with_config_lines(env.mint_one(&env.airdrop), &Pubkey::new_unique()),
+    ] {
+        let result = co_signer.co_sign(env.transaction(&[instruction]));
+        assert!(matches!(result, Err(CoSignError::Rejected(_))));
+    }
+
+    // Airdrops without config lines reject the extra account
+    let instruction = with_config_lines(env.mint_one(&env.airdrop), &config_lines);
+    let result = env.co_signer().co_sign(env.transaction(&[instruction]));
+    assert!(matches!(result, Err(CoSignError::Rejected(_))));
+}
+
+#[test]
+fn co_sign_checks_slot_hashes_of_shuffled_airdrop() {
+    let env = Env::new();
+    let config_lines = Pubkey::new_unique();
+    let co_signer = env.co_signer().with_slot_hashes();
+
+    let instruction = env.mint_one_with(&env.airdrop, true);
+    assert!(co_signer.co_sign(env.transaction(&[instruction])).is_ok());
+
+    // Config lines follow the slot hashes sysvar
+    let co_signer_with_lines = env
+        .co_signer()
+        .with_slot_hashes()
+        .with_config_lines(config_lines);
+    let instruction = with_config_lines(env.mint_one_with(&env.airdrop, true), &config_lines);
+    let result = co_signer_with_lines.co_sign(env.transaction(&[instruction]));
+    assert!(result.is_ok());
+
+    let result = co_signer.co_sign(env.transaction(&[env.mint_one(&env.airdrop)]));
+    assert!(matches!(result, Err(CoSignError::Rejected(_))));
+
+    // Airdrops that are not shuffled reject the extra account
+    let instruction = env.mint_one_with(&env.airdrop, true);
+    let result = env.co_signer().co_sign(env.transaction(&[instruction]));
+    assert!(matches!(result, Err(CoSignError::Rejected(_))));
+}
+
+#[test]
+fn co_sign_completes_commit_draw_transaction() {
+    let env = Env::new();
+    let instruction = commit_draw(&env.airdrop, &env.recipient, &env.authority.pubkey());
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&env.payer.pubkey()));
+    transaction.partial_sign(&[&env.payer], Hash::new_unique());
+
+    let (transaction, request) = env.co_signer().co_sign(transaction).unwrap();
+
+    assert!(transaction.verify().is_ok());
+    assert_eq!(request.recipient, env.recipient);
+    assert_eq!(request.payer, env.payer.pubkey());
+    assert_eq!(request.mint, None);
+}
+
+#[test]
+fn co_sign_rejects_unexpected_instructions() {
+    let env = Env::new();
+    let transfer = system_instruction::transfer(&env.payer.pubkey(), &Pubkey::new_unique(), 1);
+    let register = initialize_airdrop_user(&env.airdrop, &env.recipient, &env.payer.pubkey());
+    let draw = commit_draw(&env.airdrop, &env.recipient, &env.authority.pubkey());
+
+    for instructions in [
+        vec![env.mint_one(&env.airdrop), transfer],
+        vec![env.mint_one(&env.airdrop), register],
+        vec![env.mint_one(&env.airdrop), env.mint_one(&env.airdrop)],
+        vec![draw, env.mint_one(&env.airdrop)],
+        vec![env.mint_one(&Pubkey::new_unique())],
+    ] {
+        let result = env.co_signer().co_sign(env.transaction(&instructions));
+        assert!(matches!(result, Err(CoSignError::Rejected(_))));
+    }
+}
+
+#[test]
+fn co_sign_rejects_authority_as_fee_payer() {
+    let env = Env::new();
+    let transaction =
+        Transaction::new_with_payer(&[env.mint_one(&env.airdrop)], Some(&env.authority.pubkey()));
+
+    let result = env.co_signer().co_sign(transaction);
+    assert!(matches!(result, Err(CoSignError::Rejected(_))));
+}
+
+#[test]
+fn co_sign_applies_rules() {
+    let env = Env::new();
+
+    let co_signer = env
+        .co_signer()
+        .with_rule(Allowlist::new(vec![Pubkey::new_unique()]));
+    let result = co_signer.co_sign(env.transaction(&[env.mint_one(&env.airdrop)]));
+    assert!(matches!(result, Err(CoSignError::NotEligible(_))));
+
+    let co_signer = env
+        .co_signer()
+        .with_rule(Allowlist::new(vec![env.recipient]))
+        .with_rule(WalletQuota::new(2, FixedCounter(1)));
+    assert!(co_signer
+        .co_sign(env.transaction(&[env.mint_one(&env.airdrop)]))
+        .is_ok());
+
+    let co_signer = env
+        .co_signer()
+        .with_rule(WalletQuota::new(2, FixedCounter(2)));
+    let result = co_signer.co_sign(env.transaction(&[env.mint_one(&env.airdrop)]));
+    assert!(matches!(result, Err(CoSignError::NotEligible(_))));
+}
+
+#[test]
+fn handle_round_trips_json() {
+    let env = Env::new();
+    let transaction = env.transaction(&[env.mint_one(&env.airdrop)]);
+    let body = serde_json::to_vec(&SignPayload {
+        transaction: base64::encode(bincode::serialize(&transaction).unwrap()),
+    })
+    .unwrap();
+
+    let (status, response) = env.co_signer().handle(&body);
+    assert_eq!(status, 200);
+
+    let payload: SignPayload = serde_json::from_str(&response).unwrap();
+    let signed: Transaction =
+        bincode::deserialize(&base64::decode(payload.transaction).unwrap()).unwrap();
+    assert!(signed.verify().is_ok());
+
+    let (status, _) = env.co_signer().handle(b"{\"transaction\":\"???\"}");
+    assert_eq!(status, 400);
+}
@@ -9,7 +9,7 @@ entrypoint!(process_ix);
 
 pub fn process_ix<'a>(
     program_id: &Pubkey,
-    accounts: &'a [AccountInfo<'a>],
+    accounts: &[AccountInfo<'a>],
     instruction_data: &[u8],
 ) -> ProgramResult {
     if let Err(error) = processor::process_instruction(program_id, accounts, instruction_data) {
@@ -14,26 +14,176 @@ pub enum AirdropError {
     #[error("Unexpected input in instruction argument")]
     BadInstructionArgument,
 
-    #[error("Acoount is not signer")]
+    #[error("Required signature is missing")]
     SignerRequired,
 
-    #[error("Acoount is not writeable")]
+    #[error("Account must be writeable")]
     WriteableRequired,
 
-    #[error("Acoount is not properly derived")]
+    #[error("Account is not a valid program derived address")]
     PdaCheckFailed,
 
-    #[error("Acoount is not initialized")]
+    #[error("Account is not initialized")]
     Uninitialized,
 
     #[error("Wrong account address")]
     WrongAccountAddress,
 
-    #[error("User timeout is not expired yet")]
+    #[error("User is locked after the previous mint")]
     UserTimeout,
 
-    #[error("Out of supply")]
+    #[error("All tokens of the airdrop are minted")]
     OutOfSupply,
+
+    #[error("Account is not an airdrop config")]
+    NotAirdropConfig,
+
+    #[error("Account is not an airdrop user data")]
+    NotAirdropUserData,
+
+    #[error("Wrong system program")]
+    WrongSystemProgram,
+
+    #[error("Wrong token program")]
+    WrongTokenProgram,
+
+    #[error("Wrong associated token program")]
+    WrongAssociatedTokenProgram,
+
+    #[error("Wrong token metadata program")]
+    WrongTokenMetadataProgram,
+
+    #[error("Recipient token account is not associated token account of recipient")]
+    WrongRecipientTokenAccount,
+
+    #[error("Token metadata account is not metadata account of mint")]
+    WrongTokenMetadataAccount,
+
+    #[error("Wrong rent sysvar")]
+    WrongRentSysvar,
+
+    #[error("Wrong clock sysvar")]
+    WrongClockSysvar,
+
+    #[error("Malformed or unsupported event data")]
+    BadEventData,
+
+    #[error("Malformed return data")]
+    BadReturnData,
+
+    #[error("Account is owned by another program")]
+    WrongAccountOwner,
+
+    #[error("Account is already initialized")]
+    AlreadyInitialized,
+
+    #[error("User is already registered for the airdrop")]
+    UserAlreadyRegistered,
+
+    #[error("User data belongs to another user")]
+    WrongRecipient,
+
+    #[error("User data belongs to another airdrop")]
+    WrongAirdrop,
+
+    #[error("Metadata string is not valid UTF-8")]
+    BadMetadataString,
+
+    #[error("Arithmetic overflow")]
+    ArithmeticOverflow,
+
+    #[error("Airdrop amount must be positive")]
+    InvalidAirdropAmount,
+
+    #[error("Base URI must not be empty and must fit Metaplex URI length")]
+    InvalidBaseUri,
+
+    #[error("Symbol must fit Metaplex symbol length")]
+    InvalidSymbol,
+
+    #[error("Name template must contain one `{{}}` placeholder and fit Metaplex name length")]
+    InvalidNameTemplate,
+
+    #[error("Airdrop config account size does not match its metadata strings and index map")]
+    WrongConfigSize,
+
+    #[error("Index offset overflows number of the last token")]
+    InvalidIndexOffset,
+
+    #[error("Hidden name template and URI must be set together and fit Metaplex lengths")]
+    InvalidHiddenMetadata,
+
+    #[error("Airdrop metadata is not hidden")]
+    NotHidden,
+
+    #[error("Token metadata is already revealed")]
+    AlreadyRevealed,
+
+    #[error("Wrong slot hashes sysvar")]
+    WrongSlotHashesSysvar,
+
+    #[error("Provenance hash and seed commitment must be set together with hidden metadata")]
+    InvalidProvenance,
+
+    #[error("Airdrop has no seed commitment")]
+    NoSeedCommitment,
+
+    #[error("Seed is already revealed")]
+    SeedAlreadyRevealed,
+
+    #[error("Seed does not match the commitment")]
+    WrongSeed,
+
+    #[error("Seed may only be revealed after the sale is ended")]
+    SaleNotFinished,
+
+    #[error("Seed must be revealed before token metadata")]
+    SeedNotRevealed,
+
+    #[error("Account is not config lines")]
+    NotConfigLines,
+
+    #[error("Config lines can not be combined with hidden metadata")]
+    InvalidConfigLines,
+
+    #[error("Airdrop has no config lines")]
+    NoConfigLines,
+
+    #[error("Wrong config lines account")]
+    WrongConfigLinesAccount,
+
+    #[error("Config line has empty or too long name or URI, or is out of airdrop range")]
+    InvalidConfigLine,
+
+    #[error("Config lines may only be changed before the sale")]
+    SaleStarted,
+
+    #[error("Config line of the token is not uploaded")]
+    ConfigLineMissing,
+
+    #[error("Existing recipient token account has wrong owner or mint")]
+    InvalidRecipientTokenAccount,
+
+    #[error("Mint account is not derived from the airdrop and the amount of minted tokens")]
+    WrongMintAccount,
+
+    #[error("Airdrop requires the recipient to pay")]
+    UserMustPay,
+
+    #[error("Airdrop indices are not shuffled")]
+    NotShuffled,
+
+    #[error("Shuffled mint requires a committed draw")]
+    DrawNotCommitted,
+
+    #[error("Hash of the committed slot is not available yet")]
+    SlotHashNotReady,
+
+    #[error("Hash of the committed slot is no longer available")]
+    SlotHashExpired,
+
+    #[error("Sale is ended by admin")]
+    SaleEnded,
 }
 
 impl PrintProgramError for AirdropError {
@@ -0,0 +1,319 @@
+use std::convert::TryInto;
+
+use arrayref::{array_mut_ref, array_refs, mut_array_refs};
+use solana_program::{log::sol_log_data, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::error::AirdropError;
+
+/// Version of the event layout. Written as the first byte of every event
+pub const EVENT_VERSION: u8 = 1;
+
+/// Events emitted by the program via `sol_log_data`.
+///
+/// Layout: `[version: u8][event type: u8][event body]`. All integers are little endian
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AirdropEvent {
+    /// Event type 1. Airdrop config was created
+    AirdropInitialized(AirdropInitializedEvent),
+
+    /// Event type 2. User data account was registered for airdrop
+    UserRegistered(UserRegisteredEvent),
+
+    /// Event type 3. Token was minted to recipient
+    Minted(MintedEvent),
+
+    /// Event type 4. Hidden metadata of a token was replaced with the final one
+    Revealed(RevealedEvent),
+
+    /// Event type 5. Seed of a fair mint was revealed and metadata starting offset stored
+    SeedRevealed(SeedRevealedEvent),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AirdropInitializedEvent {
+    pub airdrop: Pubkey,
+    pub airdrop_authority: Pubkey,
+    pub revenues_wallet: Pubkey,
+    pub admin_account: Pubkey,
+    pub airdrop_amount: u64,
+    pub price: u64,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UserRegisteredEvent {
+    pub airdrop: Pubkey,
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MintedEvent {
+    pub airdrop: Pubkey,
+    pub recipient: Pubkey,
+    pub mint: Pubkey,
+    pub payer: Pubkey,
+    pub airdrop_index: u64,
+    pub price: u64,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RevealedEvent {
+    pub airdrop: Pubkey,
+    pub mint: Pubkey,
+    pub airdrop_index: u64,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SeedRevealedEvent {
+    pub airdrop: Pubkey,
+    pub seed: [u8; 32],
+    /// Hash of the sale end slot, mixed into the starting offset
+    pub slot_hash: [u8; 32],
+    pub starting_offset: u64,
+    pub timestamp: i64,
+}
+
+impl AirdropEvent {
+    pub fn pack(&self) -> Vec<u8> {
+        let (event_type, body_len) = match self {
+            AirdropEvent::AirdropInitialized(_) => (1, AirdropInitializedEvent::LEN),
+            AirdropEvent::UserRegistered(_) => (2, UserRegisteredEvent::LEN),
+            AirdropEvent::Minted(_) => (3, MintedEvent::LEN),
+            AirdropEvent::Revealed(_) => (4, RevealedEvent::LEN),
+            AirdropEvent::SeedRevealed(_) => (5, SeedRevealedEvent::LEN),
+        };
+
+        let mut data = vec![0; 2 + body_len];
+        data[0] = EVENT_VERSION;
+        data[1] = event_type;
+
+        match self {
+            AirdropEvent::AirdropInitialized(event) => event.pack_into_slice(&mut data[2..]),
+            AirdropEvent::UserRegistered(event) => event.pack_into_slice(&mut data[2..]),
+            AirdropEvent::Minted(event) => event.pack_into_slice(&mut data[2..]),
+            AirdropEvent::Revealed(event) => event.pack_into_slice(&mut data[2..]),
+            AirdropEvent::SeedRevealed(event) => event.pack_into_slice(&mut data[2..]),
+        }
+
+        data
+    }
+
+    pub fn unpack(data: &[u8]) -> Result<AirdropEvent, ProgramError> {
+        let (version, event_type, body) = match data {
+            [version, event_type, body @ ..] => (*version, *event_type, body),
+            _ => return Err(AirdropError::BadEventData.into()),
+        };
+
+        if version != EVENT_VERSION {
+            return Err(AirdropError::BadEventData.into());
+        }
+
+        match event_type {
+            1 => Ok(AirdropEvent::AirdropInitialized(
+                AirdropInitializedEvent::unpack_from_slice(body)?,
+            )),
+            2 => Ok(AirdropEvent::UserRegistered(
+                UserRegisteredEvent::unpack_from_slice(body)?,
+            )),
+            3 => Ok(AirdropEvent::Minted(MintedEvent::unpack_from_slice(body)?)),
+            4 => Ok(AirdropEvent::Revealed(RevealedEvent::unpack_from_slice(
+                body,
+            )?)),
+            5 => Ok(AirdropEvent::SeedRevealed(
+                SeedRevealedEvent::unpack_from_slice(body)?,
+            )),
+            _ => Err(AirdropError::BadEventData.into()),
+        }
+    }
+
+    /// Write event to program logs
+    pub fn emit(&self) {
+        sol_log_data(&[&self.pack()]);
+    }
+}
+
+impl AirdropInitializedEvent {
+    pub const LEN: usize = 32 + 32 + 32 + 32 + 8 + 8 + 8;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, AirdropInitializedEvent::LEN];
+
+        let (
+            airdrop,
+            airdrop_authority,
+            revenues_wallet,
+            admin_account,
+            airdrop_amount,
+            price,
+            timestamp,
+        ) = mut_array_refs![dst, 32, 32, 32, 32, 8, 8, 8];
+
+        airdrop.copy_from_slice(&self.airdrop.to_bytes());
+        airdrop_authority.copy_from_slice(&self.airdrop_authority.to_bytes());
+        revenues_wallet.copy_from_slice(&self.revenues_wallet.to_bytes());
+        admin_account.copy_from_slice(&self.admin_account.to_bytes());
+        airdrop_amount.copy_from_slice(&self.airdrop_amount.to_le_bytes());
+        price.copy_from_slice(&self.price.to_le_bytes());
+        timestamp.copy_from_slice(&self.timestamp.to_le_bytes());
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<AirdropInitializedEvent, ProgramError> {
+        let src: &[u8; AirdropInitializedEvent::LEN] =
+            src.try_into().or(Err(AirdropError::BadEventData))?;
+
+        let (
+            airdrop_src,
+            airdrop_authority_src,
+            revenues_wallet_src,
+            admin_account_src,
+            airdrop_amount_src,
+            price_src,
+            timestamp_src,
+        ) = array_refs![src, 32, 32, 32, 32, 8, 8, 8];
+
+        Ok(AirdropInitializedEvent {
+            airdrop: Pubkey::new_from_array(*airdrop_src),
+            airdrop_authority: Pubkey::new_from_array(*airdrop_authority_src),
+            revenues_wallet: Pubkey::new_from_array(*revenues_wallet_src),
+            admin_account: Pubkey::new_from_array(*admin_account_src),
+            airdrop_amount: u64::from_le_bytes(*airdrop_amount_src),
+            price: u64::from_le_bytes(*price_src),
+            timestamp: i64::from_le_bytes(*timestamp_src),
+        })
+    }
+}
+
+impl UserRegisteredEvent {
+    pub const LEN: usize = 32 + 32 + 8;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, UserRegisteredEvent::LEN];
+
+        let (airdrop, user, timestamp) = mut_array_refs![dst, 32, 32, 8];
+
+        airdrop.copy_from_slice(&self.airdrop.to_bytes());
+        user.copy_from_slice(&self.user.to_bytes());
+        timestamp.copy_from_slice(&self.timestamp.to_le_bytes());
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<UserRegisteredEvent, ProgramError> {
+        let src: &[u8; UserRegisteredEvent::LEN] =
+            src.try_into().or(Err(AirdropError::BadEventData))?;
+
+        let (airdrop_src, user_src, timestamp_src) = array_refs![src, 32, 32, 8];
+
+        Ok(UserRegisteredEvent {
+            airdrop: Pubkey::new_from_array(*airdrop_src),
+            user: Pubkey::new_from_array(*user_src),
+            timestamp: i64::from_le_bytes(*timestamp_src),
+        })
+    }
+}
+
+impl MintedEvent {
+    pub const LEN: usize = 32 + 32 + 32 + 32 + 8 + 8 + 8;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, MintedEvent::LEN];
+
+        let (airdrop, recipient, mint, payer, airdrop_index, price, timestamp) =
+            mut_array_refs![dst, 32, 32, 32, 32, 8, 8, 8];
+
+        airdrop.copy_from_slice(&self.airdrop.to_bytes());
+        recipient.copy_from_slice(&self.recipient.to_bytes());
+        mint.copy_from_slice(&self.mint.to_bytes());
+        payer.copy_from_slice(&self.payer.to_bytes());
+        airdrop_index.copy_from_slice(&self.airdrop_index.to_le_bytes());
+        price.copy_from_slice(&self.price.to_le_bytes());
+        timestamp.copy_from_slice(&self.timestamp.to_le_bytes());
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<MintedEvent, ProgramError> {
+        let src: &[u8; MintedEvent::LEN] = src.try_into().or(Err(AirdropError::BadEventData))?;
+
+        let (
+            airdrop_src,
+            recipient_src,
+            mint_src,
+            payer_src,
+            airdrop_index_src,
+            price_src,
+            timestamp_src,
+        ) = array_refs![src, 32, 32, 32, 32, 8, 8, 8];
+
+        Ok(MintedEvent {
+            airdrop: Pubkey::new_from_array(*airdrop_src),
+            recipient: Pubkey::new_from_array(*recipient_src),
+            mint: Pubkey::new_from_array(*mint_src),
+            payer: Pubkey::new_from_array(*payer_src),
+            airdrop_index: u64::from_le_bytes(*airdrop_index_src),
+            price: u64::from_le_bytes(*price_src),
+            timestamp: i64::from_le_bytes(*timestamp_src),
+        })
+    }
+}
+
+impl RevealedEvent {
+    pub const LEN: usize = 32 + 32 + 8 + 8;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, RevealedEvent::LEN];
+
+        let (airdrop, mint, airdrop_index, timestamp) = mut_array_refs![dst, 32, 32, 8, 8];
+
+        airdrop.copy_from_slice(&self.airdrop.to_bytes());
+        mint.copy_from_slice(&self.mint.to_bytes());
+        airdrop_index.copy_from_slice(&self.airdrop_index.to_le_bytes());
+        timestamp.copy_from_slice(&self.timestamp.to_le_bytes());
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<RevealedEvent, ProgramError> {
+        let src: &[u8; RevealedEvent::LEN] = src.try_into().or(Err(AirdropError::BadEventData))?;
+
+        let (airdrop_src, mint_src, airdrop_index_src, timestamp_src) =
+            array_refs![src, 32, 32, 8, 8];
+
+        Ok(RevealedEvent {
+            airdrop: Pubkey::new_from_array(*airdrop_src),
+            mint: Pubkey::new_from_array(*mint_src),
+            airdrop_index: u64::from_le_bytes(*airdrop_index_src),
+            timestamp: i64::from_le_bytes(*timestamp_src),
+        })
+    }
+}
+
+impl SeedRevealedEvent {
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8;
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, SeedRevealedEvent::LEN];
+
+        let (airdrop, seed, slot_hash, starting_offset, timestamp) =
+            mut_array_refs![dst, 32, 32, 32, 8, 8];
+
+        airdrop.copy_from_slice(&self.airdrop.to_bytes());
+        seed.copy_from_slice(&self.seed);
+        slot_hash.copy_from_slice(&self.slot_hash);
+        starting_offset.copy_from_slice(&self.starting_offset.to_le_bytes());
+        timestamp.copy_from_slice(&self.timestamp.to_le_bytes());
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<SeedRevealedEvent, ProgramError> {
+        let src: &[u8; SeedRevealedEvent::LEN] =
+            src.try_into().or(Err(AirdropError::BadEventData))?;
+
+        let (airdrop_src, seed_src, slot_hash_src, starting_offset_src, timestamp_src) =
+            array_refs![src, 32, 32, 32, 8, 8];
+
+        Ok(SeedRevealedEvent {
+            airdrop: Pubkey::new_from_array(*airdrop_src),
+            seed: *seed_src,
+            slot_hash: *slot_hash_src,
+            starting_offset: u64::from_le_bytes(*starting_offset_src),
+            timestamp: i64::from_le_bytes(*timestamp_src),
+        })
+    }
+}
@@ -1,21 +1,112 @@
-use std::convert::TryInto;
+use std::convert::TryFrom;
 
-use arrayref::array_refs;
-use solana_program::program_error::ProgramError;
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+use spl_associated_token_account::get_associated_token_address;
 
-use crate::error::AirdropError;
+use crate::{
+    error::AirdropError,
+    pda::{find_airdrop_user_data, find_metadata_account, find_mint, find_mint_authority},
+    state::{AirdropConfig, ConfigLine},
+};
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct InitializeAirdropArgs {
     pub airdrop_amount: u64,
-    pub metadata_prefix: [u8; 32],
-    pub symbol: [u8; 8],
     pub price: u64,
+    /// Token number is the airdrop index plus `index_offset`
+    pub index_offset: u64,
+    /// Token number is zero padded to at least `index_padding` digits
+    pub index_padding: u8,
+    /// Airdrop index of every token is drawn at random instead of assigned sequentially
+    pub shuffled: bool,
+    /// Hash of metadata files in their original order. Zeros if fair mint is not used
+    pub provenance_hash: Hash,
+    /// SHA-256 of the seed revealed by `RevealSeed` after `EndSale`.
+    /// Zeros if fair mint is not used
+    pub seed_commitment: Hash,
+    /// Account with name and URI of every token, uploaded by `AddConfigLines`.
+    /// Zeros if name and URI are templated
+    pub config_lines: Pubkey,
+    /// Mints are program addresses derived from the airdrop and the amount of minted tokens,
+    /// see `pda::find_mint`, instead of keypairs signing `MintOne`
+    pub pda_mints: bool,
+    /// Recipient must sign `InitializeAirdropUser` and `MintOne`
+    pub require_user_signature: bool,
+    /// Recipient must be the fee payer of `InitializeAirdropUser` and payer of `MintOne`
+    pub require_user_payer: bool,
+    /// Token metadata URI is `base_uri` followed by the token number and `uri_extension`
+    pub base_uri: String,
+    pub uri_extension: String,
+    /// Token name is `name_template` with `{}` replaced by the token number
+    pub name_template: String,
+    pub symbol: String,
+    /// Name template of tokens until reveal. Empty if metadata is not hidden
+    pub hidden_name_template: String,
+    /// URI of tokens until reveal. Empty if metadata is not hidden
+    pub hidden_uri: String,
 }
 
+impl InitializeAirdropArgs {
+    /// Account size of the airdrop config created with these arguments
+    pub fn config_space(&self) -> usize {
+        AirdropConfig::space(
+            &[
+                &self.base_uri,
+                &self.uri_extension,
+                &self.name_template,
+                &self.symbol,
+                &self.hidden_name_template,
+                &self.hidden_uri,
+            ],
+            AirdropConfig::index_map_len(self.airdrop_amount, self.shuffled),
+        )
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct InitializeAirdropUserDataArgs {}
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct MintOneArgs {}
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct QuoteArgs {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevealArgs {
+    /// Airdrop indices of revealed tokens, in order of metadata accounts
+    pub airdrop_indices: Vec<u64>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RevealSeedArgs {
+    /// Seed with SHA-256 equal to the seed commitment of the airdrop
+    pub seed: [u8; 32],
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CommitDrawArgs {}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EndSaleArgs {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddConfigLinesArgs {
+    /// Airdrop index of the first line
+    pub first_index: u64,
+    /// Lines of consecutive airdrop indices
+    pub lines: Vec<ConfigLine>,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AirdropInstruction {
     ///
     /// Accounts required:
@@ -32,7 +123,7 @@ pub enum AirdropInstruction {
     ///
     /// Accounts required:
     /// 0. `[writeable]`. User data account. Used to store user data
-    /// 1. `[]`. User. Wallet that will use the account
+    /// 1. `[]`. User. Wallet that will use the account. Signer if the airdrop requires it
     /// 2. `[writeable]`. Airdrop. Airdrop that data account will be associated with
     /// 3. `[]`. Rent sysvar
     /// 4. `[signer]`. Fee payer. Wallet that is paying fee for creating an account
@@ -43,9 +134,12 @@ pub enum AirdropInstruction {
     /// Accounts required:
     /// 0. `[writeable]`. Airdrop account
     /// 1. `[writeable]`. User data account
-    /// 2. `[signer, writeable]`. SPL mint account. Represents a token in solana blockchain
-    /// 3. `[]`. Recipient. Wallet that owns user data account and will recieve a token
-    /// 4. `[writeable]`. Recipient token account.
+    /// 2. `[signer, writeable]`. SPL mint account. Represents a token in solana blockchain.
+    ///    Not a signer if the airdrop has `pda_mints`, then it is `pda::find_mint`
+    ///    of the airdrop and the amount of minted tokens
+    /// 3. `[]`. Recipient. Wallet that owns user data account and will recieve a token.
+    ///    Signer if the airdrop requires it
+    /// 4. `[writeable]`. Recipient associated token account, created unless it already exists
     /// 5. `[writeable]`. Token metadata account. Holds NFT metadata
     /// 6. `[]`. Mint authority
     /// 7. `[]`. System program
@@ -58,30 +152,286 @@ pub enum AirdropInstruction {
     /// 14. `[signer]`. Airdrop authority. Authority owner must verify that user is eglible for airdrop
     /// 15. `[]`. Admin account
     /// 16. `[writeable]`. Revenue wallet
+    /// 17. `[]`. Slot hashes sysvar. Only passed if the airdrop is shuffled. Holds the hash
+    ///     of the slot committed by `CommitDraw`, which seeds the airdrop index
+    /// 18. `[]`. Config lines account. Only passed if the airdrop has config lines,
+    ///     takes position 17 if the airdrop is not shuffled
+    ///
+    /// Shuffled airdrops require a draw committed by `CommitDraw` in an earlier slot,
+    /// the draw is consumed by the mint
+    ///
+    /// Return data: `MintOneResult` with assigned airdrop index, mint and charged price
     MintOne(MintOneArgs),
+
+    ///
+    /// Read-only check whether recipient is able to mint right now. Does not modify any account
+    ///
+    /// Accounts required:
+    /// 0. `[]`. Airdrop account
+    /// 1. `[]`. User data account. May be not created yet
+    /// 2. `[]`. Recipient
+    /// 3. `[]`. Clock sysvar
+    ///
+    /// Return data: `QuoteResult` with eligibility status, price and user timeout
+    Quote(QuoteArgs),
+
+    ///
+    /// Replaces hidden metadata of minted tokens with the final one
+    /// and transfers update authority to admin account
+    ///
+    /// Accounts required:
+    /// 0. `[]`. Airdrop account
+    /// 1. `[signer]`. Admin account
+    /// 2. `[]`. Mint authority
+    /// 3. `[]`. Metaplex token metadata program
+    /// 4. `[writeable]`. Token metadata account of every airdrop index in arguments, in order
+    Reveal(RevealArgs),
+
+    ///
+    /// Checks the seed against the commitment of a fair mint airdrop ended by `EndSale`.
+    /// Stores the starting offset of metadata files derived from the seed and the hash
+    /// of the sale end slot. The hash is unknown when `EndSale` is signed, so the admin
+    /// knowing the seed can not choose the offset. It is available in later slots until it
+    /// leaves the slot hashes sysvar
+    ///
+    /// Accounts required:
+    /// 0. `[writeable]`. Airdrop account
+    /// 1. `[signer]`. Admin account
+    /// 2. `[]`. Slot hashes sysvar
+    RevealSeed(RevealSeedArgs),
+
+    ///
+    /// Writes name and URI of consecutive tokens to the config lines account of the airdrop.
+    /// Config lines account must be created with `ConfigLines::space` bytes and owned by the
+    /// program, it is initialized by the first call. Lines may be changed until the first mint
+    ///
+    /// Accounts required:
+    /// 0. `[]`. Airdrop account
+    /// 1. `[writeable]`. Config lines account
+    /// 2. `[signer]`. Admin account
+    AddConfigLines(AddConfigLinesArgs),
+
+    ///
+    /// Commits the recipient of a shuffled airdrop to the hash of the current slot,
+    /// which draws the airdrop index of the next `MintOne`. The hash is unknown when
+    /// the transaction is signed, so neither the recipient nor the payer can choose it.
+    /// Checks mint eligibility and locks the recipient as a mint does, the draw is available
+    /// to `MintOne` in later slots until it leaves the slot hashes sysvar
+    ///
+    /// Accounts required:
+    /// 0. `[]`. Airdrop account
+    /// 1. `[writeable]`. User data account
+    /// 2. `[]`. Recipient
+    /// 3. `[signer]`. Airdrop authority
+    /// 4. `[]`. Clock sysvar
+    CommitDraw(CommitDrawArgs),
+
+    ///
+    /// Ends the sale of a fair mint airdrop, remaining tokens are never minted. The admin
+    /// may end the sale before the supply is exhausted. Fixes the current slot, whose hash
+    /// `RevealSeed` mixes into the starting offset. The sale may be ended again only once
+    /// the hash leaves the slot hashes sysvar without the seed being revealed
+    ///
+    /// Accounts required:
+    /// 0. `[writeable]`. Airdrop account
+    /// 1. `[signer]`. Admin account
+    EndSale(EndSaleArgs),
+}
+
+impl AirdropInstruction {
+    /// Fails with `BadInstructionArgument` on strings longer than 255 bytes
+    /// and on more than 255 airdrop indices or config lines
+    pub fn pack(&self) -> Result<Vec<u8>, ProgramError> {
+        let data = match self {
+            AirdropInstruction::InitializeAirdrop(args) => {
+                let mut data = vec![0; 1 + 125];
+                data[0] = 1;
+                let body = array_mut_ref![data, 1, 125];
+
+                let (
+                    airdrop_amount,
+                    price,
+                    index_offset,
+                    index_padding,
+                    shuffled,
+                    provenance_hash,
+                    seed_commitment,
+                    config_lines,
+                    pda_mints,
+                    require_user_signature,
+                    require_user_payer,
+                ) = mut_array_refs![body, 8, 8, 8, 1, 1, 32, 32, 32, 1, 1, 1];
+
+                airdrop_amount.copy_from_slice(&args.airdrop_amount.to_le_bytes());
+                price.copy_from_slice(&args.price.to_le_bytes());
+                index_offset.copy_from_slice(&args.index_offset.to_le_bytes());
+                index_padding[0] = args.index_padding;
+                shuffled[0] = args.shuffled as u8;
+                provenance_hash.copy_from_slice(args.provenance_hash.as_ref());
+                seed_commitment.copy_from_slice(args.seed_commitment.as_ref());
+                config_lines.copy_from_slice(args.config_lines.as_ref());
+                pda_mints[0] = args.pda_mints as u8;
+                require_user_signature[0] = args.require_user_signature as u8;
+                require_user_payer[0] = args.require_user_payer as u8;
+
+                for value in [
+                    &args.base_uri,
+                    &args.uri_extension,
+                    &args.name_template,
+                    &args.symbol,
+                    &args.hidden_name_template,
+                    &args.hidden_uri,
+                ] {
+                    pack_string(&mut data, value)?;
+                }
+
+                data
+            }
+            AirdropInstruction::InitializeAirdropUser(_) => vec![2],
+            AirdropInstruction::MintOne(_) => vec![3],
+            AirdropInstruction::Quote(_) => vec![4],
+            AirdropInstruction::Reveal(args) => {
+                let mut data = vec![5, pack_count(args.airdrop_indices.len())?];
+                for airdrop_index in &args.airdrop_indices {
+                    data.extend_from_slice(&airdrop_index.to_le_bytes());
+                }
+
+                data
+            }
+            AirdropInstruction::RevealSeed(args) => [&[6], &args.seed[..]].concat(),
+            AirdropInstruction::AddConfigLines(args) => {
+                let mut data = vec![7];
+                data.extend_from_slice(&args.first_index.to_le_bytes());
+                data.push(pack_count(args.lines.len())?);
+                for line in &args.lines {
+                    pack_string(&mut data, &line.name)?;
+                    pack_string(&mut data, &line.uri)?;
+                }
+
+                data
+            }
+            AirdropInstruction::CommitDraw(_) => vec![8],
+            AirdropInstruction::EndSale(_) => vec![9],
+        };
+
+        Ok(data)
+    }
+}
+
+/// Length or count of a `u8` prefixed field
+fn pack_count(len: usize) -> Result<u8, ProgramError> {
+    u8::try_from(len).or(Err(AirdropError::BadInstructionArgument.into()))
+}
+
+fn pack_string(data: &mut Vec<u8>, value: &str) -> Result<(), ProgramError> {
+    data.push(pack_count(value.len())?);
+    data.extend_from_slice(value.as_bytes());
+
+    Ok(())
 }
 
+/// Layout: `[airdrop amount: u64][price: u64][index offset: u64][index padding: u8]`
+/// `[shuffled: u8][provenance hash: 32][seed commitment: 32][config lines: 32][pda mints: u8]`
+/// `[require user signature: u8][require user payer: u8]` followed by base URI, URI extension,
+/// name template, symbol, hidden name template and hidden URI, each as
+/// `[length: u8][utf-8 bytes]`. All integers are little endian, flags are 0 or 1
 fn parse_initialize_airdrop_args(body: &[u8]) -> Result<InitializeAirdropArgs, ProgramError> {
-    let body_sized: &[u8; 56] = body
-        .try_into()
-        .or(Err(AirdropError::BadInstructionArgument))?;
+    if body.len() < 125 {
+        return Err(AirdropError::BadInstructionArgument.into());
+    }
 
-    let (airdrop_amount_array, metadata_prefix_array, symbol_array, price_array) =
-        array_refs!(body_sized, 8, 32, 8, 8);
+    let (numbers, strings) = body.split_at(125);
+    let (
+        airdrop_amount_array,
+        price_array,
+        index_offset_array,
+        index_padding_array,
+        shuffled_array,
+        provenance_hash_array,
+        seed_commitment_array,
+        config_lines_array,
+        pda_mints_array,
+        require_user_signature_array,
+        require_user_payer_array,
+    ) = array_refs!(
+        array_ref![numbers, 0, 125],
+        8,
+        8,
+        8,
+        1,
+        1,
+        32,
+        32,
+        32,
+        1,
+        1,
+        1
+    );
 
     let airdrop_amount = u64::from_le_bytes(*airdrop_amount_array);
-    let metadata_prefix = *metadata_prefix_array;
-    let symbol = *symbol_array;
     let price = u64::from_le_bytes(*price_array);
+    let index_offset = u64::from_le_bytes(*index_offset_array);
+    let index_padding = index_padding_array[0];
+    let parse_flag = |array: &[u8; 1]| match array[0] {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(AirdropError::BadInstructionArgument),
+    };
+    let shuffled = parse_flag(shuffled_array)?;
+    let provenance_hash = Hash::new_from_array(*provenance_hash_array);
+    let seed_commitment = Hash::new_from_array(*seed_commitment_array);
+    let config_lines = Pubkey::new_from_array(*config_lines_array);
+    let pda_mints = parse_flag(pda_mints_array)?;
+    let require_user_signature = parse_flag(require_user_signature_array)?;
+    let require_user_payer = parse_flag(require_user_payer_array)?;
+
+    let (base_uri, strings) = parse_string(strings)?;
+    let (uri_extension, strings) = parse_string(strings)?;
+    let (name_template, strings) = parse_string(strings)?;
+    let (symbol, strings) = parse_string(strings)?;
+    let (hidden_name_template, strings) = parse_string(strings)?;
+    let (hidden_uri, strings) = parse_string(strings)?;
+
+    if !strings.is_empty() {
+        return Err(AirdropError::BadInstructionArgument.into());
+    }
 
     Ok(InitializeAirdropArgs {
         airdrop_amount,
-        metadata_prefix,
-        symbol,
         price,
+        index_offset,
+        index_padding,
+        shuffled,
+        provenance_hash,
+        seed_commitment,
+        config_lines,
+        pda_mints,
+        require_user_signature,
+        require_user_payer,
+        base_uri,
+        uri_extension,
+        name_template,
+        symbol,
+        hidden_name_template,
+        hidden_uri,
     })
 }
 
+fn parse_string(src: &[u8]) -> Result<(String, &[u8]), ProgramError> {
+    let (len, rest) = src
+        .split_first()
+        .ok_or(AirdropError::BadInstructionArgument)?;
+    let len = *len as usize;
+    if rest.len() < len {
+        return Err(AirdropError::BadInstructionArgument.into());
+    }
+
+    let (value, rest) = rest.split_at(len);
+    let value = std::str::from_utf8(value).or(Err(AirdropError::BadMetadataString))?;
+
+    Ok((value.to_string(), rest))
+}
+
 fn parse_initialize_airdrop_user_args(
     _body: &[u8],
 ) -> Result<InitializeAirdropUserDataArgs, ProgramError> {
@@ -92,6 +442,82 @@ fn parse_mint_one_args(_body: &[u8]) -> Result<MintOneArgs, ProgramError> {
     Ok(MintOneArgs {})
 }
 
+fn parse_quote_args(_body: &[u8]) -> Result<QuoteArgs, ProgramError> {
+    Ok(QuoteArgs {})
+}
+
+/// Layout: `[count: u8]` followed by `count` airdrop indices, `u64` little endian each.
+/// At least one index is required
+fn parse_reveal_args(body: &[u8]) -> Result<RevealArgs, ProgramError> {
+    let (count, indices) = body
+        .split_first()
+        .ok_or(AirdropError::BadInstructionArgument)?;
+
+    if *count == 0 || indices.len() != *count as usize * 8 {
+        return Err(AirdropError::BadInstructionArgument.into());
+    }
+
+    let airdrop_indices = indices
+        .chunks_exact(8)
+        .map(|chunk| u64::from_le_bytes(*array_ref![chunk, 0, 8]))
+        .collect();
+
+    Ok(RevealArgs { airdrop_indices })
+}
+
+/// Layout: `[seed: 32]`
+fn parse_reveal_seed_args(body: &[u8]) -> Result<RevealSeedArgs, ProgramError> {
+    if body.len() != 32 {
+        return Err(AirdropError::BadInstructionArgument.into());
+    }
+
+    Ok(RevealSeedArgs {
+        seed: *array_ref![body, 0, 32],
+    })
+}
+
+/// Layout: `[first index: u64][count: u8]` followed by `count` lines,
+/// each as name and URI encoded `[length: u8][utf-8 bytes]`
+fn parse_add_config_lines_args(body: &[u8]) -> Result<AddConfigLinesArgs, ProgramError> {
+    if body.len() < 9 {
+        return Err(AirdropError::BadInstructionArgument.into());
+    }
+
+    let (header, mut rest) = body.split_at(9);
+    let (first_index, count) = array_refs![array_ref![header, 0, 9], 8, 1];
+    if count[0] == 0 {
+        return Err(AirdropError::BadInstructionArgument.into());
+    }
+
+    let mut lines = Vec::with_capacity(count[0] as usize);
+    for _ in 0..count[0] {
+        let (name, next) = parse_string(rest)?;
+        let (uri, next) = parse_string(next)?;
+        lines.push(ConfigLine { name, uri });
+        rest = next;
+    }
+
+    if !rest.is_empty() {
+        return Err(AirdropError::BadInstructionArgument.into());
+    }
+
+    Ok(AddConfigLinesArgs {
+        first_index: u64::from_le_bytes(*first_index),
+        lines,
+    })
+}
+
+fn parse_commit_draw_args(_body: &[u8]) -> Result<CommitDrawArgs, ProgramError> {
+    Ok(CommitDrawArgs {})
+}
+
+fn parse_end_sale_args(_body: &[u8]) -> Result<EndSaleArgs, ProgramError> {
+    Ok(EndSaleArgs {})
+}
+
+/// Parses instruction data. Fails with `InvalidInstructionData` on empty input,
+/// `BadInstructionId` on unknown tag, `BadInstructionArgument` on malformed arguments
+/// and `BadMetadataString` on metadata strings that are not valid UTF-8
 pub fn deserialize_instruction_data(
     instruction_data: &[u8],
 ) -> Result<AirdropInstruction, ProgramError> {
@@ -107,6 +533,317 @@ pub fn deserialize_instruction_data(
             parse_initialize_airdrop_user_args(body)?,
         )),
         3 => Ok(AirdropInstruction::MintOne(parse_mint_one_args(body)?)),
+        4 => Ok(AirdropInstruction::Quote(parse_quote_args(body)?)),
+        5 => Ok(AirdropInstruction::Reveal(parse_reveal_args(body)?)),
+        6 => Ok(AirdropInstruction::RevealSeed(parse_reveal_seed_args(
+            body,
+        )?)),
+        7 => Ok(AirdropInstruction::AddConfigLines(
+            parse_add_config_lines_args(body)?,
+        )),
+        8 => Ok(AirdropInstruction::CommitDraw(parse_commit_draw_args(
+            body,
+        )?)),
+        9 => Ok(AirdropInstruction::EndSale(parse_end_sale_args(body)?)),
         _ => Err(AirdropError::BadInstructionId.into()),
     }
 }
+
+/// Instructions without strings and lists always pack
+const FIXED_SIZE: &str = "instruction of fixed size failed to pack";
+
+/// Creates `InitializeAirdrop` instruction. Airdrop account must be already created
+/// with `args.config_space()` bytes of space and owned by the program.
+/// Fails with `BadInstructionArgument` on metadata strings longer than 255 bytes
+pub fn initialize_airdrop(
+    airdrop: &Pubkey,
+    airdrop_authority: &Pubkey,
+    revenues_wallet: &Pubkey,
+    admin_account: &Pubkey,
+    fee_payer: &Pubkey,
+    args: InitializeAirdropArgs,
+) -> Result<Instruction, ProgramError> {
+    let (mint_authority, _) = find_mint_authority(airdrop);
+
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*airdrop, false),
+            AccountMeta::new_readonly(*airdrop_authority, false),
+            AccountMeta::new(mint_authority, false),
+            AccountMeta::new_readonly(*revenues_wallet, false),
+            AccountMeta::new_readonly(*admin_account, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new(*fee_payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: AirdropInstruction::InitializeAirdrop(args).pack()?,
+    })
+}
+
+/// Creates `InitializeAirdropUser` instruction
+pub fn initialize_airdrop_user(airdrop: &Pubkey, user: &Pubkey, fee_payer: &Pubkey) -> Instruction {
+    let (user_data, _) = find_airdrop_user_data(airdrop, user);
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(user_data, false),
+            AccountMeta::new_readonly(*user, false),
+            AccountMeta::new(*airdrop, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new(*fee_payer, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: AirdropInstruction::InitializeAirdropUser(InitializeAirdropUserDataArgs {})
+            .pack()
+            .expect(FIXED_SIZE),
+    }
+}
+
+/// Creates `MintOne` instruction. `mint` is a fresh keypair that must sign the transaction.
+/// `shuffled` is the flag of the airdrop config, shuffled airdrops read the slot hashes sysvar
+#[allow(clippy::too_many_arguments)]
+pub fn mint_one(
+    airdrop: &Pubkey,
+    mint: &Pubkey,
+    user: &Pubkey,
+    payer: &Pubkey,
+    airdrop_authority: &Pubkey,
+    admin_account: &Pubkey,
+    revenues_wallet: &Pubkey,
+    shuffled: bool,
+) -> Instruction {
+    let (user_data, _) = find_airdrop_user_data(airdrop, user);
+    let (mint_authority, _) = find_mint_authority(airdrop);
+    let (token_metadata, _) = find_metadata_account(mint);
+
+    let mut accounts = vec![
+        AccountMeta::new(*airdrop, false),
+        AccountMeta::new(user_data, false),
+        AccountMeta::new(*mint, true),
+        AccountMeta::new_readonly(*user, false),
+        AccountMeta::new(get_associated_token_address(user, mint), false),
+        AccountMeta::new(token_metadata, false),
+        AccountMeta::new_readonly(mint_authority, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::clock::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        AccountMeta::new_readonly(metaplex_token_metadata::id(), false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(*airdrop_authority, true),
+        AccountMeta::new_readonly(*admin_account, false),
+        AccountMeta::new(*revenues_wallet, false),
+    ];
+    if shuffled {
+        accounts.push(AccountMeta::new_readonly(sysvar::slot_hashes::id(), false));
+    }
+
+    Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: AirdropInstruction::MintOne(MintOneArgs {})
+            .pack()
+            .expect(FIXED_SIZE),
+    }
+}
+
+/// Creates `MintOne` instruction of an airdrop with `pda_mints`, minting the token
+/// after `minted` tokens. Fails if another token is minted first
+#[allow(clippy::too_many_arguments)]
+pub fn mint_one_with_pda_mint(
+    airdrop: &Pubkey,
+    minted: u64,
+    user: &Pubkey,
+    payer: &Pubkey,
+    airdrop_authority: &Pubkey,
+    admin_account: &Pubkey,
+    revenues_wallet: &Pubkey,
+    shuffled: bool,
+) -> Instruction {
+    let (mint, _) = find_mint(airdrop, minted);
+    let mut instruction = mint_one(
+        airdrop,
+        &mint,
+        user,
+        payer,
+        airdrop_authority,
+        admin_account,
+        revenues_wallet,
+        shuffled,
+    );
+    instruction.accounts[2].is_signer = false;
+
+    instruction
+}
+
+/// Creates `MintOne` instruction of an airdrop reading token metadata from `config_lines`
+#[allow(clippy::too_many_arguments)]
+pub fn mint_one_with_config_lines(
+    airdrop: &Pubkey,
+    mint: &Pubkey,
+    user: &Pubkey,
+    payer: &Pubkey,
+    airdrop_authority: &Pubkey,
+    admin_account: &Pubkey,
+    revenues_wallet: &Pubkey,
+    shuffled: bool,
+    config_lines: &Pubkey,
+) -> Instruction {
+    let instruction = mint_one(
+        airdrop,
+        mint,
+        user,
+        payer,
+        airdrop_authority,
+        admin_account,
+        revenues_wallet,
+        shuffled,
+    );
+
+    with_config_lines(instruction, config_lines)
+}
+
+/// Adds `config_lines` account to `MintOne` instruction of `mint_one` or `mint_one_with_pda_mint`
+pub fn with_config_lines(mut instruction: Instruction, config_lines: &Pubkey) -> Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(*config_lines, false));
+
+    instruction
+}
+
+/// Makes `user` sign `InitializeAirdropUser` or `MintOne` instruction, which airdrops with
+/// `require_user_signature` need if the user is not the payer
+pub fn with_user_signer(mut instruction: Instruction, user: &Pubkey) -> Instruction {
+    for account in &mut instruction.accounts {
+        if account.pubkey == *user {
+            account.is_signer = true;
+        }
+    }
+
+    instruction
+}
+
+/// Creates `Quote` instruction. Intended to be simulated, result is in return data
+pub fn quote(airdrop: &Pubkey, user: &Pubkey) -> Instruction {
+    let (user_data, _) = find_airdrop_user_data(airdrop, user);
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*airdrop, false),
+            AccountMeta::new_readonly(user_data, false),
+            AccountMeta::new_readonly(*user, false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ],
+        data: AirdropInstruction::Quote(QuoteArgs {})
+            .pack()
+            .expect(FIXED_SIZE),
+    }
+}
+
+/// Creates `Reveal` instruction for `tokens`, pairs of airdrop index and mint.
+/// Fails with `BadInstructionArgument` on more than 255 tokens
+pub fn reveal(
+    airdrop: &Pubkey,
+    admin_account: &Pubkey,
+    tokens: &[(u64, Pubkey)],
+) -> Result<Instruction, ProgramError> {
+    let (mint_authority, _) = find_mint_authority(airdrop);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*airdrop, false),
+        AccountMeta::new_readonly(*admin_account, true),
+        AccountMeta::new_readonly(mint_authority, false),
+        AccountMeta::new_readonly(metaplex_token_metadata::id(), false),
+    ];
+    accounts.extend(tokens.iter().map(|(_, mint)| {
+        let (token_metadata, _) = find_metadata_account(mint);
+        AccountMeta::new(token_metadata, false)
+    }));
+
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts,
+        data: AirdropInstruction::Reveal(RevealArgs {
+            airdrop_indices: tokens
+                .iter()
+                .map(|(airdrop_index, _)| *airdrop_index)
+                .collect(),
+        })
+        .pack()?,
+    })
+}
+
+/// Creates `RevealSeed` instruction
+pub fn reveal_seed(airdrop: &Pubkey, admin_account: &Pubkey, seed: [u8; 32]) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*airdrop, false),
+            AccountMeta::new_readonly(*admin_account, true),
+            AccountMeta::new_readonly(sysvar::slot_hashes::id(), false),
+        ],
+        data: AirdropInstruction::RevealSeed(RevealSeedArgs { seed })
+            .pack()
+            .expect(FIXED_SIZE),
+    }
+}
+
+/// Creates `EndSale` instruction
+pub fn end_sale(airdrop: &Pubkey, admin_account: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new(*airdrop, false),
+            AccountMeta::new_readonly(*admin_account, true),
+        ],
+        data: AirdropInstruction::EndSale(EndSaleArgs {})
+            .pack()
+            .expect(FIXED_SIZE),
+    }
+}
+
+/// Creates `AddConfigLines` instruction writing `lines` from airdrop index `first_index`.
+/// Fails with `BadInstructionArgument` on more than 255 lines or on names and URIs
+/// longer than 255 bytes
+pub fn add_config_lines(
+    airdrop: &Pubkey,
+    config_lines: &Pubkey,
+    admin_account: &Pubkey,
+    first_index: u64,
+    lines: Vec<ConfigLine>,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*airdrop, false),
+            AccountMeta::new(*config_lines, false),
+            AccountMeta::new_readonly(*admin_account, true),
+        ],
+        data: AirdropInstruction::AddConfigLines(AddConfigLinesArgs { first_index, lines })
+            .pack()?,
+    })
+}
+
+/// Creates `CommitDraw` instruction drawing the index of the next shuffled mint to `user`
+pub fn commit_draw(airdrop: &Pubkey, user: &Pubkey, airdrop_authority: &Pubkey) -> Instruction {
+    let (user_data, _) = find_airdrop_user_data(airdrop, user);
+
+    Instruction {
+        program_id: crate::id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*airdrop, false),
+            AccountMeta::new(user_data, false),
+            AccountMeta::new_readonly(*user, false),
+            AccountMeta::new_readonly(*airdrop_authority, true),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+        ],
+        data: AirdropInstruction::CommitDraw(CommitDrawArgs {})
+            .pack()
+            .expect(FIXED_SIZE),
+    }
+}
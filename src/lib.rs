@@ -1,7 +1,12 @@
+#[macro_use]
+mod macros;
+
 pub mod error;
+pub mod event;
 pub mod instruction;
 pub mod pda;
 pub mod processor;
+pub mod return_data;
 pub mod state;
 pub mod util;
 
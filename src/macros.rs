@@ -0,0 +1,8 @@
+/// Logs progress of instruction processing. Compiled out with `no-trace` feature,
+/// failures are still reported with `msg!` and `PrintProgramError`
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(not(feature = "no-trace"))]
+        solana_program::msg!($($arg)*);
+    };
+}
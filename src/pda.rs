@@ -1,6 +1,7 @@
+use metaplex_token_metadata::state::PREFIX;
 use solana_program::pubkey::Pubkey;
 
-use crate::state::{MINT_AUTHORITY, USER_DATA};
+use crate::state::{MINT, MINT_AUTHORITY, USER_DATA};
 
 pub fn find_airdrop_user_data(airdrop_config: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(
@@ -15,3 +16,26 @@ pub fn find_mint_authority(airdrop_config: &Pubkey) -> (Pubkey, u8) {
         &crate::id(),
     )
 }
+
+/// Mint of the token minted after `minted` tokens of an airdrop with `pda_mints`
+pub fn find_mint(airdrop_config: &Pubkey, minted: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            MINT.as_bytes(),
+            airdrop_config.as_ref(),
+            &minted.to_le_bytes(),
+        ],
+        &crate::id(),
+    )
+}
+
+pub fn find_metadata_account(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            metaplex_token_metadata::id().as_ref(),
+            mint.as_ref(),
+        ],
+        &metaplex_token_metadata::id(),
+    )
+}
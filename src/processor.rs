@@ -1,42 +1,46 @@
+use metaplex_token_metadata::state::{MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     clock::Clock,
     entrypoint::ProgramResult,
+    hash::Hash,
     msg,
     program_error::ProgramError,
     program_pack::IsInitialized,
     pubkey::Pubkey,
     rent::Rent,
+    slot_hashes::MAX_ENTRIES,
     system_program,
-    sysvar::Sysvar,
+    sysvar::{self, Sysvar},
 };
+use spl_associated_token_account::get_associated_token_address;
 
 use crate::{
     error::AirdropError,
-    instruction::deserialize_instruction_data,
-    pda::{find_airdrop_user_data, find_mint_authority},
-    state::{AirdropConfig, AirdropUserData},
+    instruction::{
+        deserialize_instruction_data, AddConfigLinesArgs, InitializeAirdropArgs, RevealArgs,
+        RevealSeedArgs,
+    },
+    pda::{find_airdrop_user_data, find_metadata_account, find_mint, find_mint_authority},
+    return_data::{QuoteResult, QuoteStatus, UNKNOWN_INDEX},
+    state::{AirdropConfig, AirdropUserData, ConfigLines, NAME_PLACEHOLDER},
     util::{
-        process_airdrop_one_logic, process_initialize_airdrop_logic,
-        process_initialize_airdrop_user_account_logic,
+        committed_slot_hash, index_seed, metadata_name, metadata_uri,
+        process_add_config_lines_logic, process_airdrop_one_logic, process_commit_draw_logic,
+        process_end_sale_logic, process_initialize_airdrop_logic,
+        process_initialize_airdrop_user_account_logic, process_reveal_logic,
+        process_reveal_seed_logic, token_number,
     },
 };
 
 pub fn process_instruction<'a>(
     program_id: &Pubkey,
-    accounts: &'a [AccountInfo<'a>],
+    accounts: &[AccountInfo<'a>],
     instruction_data: &[u8],
 ) -> ProgramResult {
     match deserialize_instruction_data(instruction_data)? {
         crate::instruction::AirdropInstruction::InitializeAirdrop(args) => {
-            process_initialize_airdrop(
-                program_id,
-                accounts,
-                args.airdrop_amount,
-                args.metadata_prefix,
-                args.symbol,
-                args.price,
-            )
+            process_initialize_airdrop(program_id, accounts, args)
         }
         crate::instruction::AirdropInstruction::InitializeAirdropUser(_) => {
             process_initialize_airdrop_user(program_id, accounts)
@@ -44,16 +48,29 @@ pub fn process_instruction<'a>(
         crate::instruction::AirdropInstruction::MintOne(_) => {
             process_mint_one(program_id, accounts)
         }
+        crate::instruction::AirdropInstruction::Quote(_) => process_quote(program_id, accounts),
+        crate::instruction::AirdropInstruction::Reveal(args) => {
+            process_reveal(program_id, accounts, args)
+        }
+        crate::instruction::AirdropInstruction::RevealSeed(args) => {
+            process_reveal_seed(program_id, accounts, args)
+        }
+        crate::instruction::AirdropInstruction::AddConfigLines(args) => {
+            process_add_config_lines(program_id, accounts, args)
+        }
+        crate::instruction::AirdropInstruction::CommitDraw(_) => {
+            process_commit_draw(program_id, accounts)
+        }
+        crate::instruction::AirdropInstruction::EndSale(_) => {
+            process_end_sale(program_id, accounts)
+        }
     }
 }
 
 fn process_initialize_airdrop<'a>(
     program_id: &Pubkey,
-    accounts: &'a [AccountInfo<'a>],
-    airdrop_amount: u64,
-    metadata_prefix: [u8; 32],
-    symbol: [u8; 8],
-    price: u64,
+    accounts: &[AccountInfo<'a>],
+    args: InitializeAirdropArgs,
 ) -> ProgramResult {
     let iter = &mut accounts.iter();
     let airdrop_account = next_account_info(iter)?;
@@ -66,33 +83,151 @@ fn process_initialize_airdrop<'a>(
     let _system_program = next_account_info(iter)?;
 
     // Airdrop account checks
-    msg!("Assert airdrop config writeable");
-    assert_writeable(airdrop_account)?;
-    msg!("Assert airdrop config owned by program");
-    assert_owned_by(airdrop_account, program_id)?;
+    trace!("Assert airdrop config writeable");
+    assert_writeable(airdrop_account, "airdrop config")?;
+    trace!("Assert airdrop config owned by program");
+    assert_owned_by(airdrop_account, program_id, "airdrop config")?;
+    trace!("Assert airdrop config sized for arguments");
+    if airdrop_account.data_len() != args.config_space() {
+        msg!(
+            "Airdrop config has {} bytes, expected {}",
+            airdrop_account.data_len(),
+            args.config_space()
+        );
+        return Err(AirdropError::WrongConfigSize.into());
+    }
 
     // Airdrop authority checks
 
     // Mint authority checks
     let (mint_authority_pda, mint_authority_bump) = find_mint_authority(airdrop_account.key);
 
-    msg!("Assert mint authority is PDA");
-    if mint_authority_pda != *mint_authority.key {
-        return Err(AirdropError::PdaCheckFailed.into());
-    }
+    trace!("Assert mint authority is PDA");
+    assert_address(
+        mint_authority,
+        &mint_authority_pda,
+        AirdropError::PdaCheckFailed,
+    )?;
 
-    msg!("Assert mint authority is writeable");
-    assert_writeable(mint_authority)?;
+    trace!("Assert mint authority is writeable");
+    assert_writeable(mint_authority, "mint authority")?;
 
     // Revenues account checks
 
     // Fee payer checks
-    msg!("Assert fee payer is signer");
-    assert_signer(fee_payer)?;
+    trace!("Assert fee payer is signer");
+    assert_signer(fee_payer, "fee payer")?;
+
+    // Argument checks
+    trace!("Assert arguments are valid");
+    if args.airdrop_amount == 0 {
+        msg!("Airdrop amount is zero");
+        return Err(AirdropError::InvalidAirdropAmount.into());
+    }
+
+    // Longest name and URI are the ones of the last token
+    let last_number = match token_number(
+        args.airdrop_amount - 1,
+        args.index_offset,
+        args.index_padding,
+    ) {
+        Some(number) => number,
+        None => {
+            msg!(
+                "Index offset {} overflows the last token number",
+                args.index_offset
+            );
+            return Err(AirdropError::InvalidIndexOffset.into());
+        }
+    };
+
+    // Config lines replace name and URI templates, they are checked on upload
+    let has_config_lines = args.config_lines != Pubkey::default();
+    if has_config_lines && (!args.hidden_uri.is_empty() || !args.hidden_name_template.is_empty()) {
+        msg!(
+            "Config lines {} are used together with hidden metadata",
+            args.config_lines
+        );
+        return Err(AirdropError::InvalidConfigLines.into());
+    }
+
+    if !has_config_lines {
+        if args.name_template.matches(NAME_PLACEHOLDER).count() != 1 {
+            msg!(
+                "Name template {:?} must contain one {} placeholder",
+                args.name_template,
+                NAME_PLACEHOLDER
+            );
+            return Err(AirdropError::InvalidNameTemplate.into());
+        }
+
+        assert_metadata_string(
+            &metadata_uri(&args.base_uri, &last_number, &args.uri_extension),
+            args.base_uri.is_empty(),
+            MAX_URI_LENGTH,
+            "URI",
+            AirdropError::InvalidBaseUri,
+        )?;
+        assert_metadata_string(
+            &metadata_name(&args.name_template, &last_number),
+            false,
+            MAX_NAME_LENGTH,
+            "name",
+            AirdropError::InvalidNameTemplate,
+        )?;
+    }
+    assert_metadata_string(
+        &args.symbol,
+        false,
+        MAX_SYMBOL_LENGTH,
+        "symbol",
+        AirdropError::InvalidSymbol,
+    )?;
+
+    // Hidden metadata is either not used or complete
+    if !args.hidden_uri.is_empty() || !args.hidden_name_template.is_empty() {
+        if args.hidden_name_template.matches(NAME_PLACEHOLDER).count() != 1 {
+            msg!(
+                "Hidden name template {:?} must contain one {} placeholder",
+                args.hidden_name_template,
+                NAME_PLACEHOLDER
+            );
+            return Err(AirdropError::InvalidHiddenMetadata.into());
+        }
+
+        assert_metadata_string(
+            &args.hidden_uri,
+            args.hidden_uri.is_empty(),
+            MAX_URI_LENGTH,
+            "hidden URI",
+            AirdropError::InvalidHiddenMetadata,
+        )?;
+        assert_metadata_string(
+            &metadata_name(&args.hidden_name_template, &last_number),
+            false,
+            MAX_NAME_LENGTH,
+            "hidden name",
+            AirdropError::InvalidHiddenMetadata,
+        )?;
+    }
+
+    // Fair mint commits to metadata order and seed, and hides metadata until the seed is revealed
+    let has_provenance_hash = args.provenance_hash != Hash::default();
+    let has_seed_commitment = args.seed_commitment != Hash::default();
+    if (has_provenance_hash || has_seed_commitment)
+        && !(has_provenance_hash && has_seed_commitment && !args.hidden_uri.is_empty())
+    {
+        msg!(
+            "Provenance hash {} and seed commitment {} require each other and hidden metadata",
+            args.provenance_hash,
+            args.seed_commitment
+        );
+        return Err(AirdropError::InvalidProvenance.into());
+    }
 
     // ----------------
 
-    msg!("Get rent info from account");
+    trace!("Get rent info from account");
     let rent = Rent::from_account_info(rent)?;
 
     process_initialize_airdrop_logic(
@@ -102,10 +237,7 @@ fn process_initialize_airdrop<'a>(
         revenues_account,
         admin_account,
         fee_payer,
-        airdrop_amount,
-        metadata_prefix,
-        symbol,
-        price,
+        args,
         program_id,
         rent,
         mint_authority_bump,
@@ -116,7 +248,7 @@ fn process_initialize_airdrop<'a>(
 
 fn process_initialize_airdrop_user<'a>(
     program_id: &Pubkey,
-    accounts: &'a [AccountInfo<'a>],
+    accounts: &[AccountInfo<'a>],
 ) -> ProgramResult {
     let iter = &mut accounts.iter();
     let user_data_account = next_account_info(iter)?;
@@ -126,46 +258,50 @@ fn process_initialize_airdrop_user<'a>(
     let fee_payer = next_account_info(iter)?;
 
     // User data account checks
-    msg!("Assert user data is properly derived");
+    trace!("Assert user data is properly derived");
     let (user_data_account_pda, user_data_account_bump) =
         find_airdrop_user_data(airdrop.key, user.key);
 
-    if user_data_account_pda != *user_data_account.key {
-        return Err(AirdropError::PdaCheckFailed.into());
-    }
+    assert_address(
+        user_data_account,
+        &user_data_account_pda,
+        AirdropError::PdaCheckFailed,
+    )?;
 
-    msg!("Assert user data is not initialized");
+    trace!("Assert user data is not initialized");
     if user_data_account.lamports() > 0 {
-        return Err(ProgramError::AccountAlreadyInitialized);
+        msg!("User {} is already registered", user.key);
+        return Err(AirdropError::UserAlreadyRegistered.into());
     }
 
-    msg!("Assert user data account is writeable");
-    assert_writeable(user_data_account)?;
+    trace!("Assert user data account is writeable");
+    assert_writeable(user_data_account, "user data account")?;
 
     // User checks
-    // msg!("Assert that user is regular wallet");
-    // assert_owned_by(user, &system_program::id())?;
+    // trace!("Assert that user is regular wallet");
+    // assert_owned_by(user, &system_program::id(), "user")?;
 
     // Airdrop config checks
-    msg!("Assert that airdrop config is owned by program");
-    assert_owned_by(airdrop, program_id)?;
-    msg!("Assert that airdrop config is writeable");
-    assert_writeable(airdrop)?;
+    trace!("Assert that airdrop config is owned by program");
+    assert_owned_by(airdrop, program_id, "airdrop config")?;
+    trace!("Assert that airdrop config is writeable");
+    assert_writeable(airdrop, "airdrop config")?;
 
-    msg!("Assert airdrop config is initialized");
+    trace!("Assert airdrop config is initialized");
     let airdrop_data = AirdropConfig::unpack_from_account(airdrop)?;
 
-    if !airdrop_data.is_initialized() {
-        return Err(AirdropError::Uninitialized.into());
-    }
+    assert_initialized(&airdrop_data, airdrop, "airdrop config")?;
 
     // Fee payer checks
-    msg!("Assert that fee payer is signer");
-    assert_signer(fee_payer)?;
+    trace!("Assert that fee payer is signer");
+    assert_signer(fee_payer, "fee payer")?;
+
+    trace!("Assert user consents to the airdrop");
+    assert_user_consent(&airdrop_data, user, fee_payer)?;
 
     // ----------------
 
-    msg!("Get rent");
+    trace!("Get rent");
     let rent = Rent::from_account_info(rent)?;
 
     process_initialize_airdrop_user_account_logic(
@@ -181,7 +317,7 @@ fn process_initialize_airdrop_user<'a>(
     Ok(())
 }
 
-fn process_mint_one<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) -> ProgramResult {
+fn process_mint_one<'a>(program_id: &Pubkey, accounts: &[AccountInfo<'a>]) -> ProgramResult {
     let iter = &mut accounts.iter();
     let airdrop_config = next_account_info(iter)?;
     let user_data_account = next_account_info(iter)?;
@@ -190,108 +326,242 @@ fn process_mint_one<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) ->
     let user_token_account = next_account_info(iter)?;
     let token_metadata_account = next_account_info(iter)?;
     let mint_authority = next_account_info(iter)?;
-    let system_program = next_account_info(iter)?; // System program
+    let system_program = next_account_info(iter)?;
     let clock_var = next_account_info(iter)?;
     let rent_var = next_account_info(iter)?;
-    let token_program = next_account_info(iter)?; // Token program
-    let _ = next_account_info(iter)?; // Associated token program
-    let _ = next_account_info(iter)?; // Token metadata program
+    let token_program = next_account_info(iter)?;
+    let associated_token_program = next_account_info(iter)?;
+    let token_metadata_program = next_account_info(iter)?;
     let payer = next_account_info(iter)?;
     let airdrop_authority = next_account_info(iter)?;
     let admin_account = next_account_info(iter)?;
     let revenue_wallet = next_account_info(iter)?;
 
     // Airdrop config checks
-    msg!("Check if airdrop account is writeable");
-    assert_writeable(airdrop_config)?;
-    msg!("Check if airdrop account is owned by this program");
-    assert_owned_by(airdrop_config, program_id)?;
+    trace!("Check if airdrop account is writeable");
+    assert_writeable(airdrop_config, "airdrop config")?;
+    trace!("Check if airdrop account is owned by this program");
+    assert_owned_by(airdrop_config, program_id, "airdrop config")?;
 
     let airdrop_data = AirdropConfig::unpack_from_account(airdrop_config)?;
 
-    msg!("Check if airdrop account is initialized");
-    if !airdrop_data.is_initialized() {
-        return Err(AirdropError::Uninitialized.into());
-    }
-
-    msg!("Check supply");
-    if airdrop_data.airdrop_index >= airdrop_data.airdrop_amount {
-        return Err(AirdropError::OutOfSupply.into());
-    }
+    trace!("Check if airdrop account is initialized");
+    assert_initialized(&airdrop_data, airdrop_config, "airdrop config")?;
 
     // User data account checks
-    msg!("Assert user data is writeable");
-    assert_writeable(user_data_account)?;
-    msg!("Check if user data is owned by this program");
-    assert_owned_by(user_data_account, program_id)?;
+    trace!("Assert user data is writeable");
+    assert_writeable(user_data_account, "user data account")?;
+    trace!("Check if user data is owned by this program");
+    assert_owned_by(user_data_account, program_id, "user data account")?;
 
     let user_data = AirdropUserData::unpack_from_account(user_data_account)?;
 
-    msg!("Check if user data account is initialized");
-    if !user_data.is_initialized() {
-        return Err(AirdropError::Uninitialized.into());
+    trace!("Check if user data account is initialized");
+    assert_initialized(&user_data, user_data_account, "user data account")?;
+
+    trace!("Check if user data belongs to user and airdrop");
+    if user_data.user != *user.key {
+        msg!(
+            "User data belongs to user {}, got {}",
+            user_data.user,
+            user.key
+        );
+        return Err(AirdropError::WrongRecipient.into());
     }
 
-    msg!("Check if airdrop data and user wallet are valid for user data account");
-    if !(user_data.user == *user.key && user_data.airdrop == *airdrop_config.key) {
-        return Err(ProgramError::InvalidAccountData);
+    if user_data.airdrop != *airdrop_config.key {
+        msg!(
+            "User data belongs to airdrop {}, got {}",
+            user_data.airdrop,
+            airdrop_config.key
+        );
+        return Err(AirdropError::WrongAirdrop.into());
     }
 
+    trace!("Assert user consents to the airdrop");
+    assert_user_consent(&airdrop_data, user, payer)?;
+
+    trace!("Assert clock sysvar is correct one");
+    assert_address(
+        clock_var,
+        &sysvar::clock::id(),
+        AirdropError::WrongClockSysvar,
+    )?;
+
     let clock = Clock::from_account_info(clock_var)?;
 
-    msg!("Check user timeout");
-    if user_data.locked_till >= clock.unix_timestamp as u64 {
-        return Err(AirdropError::UserTimeout.into());
+    if let Err(error) = check_mint_eligibility(&airdrop_data, Some(&user_data), &clock) {
+        msg!(
+            "Minted {} of {}, user is locked till {}, now {}",
+            airdrop_data.airdrop_index,
+            airdrop_data.airdrop_amount,
+            user_data.locked_till,
+            clock.unix_timestamp
+        );
+        return Err(error.into());
     }
 
     // Mint account checks
-    msg!("Assert that mint account is signer");
-    assert_signer(mint_account)?;
-    msg!("Assert that mint account is writeable");
-    assert_writeable(mint_account)?;
+    let mint_bump = match airdrop_data.pda_mints {
+        true => {
+            trace!("Assert that mint account is derived from minted amount");
+            let (mint_pda, mint_bump) = find_mint(airdrop_config.key, airdrop_data.airdrop_index);
+            assert_address(mint_account, &mint_pda, AirdropError::WrongMintAccount)?;
+            Some(mint_bump)
+        }
+        false => {
+            trace!("Assert that mint account is signer");
+            assert_signer(mint_account, "mint account")?;
+            None
+        }
+    };
+    trace!("Assert that mint account is writeable");
+    assert_writeable(mint_account, "mint account")?;
 
     // User token account checks
-    msg!("Assert token account is writeable");
-    assert_writeable(user_token_account)?;
+    trace!("Assert token account is writeable");
+    assert_writeable(user_token_account, "user token account")?;
+    trace!("Assert token account is associated token account of recipient");
+    assert_address(
+        user_token_account,
+        &get_associated_token_address(user.key, mint_account.key),
+        AirdropError::WrongRecipientTokenAccount,
+    )?;
 
     // Metadata account checks
-    msg!("Assert metadata account is writeable");
-    assert_writeable(token_metadata_account)?;
+    trace!("Assert metadata account is writeable");
+    assert_writeable(token_metadata_account, "token metadata account")?;
+    trace!("Assert metadata account is properly derived");
+    let (token_metadata_pda, _) = find_metadata_account(mint_account.key);
+    assert_address(
+        token_metadata_account,
+        &token_metadata_pda,
+        AirdropError::WrongTokenMetadataAccount,
+    )?;
 
     // Mint authority checks
     let (mint_authority_pda, mint_authority_bump) = find_mint_authority(airdrop_config.key);
 
-    msg!("Assert mint authority is properly derived");
-    if mint_authority_pda != *mint_authority.key {
-        return Err(AirdropError::PdaCheckFailed.into());
-    }
+    trace!("Assert mint authority is properly derived");
+    assert_address(
+        mint_authority,
+        &mint_authority_pda,
+        AirdropError::PdaCheckFailed,
+    )?;
 
     // Payer checks
-    msg!("Assert payer is signer");
-    assert_signer(payer)?;
-    msg!("Assert payer is writeable");
-    assert_writeable(payer)?;
-    msg!("Assert payer is owned by system program");
-    assert_owned_by(payer, &system_program::id())?;
+    trace!("Assert payer is signer");
+    assert_signer(payer, "payer")?;
+    trace!("Assert payer is writeable");
+    assert_writeable(payer, "payer")?;
+    trace!("Assert payer is owned by system program");
+    assert_owned_by(payer, &system_program::id(), "payer")?;
 
     // Airdrop authority checks
-    msg!("Assert drop is approved by airdrop authority");
-    assert_signer(airdrop_authority)?;
+    trace!("Assert that airdrop authority is correct one");
+    assert_address(
+        airdrop_authority,
+        &airdrop_data.airdrop_authority,
+        AirdropError::WrongAccountAddress,
+    )?;
+    trace!("Assert drop is approved by airdrop authority");
+    assert_signer(airdrop_authority, "airdrop authority")?;
 
     // Admin account checks
-    msg!("Assert that admin account is correct one");
-    if airdrop_data.admin_account != *admin_account.key {
-        return Err(AirdropError::WrongAccountAddress.into());
-    }
+    trace!("Assert that admin account is correct one");
+    assert_address(
+        admin_account,
+        &airdrop_data.admin_account,
+        AirdropError::WrongAccountAddress,
+    )?;
 
     // Revenue wallet checks
-    msg!("Assert that revenue wallet is correct one");
-    if airdrop_data.revenues_wallet != *revenue_wallet.key {
-        return Err(AirdropError::WrongAccountAddress.into());
-    }
+    trace!("Assert that revenue wallet is correct one");
+    assert_address(
+        revenue_wallet,
+        &airdrop_data.revenues_wallet,
+        AirdropError::WrongAccountAddress,
+    )?;
 
-    msg!("Assert revenue wallet is writeable");
-    assert_writeable(revenue_wallet)?;
+    trace!("Assert revenue wallet is writeable");
+    assert_writeable(revenue_wallet, "revenue wallet")?;
+
+    // Program and sysvar checks
+    trace!("Assert system program is correct one");
+    assert_address(
+        system_program,
+        &system_program::id(),
+        AirdropError::WrongSystemProgram,
+    )?;
+    trace!("Assert token program is correct one");
+    assert_address(
+        token_program,
+        &spl_token::id(),
+        AirdropError::WrongTokenProgram,
+    )?;
+    trace!("Assert associated token program is correct one");
+    assert_address(
+        associated_token_program,
+        &spl_associated_token_account::id(),
+        AirdropError::WrongAssociatedTokenProgram,
+    )?;
+    trace!("Assert token metadata program is correct one");
+    assert_address(
+        token_metadata_program,
+        &metaplex_token_metadata::id(),
+        AirdropError::WrongTokenMetadataProgram,
+    )?;
+    trace!("Assert rent sysvar is correct one");
+    assert_address(rent_var, &sysvar::rent::id(), AirdropError::WrongRentSysvar)?;
+
+    // Draw checks
+    let index_seed = match airdrop_data.shuffled {
+        true => {
+            let slot_hashes = next_account_info(iter)?;
+            trace!("Assert slot hashes sysvar is correct one");
+            assert_address(
+                slot_hashes,
+                &sysvar::slot_hashes::id(),
+                AirdropError::WrongSlotHashesSysvar,
+            )?;
+
+            trace!("Check if draw is committed");
+            if user_data.draw_slot == 0 {
+                msg!("User {} has no committed draw", user.key);
+                return Err(AirdropError::DrawNotCommitted.into());
+            }
+
+            trace!("Find hash of the committed slot");
+            let slot_hash = match committed_slot_hash(
+                &slot_hashes.data.borrow(),
+                user_data.draw_slot,
+                clock.slot,
+            ) {
+                Ok(slot_hash) => slot_hash,
+                Err(error) => {
+                    msg!(
+                        "Draw is committed at slot {}, now {}",
+                        user_data.draw_slot,
+                        clock.slot
+                    );
+                    return Err(error.into());
+                }
+            };
+            Some(index_seed(&slot_hash, airdrop_config.key, user.key))
+        }
+        false => None,
+    };
+
+    // Config lines checks
+    let config_lines = match airdrop_data.has_config_lines() {
+        true => {
+            let config_lines = next_account_info(iter)?;
+            trace!("Assert config lines account is correct one");
+            assert_config_lines(config_lines, airdrop_config, &airdrop_data, program_id)?;
+            Some(config_lines)
+        }
+        false => None,
+    };
 
     // ----------------
 
@@ -314,28 +584,698 @@ fn process_mint_one<'a>(program_id: &Pubkey, accounts: &'a [AccountInfo<'a>]) ->
         mint_authority_bump,
         system_program,
         token_program,
+        index_seed,
+        config_lines,
+        mint_bump,
+    )?;
+
+    Ok(())
+}
+
+fn process_reveal<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    args: RevealArgs,
+) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let airdrop_config = next_account_info(iter)?;
+    let admin_account = next_account_info(iter)?;
+    let mint_authority = next_account_info(iter)?;
+    let token_metadata_program = next_account_info(iter)?;
+
+    // Airdrop config checks
+    trace!("Check if airdrop account is owned by this program");
+    assert_owned_by(airdrop_config, program_id, "airdrop config")?;
+
+    let airdrop_data = AirdropConfig::unpack_from_account(airdrop_config)?;
+
+    trace!("Check if airdrop account is initialized");
+    assert_initialized(&airdrop_data, airdrop_config, "airdrop config")?;
+
+    trace!("Check if airdrop metadata is hidden");
+    if !airdrop_data.is_hidden() {
+        msg!(
+            "Airdrop {} is minted with final metadata",
+            airdrop_config.key
+        );
+        return Err(AirdropError::NotHidden.into());
+    }
+
+    trace!("Check if fair mint seed is revealed");
+    if airdrop_data.is_fair_mint() && !airdrop_data.seed_revealed {
+        msg!("Seed of airdrop {} is not revealed", airdrop_config.key);
+        return Err(AirdropError::SeedNotRevealed.into());
+    }
+
+    // Admin account checks
+    trace!("Assert that admin account is correct one");
+    assert_address(
+        admin_account,
+        &airdrop_data.admin_account,
+        AirdropError::WrongAccountAddress,
+    )?;
+    trace!("Assert reveal is signed by admin");
+    assert_signer(admin_account, "admin")?;
+
+    // Mint authority checks
+    let (mint_authority_pda, mint_authority_bump) = find_mint_authority(airdrop_config.key);
+
+    trace!("Assert mint authority is PDA");
+    assert_address(
+        mint_authority,
+        &mint_authority_pda,
+        AirdropError::PdaCheckFailed,
+    )?;
+
+    // Program checks
+    trace!("Assert token metadata program is correct one");
+    assert_address(
+        token_metadata_program,
+        &metaplex_token_metadata::id(),
+        AirdropError::WrongTokenMetadataProgram,
+    )?;
+
+    // ----------------
+
+    let clock = Clock::get()?;
+
+    for airdrop_index in args.airdrop_indices {
+        let metadata = next_account_info(iter)?;
+
+        trace!("Assert token metadata is writeable and owned by metadata program");
+        assert_writeable(metadata, "token metadata")?;
+        assert_owned_by(metadata, &metaplex_token_metadata::id(), "token metadata")?;
+
+        process_reveal_logic(
+            airdrop_config,
+            &airdrop_data,
+            metadata,
+            mint_authority,
+            airdrop_index,
+            mint_authority_bump,
+            clock.unix_timestamp,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn process_reveal_seed(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: RevealSeedArgs,
+) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let airdrop_config = next_account_info(iter)?;
+    let admin_account = next_account_info(iter)?;
+    let slot_hashes = next_account_info(iter)?;
+
+    // Airdrop config checks
+    trace!("Check if airdrop account is writeable");
+    assert_writeable(airdrop_config, "airdrop config")?;
+    trace!("Check if airdrop account is owned by this program");
+    assert_owned_by(airdrop_config, program_id, "airdrop config")?;
+
+    let mut airdrop_data = AirdropConfig::unpack_from_account(airdrop_config)?;
+
+    trace!("Check if airdrop account is initialized");
+    assert_initialized(&airdrop_data, airdrop_config, "airdrop config")?;
+
+    trace!("Check if airdrop has seed commitment");
+    if !airdrop_data.is_fair_mint() {
+        msg!("Airdrop {} has no seed commitment", airdrop_config.key);
+        return Err(AirdropError::NoSeedCommitment.into());
+    }
+
+    trace!("Check if seed is not revealed yet");
+    if airdrop_data.seed_revealed {
+        msg!(
+            "Seed of airdrop {} is revealed with starting offset {}",
+            airdrop_config.key,
+            airdrop_data.starting_offset
+        );
+        return Err(AirdropError::SeedAlreadyRevealed.into());
+    }
+
+    trace!("Check if sale is ended");
+    if !airdrop_data.is_sale_ended() {
+        msg!("Sale of airdrop {} is not ended", airdrop_config.key);
+        return Err(AirdropError::SaleNotFinished.into());
+    }
+
+    // Admin account checks
+    trace!("Assert that admin account is correct one");
+    assert_address(
+        admin_account,
+        &airdrop_data.admin_account,
+        AirdropError::WrongAccountAddress,
+    )?;
+    trace!("Assert seed reveal is signed by admin");
+    assert_signer(admin_account, "admin")?;
+
+    // Sysvar checks
+    trace!("Assert slot hashes sysvar is correct one");
+    assert_address(
+        slot_hashes,
+        &sysvar::slot_hashes::id(),
+        AirdropError::WrongSlotHashesSysvar,
+    )?;
+
+    // ----------------
+
+    let clock = Clock::get()?;
+
+    process_reveal_seed_logic(
+        airdrop_config,
+        &mut airdrop_data,
+        args.seed,
+        slot_hashes,
+        &clock,
+    )?;
+
+    Ok(())
+}
+
+fn process_add_config_lines(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: AddConfigLinesArgs,
+) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let airdrop_config = next_account_info(iter)?;
+    let config_lines_account = next_account_info(iter)?;
+    let admin_account = next_account_info(iter)?;
+
+    // Airdrop config checks
+    trace!("Check if airdrop account is owned by this program");
+    assert_owned_by(airdrop_config, program_id, "airdrop config")?;
+
+    let airdrop_data = AirdropConfig::unpack_from_account(airdrop_config)?;
+
+    trace!("Check if airdrop account is initialized");
+    assert_initialized(&airdrop_data, airdrop_config, "airdrop config")?;
+
+    trace!("Check if airdrop has config lines");
+    if !airdrop_data.has_config_lines() {
+        msg!("Airdrop {} uses metadata templates", airdrop_config.key);
+        return Err(AirdropError::NoConfigLines.into());
+    }
+
+    // Config lines checks
+    trace!("Assert config lines account is correct one");
+    let config_lines = assert_config_lines(
+        config_lines_account,
+        airdrop_config,
+        &airdrop_data,
+        program_id,
+    )?;
+    trace!("Assert config lines account is writeable");
+    assert_writeable(config_lines_account, "config lines")?;
+
+    // Admin account checks
+    trace!("Assert that admin account is correct one");
+    assert_address(
+        admin_account,
+        &airdrop_data.admin_account,
+        AirdropError::WrongAccountAddress,
+    )?;
+    trace!("Assert config lines are signed by admin");
+    assert_signer(admin_account, "admin")?;
+
+    trace!("Check if sale is not started");
+    if airdrop_data.airdrop_index > 0 {
+        msg!(
+            "Minted {} of {}",
+            airdrop_data.airdrop_index,
+            airdrop_data.airdrop_amount
+        );
+        return Err(AirdropError::SaleStarted.into());
+    }
+
+    // Argument checks
+    trace!("Assert config lines are valid");
+    let end = args.first_index.checked_add(args.lines.len() as u64);
+    if !matches!(end, Some(end) if end <= airdrop_data.airdrop_amount) {
+        msg!(
+            "Lines from {} exceed airdrop amount {}",
+            args.first_index,
+            airdrop_data.airdrop_amount
+        );
+        return Err(AirdropError::InvalidConfigLine.into());
+    }
+
+    for line in &args.lines {
+        assert_metadata_string(
+            &line.name,
+            line.name.is_empty(),
+            MAX_NAME_LENGTH,
+            "config line name",
+            AirdropError::InvalidConfigLine,
+        )?;
+        assert_metadata_string(
+            &line.uri,
+            line.uri.is_empty(),
+            MAX_URI_LENGTH,
+            "config line URI",
+            AirdropError::InvalidConfigLine,
+        )?;
+    }
+
+    // ----------------
+
+    process_add_config_lines_logic(airdrop_config, config_lines_account, config_lines, args)?;
+
+    Ok(())
+}
+
+fn process_commit_draw(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let airdrop_config = next_account_info(iter)?;
+    let user_data_account = next_account_info(iter)?;
+    let user = next_account_info(iter)?;
+    let airdrop_authority = next_account_info(iter)?;
+    let clock_var = next_account_info(iter)?;
+
+    // Airdrop config checks
+    trace!("Check if airdrop account is owned by this program");
+    assert_owned_by(airdrop_config, program_id, "airdrop config")?;
+
+    let airdrop_data = AirdropConfig::unpack_from_account(airdrop_config)?;
+
+    trace!("Check if airdrop account is initialized");
+    assert_initialized(&airdrop_data, airdrop_config, "airdrop config")?;
+
+    trace!("Check if airdrop indices are shuffled");
+    if !airdrop_data.shuffled {
+        msg!("Airdrop {} mints in order", airdrop_config.key);
+        return Err(AirdropError::NotShuffled.into());
+    }
+
+    // User data account checks
+    trace!("Assert user data is writeable");
+    assert_writeable(user_data_account, "user data account")?;
+    trace!("Check if user data is owned by this program");
+    assert_owned_by(user_data_account, program_id, "user data account")?;
+
+    let user_data = AirdropUserData::unpack_from_account(user_data_account)?;
+
+    trace!("Check if user data account is initialized");
+    assert_initialized(&user_data, user_data_account, "user data account")?;
+
+    trace!("Check if user data belongs to user and airdrop");
+    if user_data.user != *user.key {
+        msg!(
+            "User data belongs to user {}, got {}",
+            user_data.user,
+            user.key
+        );
+        return Err(AirdropError::WrongRecipient.into());
+    }
+
+    if user_data.airdrop != *airdrop_config.key {
+        msg!(
+            "User data belongs to airdrop {}, got {}",
+            user_data.airdrop,
+            airdrop_config.key
+        );
+        return Err(AirdropError::WrongAirdrop.into());
+    }
+
+    // Airdrop authority checks
+    trace!("Assert that airdrop authority is correct one");
+    assert_address(
+        airdrop_authority,
+        &airdrop_data.airdrop_authority,
+        AirdropError::WrongAccountAddress,
+    )?;
+    trace!("Assert draw is approved by airdrop authority");
+    assert_signer(airdrop_authority, "airdrop authority")?;
+
+    // Clock checks
+    trace!("Assert clock sysvar is correct one");
+    assert_address(
+        clock_var,
+        &sysvar::clock::id(),
+        AirdropError::WrongClockSysvar,
     )?;
 
+    let clock = Clock::from_account_info(clock_var)?;
+
+    trace!("Check if user has no pending draw");
+    if user_data.has_draw(clock.slot) {
+        msg!("Draw is committed at slot {}", user_data.draw_slot);
+        return Err(AirdropError::UserTimeout.into());
+    }
+
+    if let Err(error) = check_mint_eligibility(&airdrop_data, Some(&user_data), &clock) {
+        msg!(
+            "Minted {} of {}, user is locked till {}, now {}",
+            airdrop_data.airdrop_index,
+            airdrop_data.airdrop_amount,
+            user_data.locked_till,
+            clock.unix_timestamp
+        );
+        return Err(error.into());
+    }
+
+    // ----------------
+
+    process_commit_draw_logic(user_data_account, user_data, &clock)?;
+
     Ok(())
 }
 
-fn assert_signer(acc: &AccountInfo) -> Result<(), ProgramError> {
+fn process_end_sale(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let airdrop_config = next_account_info(iter)?;
+    let admin_account = next_account_info(iter)?;
+
+    // Airdrop config checks
+    trace!("Check if airdrop account is writeable");
+    assert_writeable(airdrop_config, "airdrop config")?;
+    trace!("Check if airdrop account is owned by this program");
+    assert_owned_by(airdrop_config, program_id, "airdrop config")?;
+
+    let mut airdrop_data = AirdropConfig::unpack_from_account(airdrop_config)?;
+
+    trace!("Check if airdrop account is initialized");
+    assert_initialized(&airdrop_data, airdrop_config, "airdrop config")?;
+
+    trace!("Check if airdrop has seed commitment");
+    if !airdrop_data.is_fair_mint() {
+        msg!("Airdrop {} has no seed commitment", airdrop_config.key);
+        return Err(AirdropError::NoSeedCommitment.into());
+    }
+
+    trace!("Check if seed is not revealed yet");
+    if airdrop_data.seed_revealed {
+        msg!("Seed of airdrop {} is revealed", airdrop_config.key);
+        return Err(AirdropError::SeedAlreadyRevealed.into());
+    }
+
+    let clock = Clock::get()?;
+
+    // The sale end slot may only be replaced once its hash is no longer available
+    trace!("Check if sale is not ended yet");
+    if airdrop_data.is_sale_ended()
+        && clock.slot.saturating_sub(airdrop_data.sale_end_slot) <= MAX_ENTRIES as u64
+    {
+        msg!(
+            "Sale of airdrop {} is ended at slot {}, now {}",
+            airdrop_config.key,
+            airdrop_data.sale_end_slot,
+            clock.slot
+        );
+        return Err(AirdropError::SaleEnded.into());
+    }
+
+    // Admin account checks
+    trace!("Assert that admin account is correct one");
+    assert_address(
+        admin_account,
+        &airdrop_data.admin_account,
+        AirdropError::WrongAccountAddress,
+    )?;
+    trace!("Assert sale end is signed by admin");
+    assert_signer(admin_account, "admin")?;
+
+    // ----------------
+
+    process_end_sale_logic(airdrop_config, &mut airdrop_data, clock.slot)?;
+
+    Ok(())
+}
+
+fn process_quote(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let airdrop_config = next_account_info(iter)?;
+    let user_data_account = next_account_info(iter)?;
+    let user = next_account_info(iter)?;
+    let clock_var = next_account_info(iter)?;
+
+    // Airdrop config checks
+    trace!("Check if airdrop account is owned by this program");
+    assert_owned_by(airdrop_config, program_id, "airdrop config")?;
+
+    let airdrop_data = AirdropConfig::unpack_from_account(airdrop_config)?;
+
+    trace!("Check if airdrop account is initialized");
+    assert_initialized(&airdrop_data, airdrop_config, "airdrop config")?;
+
+    // User data account checks
+    trace!("Assert user data is properly derived");
+    let (user_data_account_pda, _) = find_airdrop_user_data(airdrop_config.key, user.key);
+
+    assert_address(
+        user_data_account,
+        &user_data_account_pda,
+        AirdropError::PdaCheckFailed,
+    )?;
+
+    let user_data = match user_data_account.owner == program_id {
+        true => Some(AirdropUserData::unpack_from_account(user_data_account)?)
+            .filter(|user_data| user_data.is_initialized()),
+        false => None,
+    };
+
+    // Clock checks
+    trace!("Assert clock sysvar is correct one");
+    assert_address(
+        clock_var,
+        &sysvar::clock::id(),
+        AirdropError::WrongClockSysvar,
+    )?;
+
+    let clock = Clock::from_account_info(clock_var)?;
+
+    // ----------------
+
+    let eligibility = check_mint_eligibility(&airdrop_data, user_data.as_ref(), &clock)
+        .and_then(|()| check_draw(&airdrop_data, user_data.as_ref(), &clock));
+    let status = match eligibility {
+        Ok(()) => QuoteStatus::Eligible,
+        Err(AirdropError::OutOfSupply) => QuoteStatus::OutOfSupply,
+        Err(AirdropError::Uninitialized) => QuoteStatus::UserNotRegistered,
+        Err(AirdropError::UserTimeout) => QuoteStatus::UserTimeout,
+        Err(AirdropError::SaleEnded) => QuoteStatus::SaleEnded,
+        Err(AirdropError::DrawNotCommitted) => QuoteStatus::DrawNotCommitted,
+        Err(AirdropError::SlotHashNotReady) => QuoteStatus::DrawNotReady,
+        Err(error) => return Err(error.into()),
+    };
+
+    QuoteResult {
+        status,
+        price: airdrop_data.price,
+        remaining_supply: airdrop_data
+            .airdrop_amount
+            .saturating_sub(airdrop_data.airdrop_index),
+        next_index: match airdrop_data.shuffled {
+            true => UNKNOWN_INDEX,
+            false => airdrop_data.airdrop_index,
+        },
+        locked_till: user_data.map_or(0, |user_data| user_data.locked_till),
+    }
+    .set();
+
+    Ok(())
+}
+
+/// Checks of airdrop and user state shared by `MintOne`, `Quote` and `CommitDraw`.
+/// Missing user data is reported as `Uninitialized`, a committed draw lifts the user timeout
+fn check_mint_eligibility(
+    airdrop_data: &AirdropConfig,
+    user_data: Option<&AirdropUserData>,
+    clock: &Clock,
+) -> Result<(), AirdropError> {
+    trace!("Check supply");
+    if airdrop_data.airdrop_index >= airdrop_data.airdrop_amount {
+        return Err(AirdropError::OutOfSupply);
+    }
+
+    trace!("Check if sale is not ended by admin");
+    if airdrop_data.is_sale_ended() {
+        return Err(AirdropError::SaleEnded);
+    }
+
+    trace!("Check if user is registered");
+    let user_data = user_data.ok_or(AirdropError::Uninitialized)?;
+
+    trace!("Check user timeout");
+    if user_data.locked_till >= clock.unix_timestamp && !user_data.has_draw(clock.slot) {
+        return Err(AirdropError::UserTimeout);
+    }
+
+    Ok(())
+}
+
+/// Draw checks of `MintOne` on shuffled airdrops made without the slot hashes sysvar,
+/// used by `Quote`. An expired draw is reported as `DrawNotCommitted`, a new one is required
+fn check_draw(
+    airdrop_data: &AirdropConfig,
+    user_data: Option<&AirdropUserData>,
+    clock: &Clock,
+) -> Result<(), AirdropError> {
+    let user_data = match (airdrop_data.shuffled, user_data) {
+        (true, Some(user_data)) => user_data,
+        _ => return Ok(()),
+    };
+
+    trace!("Check if draw is committed");
+    if !user_data.has_draw(clock.slot) {
+        return Err(AirdropError::DrawNotCommitted);
+    }
+
+    trace!("Check if hash of the committed slot is available");
+    if user_data.draw_slot >= clock.slot {
+        return Err(AirdropError::SlotHashNotReady);
+    }
+
+    Ok(())
+}
+
+fn assert_signer(acc: &AccountInfo, name: &str) -> Result<(), ProgramError> {
     match acc.is_signer {
         true => Ok(()),
-        false => Err(AirdropError::SignerRequired.into()),
+        false => {
+            msg!("Account {} ({}) must sign the transaction", name, acc.key);
+            Err(AirdropError::SignerRequired.into())
+        }
+    }
+}
+
+/// Checks that `user` signs and pays if the airdrop requires it
+fn assert_user_consent(
+    airdrop_data: &AirdropConfig,
+    user: &AccountInfo,
+    payer: &AccountInfo,
+) -> ProgramResult {
+    if airdrop_data.require_user_signature {
+        assert_signer(user, "user")?;
     }
+
+    if airdrop_data.require_user_payer && payer.key != user.key {
+        msg!("Payer {} is not user {}", payer.key, user.key);
+        return Err(AirdropError::UserMustPay.into());
+    }
+
+    Ok(())
 }
 
-fn assert_writeable(acc: &AccountInfo) -> Result<(), ProgramError> {
+fn assert_writeable(acc: &AccountInfo, name: &str) -> Result<(), ProgramError> {
     match acc.is_writable {
         true => Ok(()),
-        false => Err(AirdropError::WriteableRequired.into()),
+        false => {
+            msg!("Account {} ({}) must be writeable", name, acc.key);
+            Err(AirdropError::WriteableRequired.into())
+        }
     }
 }
 
-fn assert_owned_by(acc: &AccountInfo, expected_owner: &Pubkey) -> Result<(), ProgramError> {
+fn assert_owned_by(
+    acc: &AccountInfo,
+    expected_owner: &Pubkey,
+    name: &str,
+) -> Result<(), ProgramError> {
     match acc.owner.eq(expected_owner) {
         true => Ok(()),
-        false => Err(ProgramError::IllegalOwner),
+        false => {
+            msg!(
+                "Account {} ({}) is owned by {}, expected {}",
+                name,
+                acc.key,
+                acc.owner,
+                expected_owner
+            );
+            Err(AirdropError::WrongAccountOwner.into())
+        }
+    }
+}
+
+fn assert_initialized<T: IsInitialized>(state: &T, acc: &AccountInfo, name: &str) -> ProgramResult {
+    match state.is_initialized() {
+        true => Ok(()),
+        false => {
+            msg!("Account {} ({}) is not initialized", name, acc.key);
+            Err(AirdropError::Uninitialized.into())
+        }
+    }
+}
+
+/// Checks metadata string against Metaplex length limit, so that minting does not fail inside the CPI
+fn assert_metadata_string(
+    value: &str,
+    empty: bool,
+    max_len: usize,
+    name: &str,
+    error: AirdropError,
+) -> ProgramResult {
+    if empty {
+        msg!("Token {} is empty", name);
+        return Err(error.into());
+    }
+
+    if value.len() > max_len {
+        msg!(
+            "Token {} {:?} has {} bytes, at most {} allowed",
+            name,
+            value,
+            value.len(),
+            max_len
+        );
+        return Err(error.into());
+    }
+
+    Ok(())
+}
+
+/// Checks that `acc` is the config lines account of the airdrop, sized for all of its tokens
+/// and not initialized for another airdrop
+fn assert_config_lines(
+    acc: &AccountInfo,
+    airdrop_config: &AccountInfo,
+    airdrop_data: &AirdropConfig,
+    program_id: &Pubkey,
+) -> Result<ConfigLines, ProgramError> {
+    assert_address(
+        acc,
+        &airdrop_data.config_lines,
+        AirdropError::WrongConfigLinesAccount,
+    )?;
+    assert_owned_by(acc, program_id, "config lines")?;
+
+    let space = ConfigLines::space(airdrop_data.airdrop_amount);
+    if acc.data_len() != space {
+        msg!(
+            "Config lines have {} bytes, expected {}",
+            acc.data_len(),
+            space
+        );
+        return Err(AirdropError::WrongConfigLinesAccount.into());
+    }
+
+    let config_lines = ConfigLines::unpack_from_account(acc)?;
+    if config_lines.initialized && config_lines.airdrop != *airdrop_config.key {
+        msg!(
+            "Config lines belong to airdrop {}, got {}",
+            config_lines.airdrop,
+            airdrop_config.key
+        );
+        return Err(AirdropError::WrongConfigLinesAccount.into());
+    }
+
+    Ok(config_lines)
+}
+
+fn assert_address(
+    acc: &AccountInfo,
+    expected_address: &Pubkey,
+    error: AirdropError,
+) -> Result<(), ProgramError> {
+    match acc.key.eq(expected_address) {
+        true => Ok(()),
+        false => {
+            msg!("Got account {}, expected {}", acc.key, expected_address);
+            Err(error.into())
+        }
     }
 }
@@ -0,0 +1,127 @@
+use std::convert::TryInto;
+
+use arrayref::{array_mut_ref, array_refs, mut_array_refs};
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use solana_program::{program::set_return_data, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::error::AirdropError;
+
+/// Return data of `MintOne` instruction.
+///
+/// Layout: `[airdrop index: u64][mint: Pubkey][price: u64]`. All integers are little endian
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MintOneResult {
+    pub airdrop_index: u64,
+    pub mint: Pubkey,
+    pub price: u64,
+}
+
+impl MintOneResult {
+    pub const LEN: usize = 8 + 32 + 8;
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![0; MintOneResult::LEN];
+        let dst = array_mut_ref![data, 0, MintOneResult::LEN];
+
+        let (airdrop_index, mint, price) = mut_array_refs![dst, 8, 32, 8];
+
+        airdrop_index.copy_from_slice(&self.airdrop_index.to_le_bytes());
+        mint.copy_from_slice(&self.mint.to_bytes());
+        price.copy_from_slice(&self.price.to_le_bytes());
+
+        data
+    }
+
+    pub fn unpack(src: &[u8]) -> Result<MintOneResult, ProgramError> {
+        let src: &[u8; MintOneResult::LEN] = src.try_into().or(Err(AirdropError::BadReturnData))?;
+
+        let (airdrop_index_src, mint_src, price_src) = array_refs![src, 8, 32, 8];
+
+        Ok(MintOneResult {
+            airdrop_index: u64::from_le_bytes(*airdrop_index_src),
+            mint: Pubkey::new_from_array(*mint_src),
+            price: u64::from_le_bytes(*price_src),
+        })
+    }
+
+    /// Set as return data of the current instruction
+    pub fn set(&self) {
+        set_return_data(&self.pack());
+    }
+}
+
+/// `QuoteResult::next_index` of shuffled airdrops, whose index is drawn by the mint
+pub const UNKNOWN_INDEX: u64 = u64::MAX;
+
+/// Eligibility status returned by `Quote` instruction
+#[derive(Debug, Copy, Clone, PartialEq, Eq, FromPrimitive)]
+pub enum QuoteStatus {
+    Eligible = 0,
+    OutOfSupply = 1,
+    UserNotRegistered = 2,
+    UserTimeout = 3,
+    SaleEnded = 4,
+    /// Shuffled airdrop requires `CommitDraw` first, the user has no draw or it expired
+    DrawNotCommitted = 5,
+    /// Draw is committed in the current slot, its hash is available in the next one
+    DrawNotReady = 6,
+}
+
+/// Return data of `Quote` instruction.
+///
+/// Layout: `[status: u8][price: u64][remaining supply: u64][next index: u64][locked till: i64]`.
+/// All integers are little endian
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct QuoteResult {
+    pub status: QuoteStatus,
+    pub price: u64,
+    pub remaining_supply: u64,
+    /// Airdrop index of the next mint, `UNKNOWN_INDEX` if the airdrop is shuffled
+    pub next_index: u64,
+    pub locked_till: i64,
+}
+
+impl QuoteResult {
+    pub const LEN: usize = 1 + 8 + 8 + 8 + 8;
+
+    pub fn is_eligible(&self) -> bool {
+        self.status == QuoteStatus::Eligible
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![0; QuoteResult::LEN];
+        let dst = array_mut_ref![data, 0, QuoteResult::LEN];
+
+        let (status, price, remaining_supply, next_index, locked_till) =
+            mut_array_refs![dst, 1, 8, 8, 8, 8];
+
+        status[0] = self.status as u8;
+        price.copy_from_slice(&self.price.to_le_bytes());
+        remaining_supply.copy_from_slice(&self.remaining_supply.to_le_bytes());
+        next_index.copy_from_slice(&self.next_index.to_le_bytes());
+        locked_till.copy_from_slice(&self.locked_till.to_le_bytes());
+
+        data
+    }
+
+    pub fn unpack(src: &[u8]) -> Result<QuoteResult, ProgramError> {
+        let src: &[u8; QuoteResult::LEN] = src.try_into().or(Err(AirdropError::BadReturnData))?;
+
+        let (status_src, price_src, remaining_supply_src, next_index_src, locked_till_src) =
+            array_refs![src, 1, 8, 8, 8, 8];
+
+        Ok(QuoteResult {
+            status: QuoteStatus::from_u8(status_src[0]).ok_or(AirdropError::BadReturnData)?,
+            price: u64::from_le_bytes(*price_src),
+            remaining_supply: u64::from_le_bytes(*remaining_supply_src),
+            next_index: u64::from_le_bytes(*next_index_src),
+            locked_till: i64::from_le_bytes(*locked_till_src),
+        })
+    }
+
+    /// Set as return data of the current instruction
+    pub fn set(&self) {
+        set_return_data(&self.pack());
+    }
+}
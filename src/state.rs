@@ -1,41 +1,115 @@
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use metaplex_token_metadata::state::{MAX_NAME_LENGTH, MAX_URI_LENGTH};
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
 use solana_program::{
     account_info::AccountInfo,
+    hash::Hash,
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack, Sealed},
     pubkey::Pubkey,
+    slot_hashes::MAX_ENTRIES,
 };
 
+use crate::error::AirdropError;
+
 pub const USER_DATA: &str = "user_data";
 pub const MINT_AUTHORITY: &str = "mint_authority";
+pub const MINT: &str = "mint";
 
-#[derive(Debug, Copy, Clone)]
+/// Placeholder of token number in `AirdropConfig::name_template`
+pub const NAME_PLACEHOLDER: &str = "{}";
+
+/// Seconds a user is unable to mint after each mint
+pub const USER_LOCK_DURATION: i64 = 6 * 60 * 60;
+
+/// Discriminator stored in the first byte of every program-owned account
+#[derive(Debug, Copy, Clone, PartialEq, Eq, FromPrimitive)]
+pub enum AccountType {
+    Uninitialized = 0,
+    AirdropConfig = 1,
+    AirdropUserData = 2,
+    ConfigLines = 3,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AirdropConfig {
     pub initialized: bool,
     pub airdrop_authority: Pubkey,
+    /// Amount of minted tokens. Airdrop index of the next token unless indices are shuffled
     pub airdrop_index: u64,
     pub airdrop_amount: u64,
-    pub metadata_prefix: [u8; 32],
-    pub symbol: [u8; 8],
     pub airdrop_users: u64,
     pub revenues_wallet: Pubkey,
     pub admin_account: Pubkey,
     pub price: u64,
+    /// Number of the first token
+    pub index_offset: u64,
+    /// Minimal amount of digits of token number, zero padded
+    pub index_padding: u8,
+    /// Airdrop index of every token is drawn at random from the indices not minted yet
+    pub shuffled: bool,
+    /// Hash of metadata files in their original order, published before the sale.
+    /// Zeros if fair mint is not used
+    pub provenance_hash: Hash,
+    /// SHA-256 of the seed revealed after the sale. Zeros if fair mint is not used
+    pub seed_commitment: Hash,
+    /// Seed is revealed and the starting offset is set
+    pub seed_revealed: bool,
+    /// Slot of `EndSale` whose hash is mixed into the starting offset.
+    /// Zero while the sale of a fair mint airdrop goes on
+    pub sale_end_slot: u64,
+    /// Metadata file of airdrop index 0, derived from the revealed seed and the hash
+    /// of the sale end slot
+    pub starting_offset: u64,
+    /// Account with name and URI of every token. Zeros if metadata is templated
+    pub config_lines: Pubkey,
+    /// Mint of every token is a program address derived from the amount of minted tokens
+    pub pda_mints: bool,
+    /// Recipient must sign `InitializeAirdropUser` and `MintOne`
+    pub require_user_signature: bool,
+    /// Recipient must pay for `InitializeAirdropUser` and `MintOne`
+    pub require_user_payer: bool,
+    pub base_uri: String,
+    pub uri_extension: String,
+    pub name_template: String,
+    pub symbol: String,
+    /// Name template of tokens minted before reveal. Empty if metadata is not hidden
+    pub hidden_name_template: String,
+    /// URI of tokens minted before reveal. Empty if metadata is not hidden
+    pub hidden_uri: String,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct AirdropUserData {
     pub initialized: bool,
     pub airdrop: Pubkey,
     pub user: Pubkey,
     pub mints_amount: u64,
-    pub locked_till: u64,
+    pub locked_till: i64,
+    /// Slot of `CommitDraw` whose hash draws the index of the next shuffled mint.
+    /// Zero if no draw is committed
+    pub draw_slot: u64,
 }
 
 #[derive(Debug, Copy, Clone)]
 pub struct MintAuthority {}
 
-impl Sealed for AirdropConfig {}
+/// Header of the account storing name and URI of every token of an airdrop,
+/// uploaded by admin before the sale. Lines of `ConfigLines::LINE_LEN` bytes
+/// follow the header in order of airdrop index
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ConfigLines {
+    pub initialized: bool,
+    pub airdrop: Pubkey,
+}
+
+/// Final metadata of one token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigLine {
+    pub name: String,
+    pub uri: String,
+}
 
 impl IsInitialized for AirdropConfig {
     fn is_initialized(&self) -> bool {
@@ -43,94 +117,248 @@ impl IsInitialized for AirdropConfig {
     }
 }
 
-impl Pack for AirdropConfig {
-    const LEN: usize = 1 + 32 + 8 + 8 + 32 + 8 + 8 + 32 + 32 + 8;
+impl AirdropConfig {
+    /// Size of the fixed part of the config, metadata strings follow it
+    pub const HEADER_LEN: usize =
+        1 + 32 + 8 + 8 + 8 + 32 + 32 + 8 + 8 + 1 + 1 + 32 + 32 + 1 + 8 + 8 + 32 + 1 + 1 + 1;
 
-    fn pack_into_slice(&self, dst: &mut [u8]) {
-        let dst = array_mut_ref![dst, 0, AirdropConfig::LEN];
+    /// Account size required to store a config with the given metadata strings:
+    /// base URI, URI extension, name template, symbol, hidden name template and hidden URI.
+    /// Every string is stored as `[length: u8][utf-8 bytes]`. Index map of `index_map_len`
+    /// bytes follows the strings
+    pub fn space(strings: &[&str; 6], index_map_len: usize) -> usize {
+        AirdropConfig::HEADER_LEN
+            + strings.iter().map(|value| 1 + value.len()).sum::<usize>()
+            + index_map_len
+    }
+
+    /// Size of the bitmap of minted indices, one bit per token of shuffled airdrops
+    pub fn index_map_len(airdrop_amount: u64, shuffled: bool) -> usize {
+        match shuffled {
+            true => (airdrop_amount >> 3) as usize + (airdrop_amount & 7 != 0) as usize,
+            false => 0,
+        }
+    }
+
+    /// Account size required to store this config
+    pub fn data_len(&self) -> usize {
+        AirdropConfig::space(
+            &[
+                &self.base_uri,
+                &self.uri_extension,
+                &self.name_template,
+                &self.symbol,
+                &self.hidden_name_template,
+                &self.hidden_uri,
+            ],
+            AirdropConfig::index_map_len(self.airdrop_amount, self.shuffled),
+        )
+    }
+
+    /// Bitmap of minted indices in `data` of the config account. Bit `index % 8`
+    /// of byte `index / 8` is set once token `index` is minted.
+    /// Empty unless indices are shuffled
+    pub fn index_map<'a>(&self, data: &'a mut [u8]) -> Result<&'a mut [u8], ProgramError> {
+        let index_map_len = AirdropConfig::index_map_len(self.airdrop_amount, self.shuffled);
+        let end = self.data_len();
+        if data.len() < end {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(&mut data[end - index_map_len..end])
+    }
+
+    /// Tokens are minted with hidden metadata and revealed later by admin
+    pub fn is_hidden(&self) -> bool {
+        !self.hidden_uri.is_empty()
+    }
+
+    /// Metadata files are mapped to airdrop indices by the seed committed before the sale
+    pub fn is_fair_mint(&self) -> bool {
+        self.seed_commitment != Hash::default()
+    }
+
+    /// Sale of a fair mint airdrop is ended by `EndSale`, no more tokens are minted
+    pub fn is_sale_ended(&self) -> bool {
+        self.sale_end_slot != 0
+    }
+
+    /// Token name and URI are read from config lines instead of templates
+    pub fn has_config_lines(&self) -> bool {
+        self.config_lines != Pubkey::default()
+    }
+
+    /// Fails with `InvalidAccountData` if `dst` is shorter than `data_len()`
+    /// or a string is longer than 255 bytes. Index map is left intact
+    pub fn pack_into_slice(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        if dst.len() < self.data_len() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (header, strings) = dst.split_at_mut(AirdropConfig::HEADER_LEN);
+        let header = array_mut_ref![header, 0, AirdropConfig::HEADER_LEN];
 
         let (
             initialized,
             airdrop_authority,
             airdrop_index,
             airdrop_amount,
-            metadata_prefix,
-            symbol,
             airdrop_users,
             revenues_wallet,
             admin_account,
             price,
-        ) = mut_array_refs![dst, 1, 32, 8, 8, 32, 8, 8, 32, 32, 8];
+            index_offset,
+            index_padding,
+            shuffled,
+            provenance_hash,
+            seed_commitment,
+            seed_revealed,
+            sale_end_slot,
+            starting_offset,
+            config_lines,
+            pda_mints,
+            require_user_signature,
+            require_user_payer,
+        ) = mut_array_refs![
+            header, 1, 32, 8, 8, 8, 32, 32, 8, 8, 1, 1, 32, 32, 1, 8, 8, 32, 1, 1, 1
+        ];
 
-        initialized[0] = self.initialized as u8;
+        initialized[0] = pack_account_type(self.initialized, AccountType::AirdropConfig);
         airdrop_authority.copy_from_slice(&self.airdrop_authority.to_bytes());
         airdrop_index.copy_from_slice(&self.airdrop_index.to_le_bytes());
         airdrop_amount.copy_from_slice(&self.airdrop_amount.to_le_bytes());
-        metadata_prefix.copy_from_slice(&self.metadata_prefix);
-        symbol.copy_from_slice(&self.symbol);
         airdrop_users.copy_from_slice(&self.airdrop_users.to_le_bytes());
         revenues_wallet.copy_from_slice(&self.revenues_wallet.to_bytes());
         admin_account.copy_from_slice(&self.admin_account.to_bytes());
         price.copy_from_slice(&self.price.to_le_bytes());
+        index_offset.copy_from_slice(&self.index_offset.to_le_bytes());
+        index_padding[0] = self.index_padding;
+        shuffled[0] = self.shuffled as u8;
+        provenance_hash.copy_from_slice(self.provenance_hash.as_ref());
+        seed_commitment.copy_from_slice(self.seed_commitment.as_ref());
+        seed_revealed[0] = self.seed_revealed as u8;
+        sale_end_slot.copy_from_slice(&self.sale_end_slot.to_le_bytes());
+        starting_offset.copy_from_slice(&self.starting_offset.to_le_bytes());
+        config_lines.copy_from_slice(&self.config_lines.to_bytes());
+        pda_mints[0] = self.pda_mints as u8;
+        require_user_signature[0] = self.require_user_signature as u8;
+        require_user_payer[0] = self.require_user_payer as u8;
+
+        let strings = pack_string(&self.base_uri, strings)?;
+        let strings = pack_string(&self.uri_extension, strings)?;
+        let strings = pack_string(&self.name_template, strings)?;
+        let strings = pack_string(&self.symbol, strings)?;
+        let strings = pack_string(&self.hidden_name_template, strings)?;
+        pack_string(&self.hidden_uri, strings)?;
+
+        Ok(())
     }
 
-    fn unpack_from_slice(src: &[u8]) -> Result<Self, solana_program::program_error::ProgramError> {
-        let src = array_ref![src, 0, AirdropConfig::LEN];
+    /// Fails with `InvalidAccountData` on truncated data, bad strings, missing index map
+    /// or unknown account type and with `NotAirdropConfig` on data of another account type.
+    /// Data of an uninitialized account unpacks to an uninitialized config with empty strings
+    pub fn unpack_from_slice(src: &[u8]) -> Result<AirdropConfig, ProgramError> {
+        peek_account_type(src, AccountType::AirdropConfig)?;
+        if src.len() < AirdropConfig::HEADER_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (header, strings) = src.split_at(AirdropConfig::HEADER_LEN);
+        let header = array_ref![header, 0, AirdropConfig::HEADER_LEN];
 
         let (
             initialized_src,
             airdrop_authority_src,
             airdrop_index_src,
             airdrop_amount_src,
-            metadata_prefix_src,
-            symbol_src,
             airdrop_users_src,
             revenues_wallet_src,
             admin_account_src,
             price_src,
-        ) = array_refs![src, 1, 32, 8, 8, 32, 8, 8, 32, 32, 8];
+            index_offset_src,
+            index_padding_src,
+            shuffled_src,
+            provenance_hash_src,
+            seed_commitment_src,
+            seed_revealed_src,
+            sale_end_slot_src,
+            starting_offset_src,
+            config_lines_src,
+            pda_mints_src,
+            require_user_signature_src,
+            require_user_payer_src,
+        ) = array_refs![header, 1, 32, 8, 8, 8, 32, 32, 8, 8, 1, 1, 32, 32, 1, 8, 8, 32, 1, 1, 1];
 
-        let initialized = match initialized_src {
-            [0] => false,
-            [1] => true,
-            _ => return Err(ProgramError::InvalidAccountData),
+        let initialized = unpack_account_type(initialized_src, AccountType::AirdropConfig)?;
+        // Like strings, flags of an uninitialized config are not read
+        let unpack_flag = |src: &[u8; 1]| match (initialized, src[0]) {
+            (false, _) | (true, 0) => Ok(false),
+            (true, 1) => Ok(true),
+            _ => Err(ProgramError::InvalidAccountData),
         };
+        let shuffled = unpack_flag(shuffled_src)?;
+        let seed_revealed = unpack_flag(seed_revealed_src)?;
+        let pda_mints = unpack_flag(pda_mints_src)?;
+        let require_user_signature = unpack_flag(require_user_signature_src)?;
+        let require_user_payer = unpack_flag(require_user_payer_src)?;
 
-        let airdrop_authority = Pubkey::new_from_array(*airdrop_authority_src);
-        let airdrop_index = u64::from_le_bytes(*airdrop_index_src);
-        let airdrop_amount = u64::from_le_bytes(*airdrop_amount_src);
-        let symbol = symbol_src.clone();
-        let metadata_prefix = metadata_prefix_src.clone();
-        let airdrop_users = u64::from_le_bytes(*airdrop_users_src);
-        let revenues_wallet = Pubkey::new_from_array(*revenues_wallet_src);
-        let admin_account = Pubkey::new_from_array(*admin_account_src);
-        let price = u64::from_le_bytes(*price_src);
-
-        Ok(AirdropConfig {
+        // Strings follow the header in field order
+        let mut strings = strings;
+        let mut next_string = || match initialized {
+            true => unpack_string(strings).map(|(value, rest)| {
+                strings = rest;
+                value
+            }),
+            false => Ok(String::new()),
+        };
+
+        let config = AirdropConfig {
             initialized,
-            airdrop_authority,
-            airdrop_index,
-            airdrop_amount,
-            symbol,
-            metadata_prefix,
-            airdrop_users,
-            revenues_wallet,
-            admin_account,
-            price,
-        })
+            airdrop_authority: Pubkey::new_from_array(*airdrop_authority_src),
+            airdrop_index: u64::from_le_bytes(*airdrop_index_src),
+            airdrop_amount: u64::from_le_bytes(*airdrop_amount_src),
+            airdrop_users: u64::from_le_bytes(*airdrop_users_src),
+            revenues_wallet: Pubkey::new_from_array(*revenues_wallet_src),
+            admin_account: Pubkey::new_from_array(*admin_account_src),
+            price: u64::from_le_bytes(*price_src),
+            index_offset: u64::from_le_bytes(*index_offset_src),
+            index_padding: index_padding_src[0],
+            shuffled,
+            provenance_hash: Hash::new_from_array(*provenance_hash_src),
+            seed_commitment: Hash::new_from_array(*seed_commitment_src),
+            seed_revealed,
+            sale_end_slot: u64::from_le_bytes(*sale_end_slot_src),
+            starting_offset: u64::from_le_bytes(*starting_offset_src),
+            config_lines: Pubkey::new_from_array(*config_lines_src),
+            pda_mints,
+            require_user_signature,
+            require_user_payer,
+            base_uri: next_string()?,
+            uri_extension: next_string()?,
+            name_template: next_string()?,
+            symbol: next_string()?,
+            hidden_name_template: next_string()?,
+            hidden_uri: next_string()?,
+        };
+
+        if config.initialized && src.len() < config.data_len() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(config)
     }
-}
 
-impl AirdropConfig {
+    /// Fails with `InvalidAccountData` on malformed data or unknown account type
+    /// and with `NotAirdropConfig` on data of another account type
     pub fn unpack_from_account(account: &AccountInfo) -> Result<AirdropConfig, ProgramError> {
-        Self::unpack_unchecked(&account.data.borrow())
+        Self::unpack_from_slice(&account.data.borrow())
     }
 
     pub fn pack_into_account(
-        state: AirdropConfig,
+        state: &AirdropConfig,
         account: &AccountInfo,
     ) -> Result<(), ProgramError> {
-        Self::pack(state, &mut account.data.borrow_mut())
+        state.pack_into_slice(&mut account.data.borrow_mut())
     }
 }
 
@@ -143,37 +371,51 @@ impl IsInitialized for AirdropUserData {
 }
 
 impl Pack for AirdropUserData {
-    const LEN: usize = 1 + 32 + 32 + 8 + 8;
+    const LEN: usize = 1 + 32 + 32 + 8 + 8 + 8;
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, AirdropUserData::LEN];
 
-        let (initialized, airdrop, user, mints_amount, locked_till) =
-            mut_array_refs![dst, 1, 32, 32, 8, 8];
+        let (initialized, airdrop, user, mints_amount, locked_till, draw_slot) =
+            mut_array_refs![dst, 1, 32, 32, 8, 8, 8];
 
-        initialized[0] = self.initialized as u8;
+        initialized[0] = pack_account_type(self.initialized, AccountType::AirdropUserData);
         airdrop.copy_from_slice(&self.airdrop.to_bytes());
         user.copy_from_slice(&self.user.to_bytes());
         mints_amount.copy_from_slice(&self.mints_amount.to_le_bytes());
         locked_till.copy_from_slice(&self.locked_till.to_le_bytes());
+        draw_slot.copy_from_slice(&self.draw_slot.to_le_bytes());
+    }
+
+    /// Checks account type before data length, unlike the default implementation
+    fn unpack_unchecked(input: &[u8]) -> Result<Self, ProgramError> {
+        peek_account_type(input, AccountType::AirdropUserData)?;
+        if input.len() != AirdropUserData::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Self::unpack_from_slice(input)
     }
 
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
         let src = array_ref![src, 0, AirdropUserData::LEN];
 
-        let (initialized_src, airdrop_src, user_src, mints_amount_src, locked_till_src) =
-            array_refs![src, 1, 32, 32, 8, 8];
+        let (
+            initialized_src,
+            airdrop_src,
+            user_src,
+            mints_amount_src,
+            locked_till_src,
+            draw_slot_src,
+        ) = array_refs![src, 1, 32, 32, 8, 8, 8];
 
-        let initialized = match initialized_src {
-            [0] => false,
-            [1] => true,
-            _ => return Err(ProgramError::InvalidAccountData),
-        };
+        let initialized = unpack_account_type(initialized_src, AccountType::AirdropUserData)?;
 
         let airdrop = Pubkey::new_from_array(*airdrop_src);
         let user = Pubkey::new_from_array(*user_src);
         let mints_amount = u64::from_le_bytes(*mints_amount_src);
-        let locked_till = u64::from_le_bytes(*locked_till_src);
+        let locked_till = i64::from_le_bytes(*locked_till_src);
+        let draw_slot = u64::from_le_bytes(*draw_slot_src);
 
         Ok(AirdropUserData {
             initialized,
@@ -181,11 +423,19 @@ impl Pack for AirdropUserData {
             user,
             mints_amount,
             locked_till,
+            draw_slot,
         })
     }
 }
 
 impl AirdropUserData {
+    /// Draw is committed and its slot hash is still in the slot hashes sysvar at `slot`
+    pub fn has_draw(&self, slot: u64) -> bool {
+        self.draw_slot != 0 && slot.saturating_sub(self.draw_slot) <= MAX_ENTRIES as u64
+    }
+
+    /// Fails with `InvalidAccountData` on wrong data length or unknown account type
+    /// and with `NotAirdropUserData` on data of another account type
     pub fn unpack_from_account(account: &AccountInfo) -> Result<AirdropUserData, ProgramError> {
         Self::unpack_unchecked(&account.data.borrow())
     }
@@ -198,6 +448,173 @@ impl AirdropUserData {
     }
 }
 
+impl ConfigLines {
+    /// Size of the fixed part, lines follow it
+    pub const HEADER_LEN: usize = 1 + 32;
+    /// Line is `[name length: u8][name: 32][uri length: u8][uri: 200]`, zero padded
+    pub const LINE_LEN: usize = 1 + MAX_NAME_LENGTH + 1 + MAX_URI_LENGTH;
+
+    /// Account size required to store a line for every token of the airdrop
+    pub fn space(airdrop_amount: u64) -> usize {
+        (airdrop_amount as usize)
+            .saturating_mul(ConfigLines::LINE_LEN)
+            .saturating_add(ConfigLines::HEADER_LEN)
+    }
+
+    pub fn pack_into_slice(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        if dst.len() < ConfigLines::HEADER_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let dst = array_mut_ref![dst, 0, ConfigLines::HEADER_LEN];
+        let (initialized, airdrop) = mut_array_refs![dst, 1, 32];
+
+        initialized[0] = pack_account_type(self.initialized, AccountType::ConfigLines);
+        airdrop.copy_from_slice(&self.airdrop.to_bytes());
+
+        Ok(())
+    }
+
+    /// Fails with `InvalidAccountData` on short data or unknown account type
+    /// and with `NotConfigLines` on data of another account type
+    pub fn unpack_from_slice(src: &[u8]) -> Result<ConfigLines, ProgramError> {
+        peek_account_type(src, AccountType::ConfigLines)?;
+        if src.len() < ConfigLines::HEADER_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let src = array_ref![src, 0, ConfigLines::HEADER_LEN];
+        let (initialized_src, airdrop_src) = array_refs![src, 1, 32];
+
+        Ok(ConfigLines {
+            initialized: unpack_account_type(initialized_src, AccountType::ConfigLines)?,
+            airdrop: Pubkey::new_from_array(*airdrop_src),
+        })
+    }
+
+    pub fn unpack_from_account(account: &AccountInfo) -> Result<ConfigLines, ProgramError> {
+        Self::unpack_from_slice(&account.data.borrow())
+    }
+
+    pub fn pack_into_account(&self, account: &AccountInfo) -> Result<(), ProgramError> {
+        self.pack_into_slice(&mut account.data.borrow_mut())
+    }
+
+    /// Line of the airdrop index in `data` of the config lines account.
+    /// `None` if the line is not uploaded yet
+    pub fn read_line(data: &[u8], index: u64) -> Result<Option<ConfigLine>, ProgramError> {
+        let line = ConfigLines::line_range(data.len(), index)
+            .map(|range| array_ref![data[range], 0, ConfigLines::LINE_LEN])
+            .ok_or(ProgramError::InvalidAccountData)?;
+        let (name_len, name, uri_len, uri) =
+            array_refs![line, 1, MAX_NAME_LENGTH, 1, MAX_URI_LENGTH];
+
+        if name_len[0] == 0 {
+            return Ok(None);
+        }
+
+        let unpack = |len: u8, value: &[u8]| {
+            value
+                .get(..len as usize)
+                .and_then(|value| std::str::from_utf8(value).ok())
+                .map(str::to_string)
+                .ok_or(ProgramError::InvalidAccountData)
+        };
+
+        Ok(Some(ConfigLine {
+            name: unpack(name_len[0], name)?,
+            uri: unpack(uri_len[0], uri)?,
+        }))
+    }
+
+    /// Writes the line of the airdrop index to `data` of the config lines account.
+    /// Fails with `InvalidAccountData` if the index is out of range or a string does not fit
+    pub fn write_line(data: &mut [u8], index: u64, line: &ConfigLine) -> Result<(), ProgramError> {
+        if line.name.len() > MAX_NAME_LENGTH || line.uri.len() > MAX_URI_LENGTH {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let dst = ConfigLines::line_range(data.len(), index)
+            .map(|range| array_mut_ref![data[range], 0, ConfigLines::LINE_LEN])
+            .ok_or(ProgramError::InvalidAccountData)?;
+        let (name_len, name, uri_len, uri) =
+            mut_array_refs![dst, 1, MAX_NAME_LENGTH, 1, MAX_URI_LENGTH];
+
+        name_len[0] = line.name.len() as u8;
+        name.fill(0);
+        name[..line.name.len()].copy_from_slice(line.name.as_bytes());
+        uri_len[0] = line.uri.len() as u8;
+        uri.fill(0);
+        uri[..line.uri.len()].copy_from_slice(line.uri.as_bytes());
+
+        Ok(())
+    }
+
+    fn line_range(data_len: usize, index: u64) -> Option<std::ops::Range<usize>> {
+        let start = (index as usize)
+            .checked_mul(ConfigLines::LINE_LEN)?
+            .checked_add(ConfigLines::HEADER_LEN)?;
+        let end = start.checked_add(ConfigLines::LINE_LEN)?;
+
+        Some(start..end).filter(|_| end <= data_len)
+    }
+}
+
 impl MintAuthority {
     pub const LEN: usize = 0;
 }
+
+fn pack_account_type(initialized: bool, account_type: AccountType) -> u8 {
+    match initialized {
+        true => account_type as u8,
+        false => AccountType::Uninitialized as u8,
+    }
+}
+
+fn pack_string<'a>(value: &str, dst: &'a mut [u8]) -> Result<&'a mut [u8], ProgramError> {
+    let len = value.len();
+    if len > u8::MAX as usize || dst.len() < 1 + len {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let (value_dst, rest) = dst.split_at_mut(1 + len);
+    value_dst[0] = len as u8;
+    value_dst[1..].copy_from_slice(value.as_bytes());
+
+    Ok(rest)
+}
+
+fn unpack_string(src: &[u8]) -> Result<(String, &[u8]), ProgramError> {
+    let (len, rest) = src.split_first().ok_or(ProgramError::InvalidAccountData)?;
+    let len = *len as usize;
+    if rest.len() < len {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let (value, rest) = rest.split_at(len);
+    let value = std::str::from_utf8(value).or(Err(ProgramError::InvalidAccountData))?;
+
+    Ok((value.to_string(), rest))
+}
+
+/// Account type of `src` is read before its length is checked,
+/// so accounts of another type are reported as such whatever their size
+fn peek_account_type(src: &[u8], expected: AccountType) -> Result<bool, ProgramError> {
+    match src.first() {
+        Some(account_type) => unpack_account_type(&[*account_type], expected),
+        None => Err(ProgramError::InvalidAccountData),
+    }
+}
+
+fn unpack_account_type(src: &[u8; 1], expected: AccountType) -> Result<bool, ProgramError> {
+    match AccountType::from_u8(src[0]) {
+        Some(AccountType::Uninitialized) => Ok(false),
+        Some(account_type) if account_type == expected => Ok(true),
+        Some(_) => Err(match expected {
+            AccountType::AirdropConfig => AirdropError::NotAirdropConfig.into(),
+            AccountType::ConfigLines => AirdropError::NotConfigLines.into(),
+            _ => AirdropError::NotAirdropUserData.into(),
+        }),
+        None => Err(ProgramError::InvalidAccountData),
+    }
+}
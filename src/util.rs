@@ -1,55 +1,94 @@
-use metaplex_token_metadata::state::Creator;
+use std::slice;
+
+use arrayref::{array_ref, array_refs};
+use metaplex_token_metadata::state::{Creator, Data, Metadata};
 use solana_program::{
     account_info::AccountInfo,
     clock::Clock,
     entrypoint::ProgramResult,
+    hash::{hash, hashv},
     msg,
     program::{invoke, invoke_signed},
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack},
     pubkey::Pubkey,
     rent::Rent,
+    slot_hashes::MAX_ENTRIES,
     system_instruction,
+    sysvar::Sysvar,
 };
 
-use crate::state::{AirdropConfig, AirdropUserData, MintAuthority, MINT_AUTHORITY, USER_DATA};
+use crate::{
+    error::AirdropError,
+    event::{
+        AirdropEvent, AirdropInitializedEvent, MintedEvent, RevealedEvent, SeedRevealedEvent,
+        UserRegisteredEvent,
+    },
+    instruction::{AddConfigLinesArgs, InitializeAirdropArgs},
+    return_data::MintOneResult,
+    state::{
+        AirdropConfig, AirdropUserData, ConfigLines, MintAuthority, MINT, MINT_AUTHORITY,
+        NAME_PLACEHOLDER, USER_DATA, USER_LOCK_DURATION,
+    },
+};
 
+#[allow(clippy::too_many_arguments)]
 pub fn process_initialize_airdrop_logic<'a>(
     airdrop_account: &AccountInfo,
     airdrop_authority: &AccountInfo,
-    mint_authority: &'a AccountInfo<'a>,
+    mint_authority: &AccountInfo<'a>,
     revenues_account: &AccountInfo,
     admin_account: &AccountInfo,
-    fee_payer: &'a AccountInfo<'a>,
-    airdrop_amount: u64,
-    metadata_prefix: [u8; 32],
-    symbol: [u8; 8],
-    price: u64,
+    fee_payer: &AccountInfo<'a>,
+    args: InitializeAirdropArgs,
     program_id: &Pubkey,
     rent: Rent,
     mint_authority_bump: u8,
 ) -> ProgramResult {
     let airdrop_data = AirdropConfig::unpack_from_account(airdrop_account)?;
 
-    msg!("Check if airdrop data already initialized");
+    trace!("Check if airdrop data already initialized");
     if airdrop_data.is_initialized() {
-        return Err(ProgramError::AccountAlreadyInitialized);
+        msg!(
+            "Airdrop config {} is already initialized",
+            airdrop_account.key
+        );
+        return Err(AirdropError::AlreadyInitialized.into());
     }
 
     let airdrop_data = AirdropConfig {
         initialized: true,
         airdrop_authority: *airdrop_authority.key,
         airdrop_index: 0,
-        airdrop_amount,
-        metadata_prefix,
-        symbol,
+        airdrop_amount: args.airdrop_amount,
         airdrop_users: 0,
         admin_account: *admin_account.key,
         revenues_wallet: *revenues_account.key,
-        price,
+        price: args.price,
+        index_offset: args.index_offset,
+        index_padding: args.index_padding,
+        shuffled: args.shuffled,
+        provenance_hash: args.provenance_hash,
+        seed_commitment: args.seed_commitment,
+        seed_revealed: false,
+        sale_end_slot: 0,
+        starting_offset: 0,
+        config_lines: args.config_lines,
+        pda_mints: args.pda_mints,
+        require_user_signature: args.require_user_signature,
+        require_user_payer: args.require_user_payer,
+        base_uri: args.base_uri,
+        uri_extension: args.uri_extension,
+        name_template: args.name_template,
+        symbol: args.symbol,
+        hidden_name_template: args.hidden_name_template,
+        hidden_uri: args.hidden_uri,
     };
 
-    AirdropConfig::pack_into_account(airdrop_data, airdrop_account)?;
+    AirdropConfig::pack_into_account(&airdrop_data, airdrop_account)?;
+    airdrop_data
+        .index_map(&mut airdrop_account.data.borrow_mut())?
+        .fill(0);
 
     let lamports = rent.minimum_balance(MintAuthority::LEN);
     let mint_authority_seed = &[
@@ -58,7 +97,7 @@ pub fn process_initialize_airdrop_logic<'a>(
         &[mint_authority_bump],
     ];
 
-    msg!("Initialize mint authority");
+    trace!("Initialize mint authority");
     invoke_signed(
         &system_instruction::create_account(
             fee_payer.key,
@@ -71,20 +110,31 @@ pub fn process_initialize_airdrop_logic<'a>(
         &[mint_authority_seed],
     )?;
 
+    AirdropEvent::AirdropInitialized(AirdropInitializedEvent {
+        airdrop: *airdrop_account.key,
+        airdrop_authority: airdrop_data.airdrop_authority,
+        revenues_wallet: airdrop_data.revenues_wallet,
+        admin_account: airdrop_data.admin_account,
+        airdrop_amount: airdrop_data.airdrop_amount,
+        price: airdrop_data.price,
+        timestamp: Clock::get()?.unix_timestamp,
+    })
+    .emit();
+
     Ok(())
 }
 
 pub fn process_initialize_airdrop_user_account_logic<'a>(
-    user_data_account: &'a AccountInfo<'a>,
-    user: &'a AccountInfo<'a>,
-    airdrop_config: &'a AccountInfo<'a>,
-    fee_payer: &'a AccountInfo<'a>,
+    user_data_account: &AccountInfo<'a>,
+    user: &AccountInfo<'a>,
+    airdrop_config: &AccountInfo<'a>,
+    fee_payer: &AccountInfo<'a>,
     rent: Rent,
     program_id: &Pubkey,
     user_data_account_bump: u8,
 ) -> ProgramResult {
     // Create account
-    msg!("Initialize user airdrop account");
+    trace!("Initialize user airdrop account");
     let lamports = rent.minimum_balance(AirdropUserData::LEN);
     let user_data_account_seed = &[
         USER_DATA.as_bytes(),
@@ -112,53 +162,85 @@ pub fn process_initialize_airdrop_user_account_logic<'a>(
         user: *user.key,
         mints_amount: 0,
         locked_till: 0,
+        draw_slot: 0,
     };
 
     AirdropUserData::pack_into_account(user_account_data, user_data_account)?;
 
     // Increase user counter
     let mut airdrop_config_data = AirdropConfig::unpack_from_account(airdrop_config)?;
-    airdrop_config_data.airdrop_users += 1;
-    AirdropConfig::pack_into_account(airdrop_config_data, airdrop_config)?;
+    airdrop_config_data.airdrop_users = airdrop_config_data
+        .airdrop_users
+        .checked_add(1)
+        .ok_or(AirdropError::ArithmeticOverflow)?;
+    AirdropConfig::pack_into_account(&airdrop_config_data, airdrop_config)?;
+
+    AirdropEvent::UserRegistered(UserRegisteredEvent {
+        airdrop: *airdrop_config.key,
+        user: *user.key,
+        timestamp: Clock::get()?.unix_timestamp,
+    })
+    .emit();
 
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn process_airdrop_one_logic<'a>(
-    airdrop_config: &'a AccountInfo<'a>,
-    user_data_account: &'a AccountInfo<'a>,
-    mint: &'a AccountInfo<'a>,
-    user: &'a AccountInfo<'a>,
-    user_token_account: &'a AccountInfo<'a>,
-    metadata: &'a AccountInfo<'a>,
-    mint_authority: &'a AccountInfo<'a>,
-    rent_account: &'a AccountInfo<'a>,
+    airdrop_config: &AccountInfo<'a>,
+    user_data_account: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    user: &AccountInfo<'a>,
+    user_token_account: &AccountInfo<'a>,
+    metadata: &AccountInfo<'a>,
+    mint_authority: &AccountInfo<'a>,
+    rent_account: &AccountInfo<'a>,
     clock: Clock,
     rent: Rent,
-    payer: &'a AccountInfo<'a>,
-    admin: &'a AccountInfo<'a>,
-    revenue_wallet: &'a AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    admin: &AccountInfo<'a>,
+    revenue_wallet: &AccountInfo<'a>,
     mint_authority_bump: u8,
-    system_program: &'a AccountInfo<'a>,
-    token_program: &'a AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    index_seed: Option<u64>,
+    config_lines: Option<&AccountInfo<'a>>,
+    mint_bump: Option<u8>,
 ) -> ProgramResult {
+    let mut airdrop_data = AirdropConfig::unpack_from_account(airdrop_config)?;
+
     // Create mint account for token
     let lamports = rent.minimum_balance(spl_token::state::Mint::LEN);
 
-    msg!("Initialize account for mint");
-    // Create mint
-    invoke(
-        &system_instruction::create_account(
-            payer.key,
-            mint.key,
+    trace!("Initialize account for mint");
+    // Create mint, signing for its address if it is derived from the minted amount
+    match mint_bump {
+        Some(mint_bump) => create_pda_account(
+            payer,
+            mint,
             lamports,
             spl_token::state::Mint::LEN as u64,
             &spl_token::id(),
-        ),
-        &[payer.clone(), mint.clone()],
-    )?;
+            &[
+                MINT.as_bytes(),
+                airdrop_config.key.as_ref(),
+                &airdrop_data.airdrop_index.to_le_bytes(),
+                &[mint_bump],
+            ],
+        )?,
+        None => invoke(
+            &system_instruction::create_account(
+                payer.key,
+                mint.key,
+                lamports,
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            &[payer.clone(), mint.clone()],
+        )?,
+    }
 
-    msg!("Fill mint data");
+    trace!("Fill mint data");
     // Initialize mint
     invoke(
         &spl_token::instruction::initialize_mint(
@@ -171,30 +253,85 @@ pub fn process_airdrop_one_logic<'a>(
         &[mint.clone(), rent_account.clone()],
     )?;
 
-    msg!("Initialize user token account");
-    // Initialize user token account
-    invoke(
-        &spl_associated_token_account::instruction::create_associated_token_account(
-            payer.key, user.key, mint.key,
-        ),
-        &[
-            payer.clone(),
-            user_token_account.clone(),
-            user.clone(),
-            mint.clone(),
-            system_program.clone(),
-            token_program.clone(),
-        ],
-    )?;
+    // Address of the user token account is known together with the mint,
+    // so it may be created before the mint
+    match *user_token_account.owner == spl_token::id() {
+        true => {
+            trace!("Check existing user token account");
+            assert_recipient_token_account(user_token_account, user.key, mint.key)?;
+        }
+        false => {
+            trace!("Initialize user token account");
+            invoke(
+                &spl_associated_token_account::instruction::create_associated_token_account(
+                    payer.key, user.key, mint.key,
+                ),
+                &[
+                    payer.clone(),
+                    user_token_account.clone(),
+                    user.clone(),
+                    mint.clone(),
+                    system_program.clone(),
+                    token_program.clone(),
+                ],
+            )?;
+        }
+    }
 
-    let mut airdrop_data = AirdropConfig::unpack_from_account(airdrop_config)?;
-    let symbol_str = str_from_u8_nul_utf8(&airdrop_data.symbol)
-        .or(Err(ProgramError::InvalidAccountData))?
-        .to_string();
+    let airdrop_index = match index_seed {
+        Some(seed) => {
+            trace!("Draw airdrop index");
+            let remaining = airdrop_data
+                .airdrop_amount
+                .checked_sub(airdrop_data.airdrop_index)
+                .ok_or(AirdropError::ArithmeticOverflow)?;
 
-    let uri_prefix = str_from_u8_nul_utf8(&airdrop_data.metadata_prefix)
-        .or(Err(ProgramError::InvalidAccountData))?
-        .to_string();
+            let mut data = airdrop_config.data.borrow_mut();
+            let index_map = airdrop_data.index_map(&mut data)?;
+            match draw_index(index_map, airdrop_data.airdrop_amount, remaining, seed) {
+                Some(airdrop_index) => airdrop_index,
+                None => {
+                    msg!("Index map has less than {} free indices", remaining);
+                    return Err(ProgramError::InvalidAccountData);
+                }
+            }
+        }
+        None => airdrop_data.airdrop_index,
+    };
+    let token_number = token_number(
+        airdrop_index,
+        airdrop_data.index_offset,
+        airdrop_data.index_padding,
+    )
+    .ok_or(AirdropError::ArithmeticOverflow)?;
+
+    // Hidden tokens get their final metadata on reveal
+    let (name, uri) = match airdrop_data.is_hidden() {
+        true => (
+            metadata_name(&airdrop_data.hidden_name_template, &token_number),
+            airdrop_data.hidden_uri.clone(),
+        ),
+        false => match config_lines {
+            Some(config_lines) => {
+                trace!("Read config line");
+                match ConfigLines::read_line(&config_lines.data.borrow(), airdrop_index)? {
+                    Some(line) => (line.name, line.uri),
+                    None => {
+                        msg!("Config line {} is not uploaded", airdrop_index);
+                        return Err(AirdropError::ConfigLineMissing.into());
+                    }
+                }
+            }
+            None => (
+                metadata_name(&airdrop_data.name_template, &token_number),
+                metadata_uri(
+                    &airdrop_data.base_uri,
+                    &token_number,
+                    &airdrop_data.uri_extension,
+                ),
+            ),
+        },
+    };
 
     let creators = vec![
         Creator {
@@ -215,7 +352,7 @@ pub fn process_airdrop_one_logic<'a>(
         &[mint_authority_bump],
     ];
 
-    msg!("Initialize metadata");
+    trace!("Initialize metadata");
     // Create token metadata
     invoke_signed(
         &metaplex_token_metadata::instruction::create_metadata_accounts(
@@ -225,9 +362,9 @@ pub fn process_airdrop_one_logic<'a>(
             *mint_authority.key,
             *payer.key,
             *mint_authority.key,
-            symbol_str.clone() + &format!(" #{}", airdrop_data.airdrop_index),
-            symbol_str,
-            uri_prefix + &format!("{}.json", airdrop_data.airdrop_index),
+            name,
+            airdrop_data.symbol.clone(),
+            uri,
             Some(creators),
             1000,
             false,
@@ -245,7 +382,7 @@ pub fn process_airdrop_one_logic<'a>(
         &[mint_authority_seed],
     )?;
 
-    msg!("Mint to user");
+    trace!("Mint to user");
     // Mint one token to user
     invoke_signed(
         &spl_token::instruction::mint_to(
@@ -264,14 +401,18 @@ pub fn process_airdrop_one_logic<'a>(
         &[mint_authority_seed],
     )?;
 
-    msg!("Update metadata");
-    // Mark NFT as sold and transfer update authority
+    trace!("Update metadata");
+    // Mark NFT as sold and transfer update authority, hidden tokens keep it until reveal
+    let new_update_authority = match airdrop_data.is_hidden() {
+        true => None,
+        false => Some(*admin.key),
+    };
     invoke_signed(
         &metaplex_token_metadata::instruction::update_metadata_accounts(
             metaplex_token_metadata::id(),
             *metadata.key,
             *mint_authority.key,
-            Some(*admin.key),
+            new_update_authority,
             None,
             Some(true),
         ),
@@ -279,7 +420,7 @@ pub fn process_airdrop_one_logic<'a>(
         &[mint_authority_seed],
     )?;
 
-    msg!("Revoke mint authority");
+    trace!("Revoke mint authority");
     // Revoke mint authority
     invoke_signed(
         &spl_token::instruction::set_authority(
@@ -294,28 +435,447 @@ pub fn process_airdrop_one_logic<'a>(
         &[mint_authority_seed],
     )?;
 
-    msg!("Transfer SOL");
+    trace!("Transfer SOL");
     // Transfer SOL to revenue wallet
     invoke(
         &system_instruction::transfer(payer.key, revenue_wallet.key, airdrop_data.price),
         &[payer.clone(), revenue_wallet.clone()],
     )?;
 
-    msg!("Write changes to program accounts");
-    airdrop_data.airdrop_index += 1;
-    AirdropConfig::pack_into_account(airdrop_data, airdrop_config)?;
+    AirdropEvent::Minted(MintedEvent {
+        airdrop: *airdrop_config.key,
+        recipient: *user.key,
+        mint: *mint.key,
+        payer: *payer.key,
+        airdrop_index,
+        price: airdrop_data.price,
+        timestamp: clock.unix_timestamp,
+    })
+    .emit();
+
+    MintOneResult {
+        airdrop_index,
+        mint: *mint.key,
+        price: airdrop_data.price,
+    }
+    .set();
+
+    trace!("Write changes to program accounts");
+    airdrop_data.airdrop_index = airdrop_data
+        .airdrop_index
+        .checked_add(1)
+        .ok_or(AirdropError::ArithmeticOverflow)?;
+    AirdropConfig::pack_into_account(&airdrop_data, airdrop_config)?;
     let mut user_data = AirdropUserData::unpack_from_account(user_data_account)?;
-    user_data.mints_amount += 1;
-    user_data.locked_till = (clock.unix_timestamp + 21600_i64) as u64; // Current time plus 6 hours
+    user_data.mints_amount = user_data
+        .mints_amount
+        .checked_add(1)
+        .ok_or(AirdropError::ArithmeticOverflow)?;
+    user_data.locked_till = clock
+        .unix_timestamp
+        .checked_add(USER_LOCK_DURATION)
+        .ok_or(AirdropError::ArithmeticOverflow)?;
+    user_data.draw_slot = 0;
     AirdropUserData::pack_into_account(user_data, user_data_account)?;
 
     Ok(())
 }
 
-fn str_from_u8_nul_utf8(utf8_src: &[u8]) -> Result<&str, std::str::Utf8Error> {
-    let nul_range_end = utf8_src
-        .iter()
-        .position(|&c| c == b'\0')
-        .unwrap_or(utf8_src.len()); // default to length if no `\0` present
-    ::std::str::from_utf8(&utf8_src[0..nul_range_end])
+pub fn process_commit_draw_logic(
+    user_data_account: &AccountInfo,
+    mut user_data: AirdropUserData,
+    clock: &Clock,
+) -> ProgramResult {
+    trace!("Commit draw and lock user");
+    user_data.draw_slot = clock.slot;
+    user_data.locked_till = clock
+        .unix_timestamp
+        .checked_add(USER_LOCK_DURATION)
+        .ok_or(AirdropError::ArithmeticOverflow)?;
+    AirdropUserData::pack_into_account(user_data, user_data_account)?;
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn process_reveal_logic<'a>(
+    airdrop_config: &AccountInfo<'a>,
+    airdrop_data: &AirdropConfig,
+    metadata: &AccountInfo<'a>,
+    mint_authority: &AccountInfo<'a>,
+    airdrop_index: u64,
+    mint_authority_bump: u8,
+    timestamp: i64,
+) -> ProgramResult {
+    let token = Metadata::from_account_info(metadata)?;
+
+    // Fair mint shifts metadata files by the starting offset, token name keeps the airdrop index
+    let file_number = token_number(
+        metadata_file_index(
+            airdrop_index,
+            airdrop_data.starting_offset,
+            airdrop_data.airdrop_amount,
+        ),
+        airdrop_data.index_offset,
+        airdrop_data.index_padding,
+    )
+    .ok_or(AirdropError::ArithmeticOverflow)?;
+
+    let token_number = token_number(
+        airdrop_index,
+        airdrop_data.index_offset,
+        airdrop_data.index_padding,
+    )
+    .ok_or(AirdropError::ArithmeticOverflow)?;
+
+    let name = metadata_name(&airdrop_data.name_template, &token_number);
+    let uri = metadata_uri(
+        &airdrop_data.base_uri,
+        &file_number,
+        &airdrop_data.uri_extension,
+    );
+
+    // Metaplex pads stored strings with zeros
+    let token_name = token.data.name.trim_end_matches('\0');
+    let token_uri = token.data.uri.trim_end_matches('\0');
+
+    trace!("Check if token is minted by the airdrop");
+    let minted_by_airdrop = matches!(
+        token.data.creators.as_deref(),
+        Some([creator, ..]) if creator.address == *mint_authority.key && creator.verified
+    );
+    if !minted_by_airdrop {
+        msg!(
+            "Token metadata {} is not created by airdrop {}",
+            metadata.key,
+            airdrop_config.key
+        );
+        return Err(AirdropError::WrongTokenMetadataAccount.into());
+    }
+
+    trace!("Check if token is hidden");
+    if token_name == name && token_uri == uri {
+        msg!("Token {} is already revealed", airdrop_index);
+        return Err(AirdropError::AlreadyRevealed.into());
+    }
+
+    let hidden_name = metadata_name(&airdrop_data.hidden_name_template, &token_number);
+    if token_name != hidden_name
+        || token_uri != airdrop_data.hidden_uri
+        || token.update_authority != *mint_authority.key
+    {
+        msg!(
+            "Token metadata {} is {:?} at {:?}, expected hidden token {}",
+            metadata.key,
+            token_name,
+            token_uri,
+            airdrop_index
+        );
+        return Err(AirdropError::WrongTokenMetadataAccount.into());
+    }
+
+    let mint_authority_seed = &[
+        MINT_AUTHORITY.as_bytes(),
+        airdrop_config.key.as_ref(),
+        &[mint_authority_bump],
+    ];
+
+    trace!("Reveal metadata");
+    // Write final metadata and transfer update authority
+    invoke_signed(
+        &metaplex_token_metadata::instruction::update_metadata_accounts(
+            metaplex_token_metadata::id(),
+            *metadata.key,
+            *mint_authority.key,
+            Some(airdrop_data.admin_account),
+            Some(Data {
+                name,
+                symbol: token.data.symbol.trim_end_matches('\0').to_string(),
+                uri,
+                seller_fee_basis_points: token.data.seller_fee_basis_points,
+                creators: token.data.creators,
+            }),
+            None,
+        ),
+        &[metadata.clone(), mint_authority.clone()],
+        &[mint_authority_seed],
+    )?;
+
+    AirdropEvent::Revealed(RevealedEvent {
+        airdrop: *airdrop_config.key,
+        mint: token.mint,
+        airdrop_index,
+        timestamp,
+    })
+    .emit();
+
+    Ok(())
+}
+
+pub fn process_end_sale_logic(
+    airdrop_account: &AccountInfo,
+    airdrop_data: &mut AirdropConfig,
+    slot: u64,
+) -> ProgramResult {
+    airdrop_data.sale_end_slot = slot;
+    AirdropConfig::pack_into_account(airdrop_data, airdrop_account)?;
+
+    Ok(())
+}
+
+pub fn process_reveal_seed_logic(
+    airdrop_account: &AccountInfo,
+    airdrop_data: &mut AirdropConfig,
+    seed: [u8; 32],
+    slot_hashes: &AccountInfo,
+    clock: &Clock,
+) -> ProgramResult {
+    trace!("Check seed against commitment");
+    if hash(&seed) != airdrop_data.seed_commitment {
+        msg!(
+            "Seed hashes to {}, expected {}",
+            hash(&seed),
+            airdrop_data.seed_commitment
+        );
+        return Err(AirdropError::WrongSeed.into());
+    }
+
+    trace!("Find hash of the sale end slot");
+    let slot_hash = match committed_slot_hash(
+        &slot_hashes.data.borrow(),
+        airdrop_data.sale_end_slot,
+        clock.slot,
+    ) {
+        Ok(slot_hash) => slot_hash,
+        Err(error) => {
+            msg!(
+                "Sale is ended at slot {}, now {}",
+                airdrop_data.sale_end_slot,
+                clock.slot
+            );
+            return Err(error.into());
+        }
+    };
+    let starting_offset = starting_offset(
+        &seed,
+        airdrop_account.key,
+        &slot_hash,
+        airdrop_data.airdrop_amount,
+    );
+
+    airdrop_data.seed_revealed = true;
+    airdrop_data.starting_offset = starting_offset;
+    AirdropConfig::pack_into_account(airdrop_data, airdrop_account)?;
+
+    AirdropEvent::SeedRevealed(SeedRevealedEvent {
+        airdrop: *airdrop_account.key,
+        seed,
+        slot_hash,
+        starting_offset,
+        timestamp: clock.unix_timestamp,
+    })
+    .emit();
+
+    Ok(())
+}
+
+pub fn process_add_config_lines_logic(
+    airdrop_account: &AccountInfo,
+    config_lines_account: &AccountInfo,
+    config_lines: ConfigLines,
+    args: AddConfigLinesArgs,
+) -> ProgramResult {
+    if !config_lines.initialized {
+        trace!("Initialize config lines");
+        ConfigLines {
+            initialized: true,
+            airdrop: *airdrop_account.key,
+        }
+        .pack_into_account(config_lines_account)?;
+    }
+
+    trace!("Write config lines");
+    let mut data = config_lines_account.data.borrow_mut();
+    for (index, line) in (args.first_index..).zip(&args.lines) {
+        ConfigLines::write_line(&mut data, index, line)?;
+    }
+
+    Ok(())
+}
+
+/// Metadata file of airdrop index 0: hash of the seed, the airdrop and the hash
+/// of the sale end slot modulo `airdrop_amount`
+pub fn starting_offset(
+    seed: &[u8; 32],
+    airdrop: &Pubkey,
+    slot_hash: &[u8; 32],
+    airdrop_amount: u64,
+) -> u64 {
+    let hash = hashv(&[seed, airdrop.as_ref(), slot_hash]);
+
+    u64::from_le_bytes(*array_ref![hash.as_ref(), 0, 8]) % airdrop_amount
+}
+
+/// Index of the metadata file of the token with the given airdrop index,
+/// files are rotated by `starting_offset`
+pub fn metadata_file_index(airdrop_index: u64, starting_offset: u64, airdrop_amount: u64) -> u64 {
+    ((airdrop_index as u128 + starting_offset as u128) % airdrop_amount as u128) as u64
+}
+
+/// Number of the token with the given airdrop index as used in its name and URI.
+/// `None` if the offset overflows
+pub fn token_number(index: u64, index_offset: u64, index_padding: u8) -> Option<String> {
+    let number = index.checked_add(index_offset)?;
+    Some(format!(
+        "{:0width$}",
+        number,
+        width = index_padding as usize
+    ))
+}
+
+/// Token name, `NAME_PLACEHOLDER` of the template is replaced by token number
+pub fn metadata_name(name_template: &str, token_number: &str) -> String {
+    name_template.replacen(NAME_PLACEHOLDER, token_number, 1)
+}
+
+/// Token metadata URI
+pub fn metadata_uri(base_uri: &str, token_number: &str, uri_extension: &str) -> String {
+    format!("{}{}{}", base_uri, token_number, uri_extension)
+}
+
+/// Checks that the recipient token account created before `MintOne` is an initialized
+/// token account of `user` holding `mint`. Fails with `InvalidRecipientTokenAccount` otherwise
+fn assert_recipient_token_account(
+    token_account: &AccountInfo,
+    user: &Pubkey,
+    mint: &Pubkey,
+) -> ProgramResult {
+    let account = spl_token::state::Account::unpack(&token_account.data.borrow())
+        .map_err(|_| AirdropError::InvalidRecipientTokenAccount)?;
+
+    if account.owner != *user || account.mint != *mint {
+        msg!(
+            "Token account {} holds mint {} of {}",
+            token_account.key,
+            account.mint,
+            account.owner
+        );
+        return Err(AirdropError::InvalidRecipientTokenAccount.into());
+    }
+
+    Ok(())
+}
+
+/// Creates account at a program address signed by `seeds`. Anyone may transfer lamports to
+/// a predictable address, which makes `create_account` fail, so a funded account is topped up
+/// to `lamports`, then allocated and assigned instead
+fn create_pda_account<'a>(
+    payer: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    lamports: u64,
+    space: u64,
+    owner: &Pubkey,
+    seeds: &[&[u8]],
+) -> ProgramResult {
+    if account.lamports() == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(payer.key, account.key, lamports, space, owner),
+            &[payer.clone(), account.clone()],
+            &[seeds],
+        );
+    }
+
+    let shortfall = lamports.saturating_sub(account.lamports());
+    if shortfall > 0 {
+        trace!("Top up funded account");
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, shortfall),
+            &[payer.clone(), account.clone()],
+        )?;
+    }
+
+    invoke_signed(
+        &system_instruction::allocate(account.key, space),
+        slice::from_ref(account),
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, owner),
+        slice::from_ref(account),
+        &[seeds],
+    )
+}
+
+/// Hash of `committed_slot` from slot hashes sysvar data at `slot`. Fails with
+/// `SlotHashNotReady` until the slot is over and with `SlotHashExpired` once it leaves
+/// the sysvar
+pub fn committed_slot_hash(
+    slot_hashes: &[u8],
+    committed_slot: u64,
+    slot: u64,
+) -> Result<[u8; 32], AirdropError> {
+    if committed_slot >= slot {
+        return Err(AirdropError::SlotHashNotReady);
+    }
+    if slot - committed_slot > MAX_ENTRIES as u64 {
+        return Err(AirdropError::SlotHashExpired);
+    }
+
+    // Slot hashes are `[count: u64]` followed by `[slot: u64][hash: 32]`, most recent first
+    let entries = slot_hashes.get(8..).unwrap_or_default();
+    entries
+        .chunks_exact(40)
+        .map(|entry| array_refs![array_ref![entry, 0, 40], 8, 32])
+        .find(|(entry_slot, _)| u64::from_le_bytes(**entry_slot) == committed_slot)
+        .map(|(_, hash)| *hash)
+        .ok_or(AirdropError::SlotHashExpired)
+}
+
+/// Seed of the airdrop index drawn for `user`: hash of the committed slot hash,
+/// the airdrop and the user. None of them is chosen after the slot hash is known
+pub fn index_seed(slot_hash: &[u8; 32], airdrop: &Pubkey, user: &Pubkey) -> u64 {
+    let hash = hashv(&[slot_hash, airdrop.as_ref(), user.as_ref()]);
+
+    u64::from_le_bytes(*array_ref![hash.as_ref(), 0, 8])
+}
+
+/// Marks the `seed % remaining`-th index not minted yet in `index_map` and returns it.
+/// `None` if the map has less than `remaining` free indices below `airdrop_amount`
+pub fn draw_index(
+    index_map: &mut [u8],
+    airdrop_amount: u64,
+    remaining: u64,
+    seed: u64,
+) -> Option<u64> {
+    if remaining == 0 {
+        return None;
+    }
+
+    let mut skip = seed % remaining;
+    for (byte_index, byte) in index_map.iter_mut().enumerate() {
+        let free = byte.count_zeros() as u64;
+        if skip >= free {
+            skip -= free;
+            continue;
+        }
+
+        for bit in 0..8 {
+            if *byte & (1 << bit) != 0 {
+                continue;
+            }
+
+            if skip == 0 {
+                let airdrop_index = byte_index as u64 * 8 + bit;
+                if airdrop_index >= airdrop_amount {
+                    return None;
+                }
+
+                *byte |= 1 << bit;
+                return Some(airdrop_index);
+            }
+            skip -= 1;
+        }
+    }
+
+    None
 }
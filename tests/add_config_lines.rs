@@ -0,0 +1,216 @@
+mod common;
+
+use common::{assert_error, AdminAirdrop, Airdrop, Env, AIRDROP_AMOUNT};
+use metaplex_token_metadata::state::{MAX_NAME_LENGTH, MAX_URI_LENGTH};
+use premint_airdrop::{
+    error::AirdropError,
+    instruction,
+    state::{ConfigLine, ConfigLines},
+};
+use solana_sdk::{program_error::ProgramError, pubkey::Pubkey, signature::Keypair, signer::Signer};
+
+fn line(index: u64) -> ConfigLine {
+    ConfigLine {
+        name: format!("Line #{}", index),
+        uri: format!("https://example.com/line/{}.json", index),
+    }
+}
+
+/// Environment with config lines airdrop, `airdrop_index` tokens already minted
+/// and its config lines account not initialized
+async fn start(upload: &AdminAirdrop, airdrop_index: u64) -> Env {
+    Env::start(vec![
+        upload.config_lines_config_account(airdrop_index),
+        upload
+            .airdrop
+            .config_lines_account(&upload.config_lines, &[]),
+    ])
+    .await
+}
+
+#[tokio::test]
+async fn add_config_lines_rejects_airdrop_without_config_lines() {
+    let upload = AdminAirdrop::new();
+    let mut env = Env::start(vec![
+        upload.airdrop.config_account(0),
+        upload
+            .airdrop
+            .config_lines_account(&upload.config_lines, &[]),
+    ])
+    .await;
+
+    let instruction = upload.add_config_lines(0, vec![line(0)]);
+    let result = upload.send(&mut env, instruction).await;
+
+    assert_error(result, AirdropError::NoConfigLines);
+}
+
+#[tokio::test]
+async fn add_config_lines_requires_admin_signature() {
+    let upload = AdminAirdrop::new();
+    let mut env = start(&upload, 0).await;
+
+    let other_admin = Keypair::new();
+    let instruction = instruction::add_config_lines(
+        &upload.airdrop.address(),
+        &upload.config_lines,
+        &other_admin.pubkey(),
+        0,
+        vec![line(0)],
+    )
+    .unwrap();
+    let result = env.send(&[instruction], &[&other_admin]).await;
+    assert_error(result, AirdropError::WrongAccountAddress);
+
+    let mut instruction = upload.add_config_lines(0, vec![line(0)]);
+    instruction.accounts[2].is_signer = false;
+    let result = env.send(&[instruction], &[]).await;
+    assert_error(result, AirdropError::SignerRequired);
+}
+
+#[tokio::test]
+async fn add_config_lines_rejects_wrong_config_lines_account() {
+    let upload = AdminAirdrop::new();
+    let mut env = start(&upload, 0).await;
+
+    let mut instruction = upload.add_config_lines(0, vec![line(0)]);
+    instruction.accounts[1].pubkey = Pubkey::new_unique();
+    let result = upload.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::WrongConfigLinesAccount);
+
+    let mut instruction = upload.add_config_lines(0, vec![line(0)]);
+    instruction.accounts[1].is_writable = false;
+    let result = upload.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::WriteableRequired);
+}
+
+#[tokio::test]
+async fn add_config_lines_rejects_bad_config_lines_data() {
+    let upload = AdminAirdrop::new();
+    let (address, account) = upload
+        .airdrop
+        .config_lines_account(&upload.config_lines, &[]);
+
+    let mut short = account.clone();
+    short.data.pop();
+    let mut foreign = account.clone();
+    foreign.owner = Pubkey::new_unique();
+    let (_, other_airdrop) = Airdrop::new().config_lines_account(&address, &[line(0)]);
+
+    let cases = [
+        (short, AirdropError::WrongConfigLinesAccount),
+        (foreign, AirdropError::WrongAccountOwner),
+        (other_airdrop, AirdropError::WrongConfigLinesAccount),
+    ];
+
+    for (account, error) in cases {
+        let mut env = Env::start(vec![
+            upload.config_lines_config_account(0),
+            (address, account),
+        ])
+        .await;
+
+        let instruction = upload.add_config_lines(0, vec![line(0)]);
+        let result = upload.send(&mut env, instruction).await;
+
+        assert_error(result, error);
+    }
+}
+
+#[tokio::test]
+async fn add_config_lines_rejects_started_sale() {
+    let upload = AdminAirdrop::new();
+    let mut env = start(&upload, 1).await;
+
+    let instruction = upload.add_config_lines(0, vec![line(0)]);
+    let result = upload.send(&mut env, instruction).await;
+
+    assert_error(result, AirdropError::SaleStarted);
+}
+
+#[tokio::test]
+async fn add_config_lines_rejects_invalid_lines() {
+    let upload = AdminAirdrop::new();
+    let mut env = start(&upload, 0).await;
+
+    let cases = [
+        (AIRDROP_AMOUNT, vec![line(0)]),
+        (AIRDROP_AMOUNT - 1, vec![line(0), line(1)]),
+        (u64::MAX, vec![line(0)]),
+        (
+            0,
+            vec![ConfigLine {
+                name: String::new(),
+                ..line(0)
+            }],
+        ),
+        (
+            0,
+            vec![ConfigLine {
+                uri: String::new(),
+                ..line(0)
+            }],
+        ),
+        (
+            0,
+            vec![ConfigLine {
+                name: "n".repeat(MAX_NAME_LENGTH + 1),
+                ..line(0)
+            }],
+        ),
+        (
+            0,
+            vec![ConfigLine {
+                uri: "u".repeat(MAX_URI_LENGTH + 1),
+                ..line(0)
+            }],
+        ),
+    ];
+
+    for (first_index, lines) in cases {
+        let instruction = upload.add_config_lines(first_index, lines);
+        let result = upload.send(&mut env, instruction).await;
+
+        assert_error(result, AirdropError::InvalidConfigLine);
+    }
+
+    let mut instruction = upload.add_config_lines(0, vec![line(0)]);
+    instruction.data.push(0);
+    let result = upload.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::BadInstructionArgument);
+}
+
+#[tokio::test]
+async fn add_config_lines_stores_lines() {
+    let upload = AdminAirdrop::new();
+    let mut env = start(&upload, 0).await;
+
+    let instruction = upload.add_config_lines(2, vec![line(2), line(3)]);
+    upload.send(&mut env, instruction).await.unwrap();
+
+    // Lines may be replaced until the sale starts
+    let last = AIRDROP_AMOUNT - 1;
+    let replaced = ConfigLine {
+        name: "n".repeat(MAX_NAME_LENGTH),
+        uri: "u".repeat(MAX_URI_LENGTH),
+    };
+    let instruction = upload.add_config_lines(3, vec![replaced.clone()]);
+    upload.send(&mut env, instruction).await.unwrap();
+    let instruction = upload.add_config_lines(last, vec![line(last)]);
+    upload.send(&mut env, instruction).await.unwrap();
+
+    let account = env.account(&upload.config_lines).await.unwrap();
+    let config_lines = ConfigLines::unpack_from_slice(&account.data).unwrap();
+    assert!(config_lines.initialized);
+    assert_eq!(config_lines.airdrop, upload.airdrop.address());
+
+    let read = |index| ConfigLines::read_line(&account.data, index).unwrap();
+    assert_eq!(read(0), None);
+    assert_eq!(read(2), Some(line(2)));
+    assert_eq!(read(3), Some(replaced));
+    assert_eq!(read(last), Some(line(last)));
+    assert_eq!(
+        ConfigLines::read_line(&account.data, AIRDROP_AMOUNT).unwrap_err(),
+        ProgramError::InvalidAccountData
+    );
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use common::{assert_error, Airdrop, Env};
+use premint_airdrop::{
+    error::AirdropError, instruction::commit_draw, pda::find_airdrop_user_data,
+    state::AirdropUserData,
+};
+use solana_sdk::{program_pack::Pack, pubkey::Pubkey, signature::Keypair, signer::Signer};
+
+/// Environment with a shuffled airdrop and `user` registered with `locked_till`
+async fn start(airdrop: &Airdrop, user: &Pubkey, locked_till: i64) -> Env {
+    Env::start(vec![
+        airdrop.config_account_with(0, airdrop.shuffled_args()),
+        airdrop.user_data_account(user, locked_till),
+    ])
+    .await
+}
+
+#[tokio::test]
+async fn commit_draw_rejects_sequential_airdrop() {
+    let airdrop = Airdrop::new();
+    let user = Pubkey::new_unique();
+    let mut env = Env::start(vec![
+        airdrop.config_account(0),
+        airdrop.user_data_account(&user, 0),
+    ])
+    .await;
+
+    let instruction = airdrop.commit_draw(&user);
+    let result = env.send(&[instruction], &[&airdrop.authority]).await;
+
+    assert_error(result, AirdropError::NotShuffled);
+}
+
+#[tokio::test]
+async fn commit_draw_requires_airdrop_authority() {
+    let airdrop = Airdrop::new();
+    let user = Pubkey::new_unique();
+    let mut env = start(&airdrop, &user, 0).await;
+
+    let other_authority = Keypair::new();
+    let instruction = commit_draw(&airdrop.address(), &user, &other_authority.pubkey());
+    let result = env.send(&[instruction], &[&other_authority]).await;
+    assert_error(result, AirdropError::WrongAccountAddress);
+
+    let mut instruction = airdrop.commit_draw(&user);
+    instruction.accounts[3].is_signer = false;
+    let result = env.send(&[instruction], &[]).await;
+    assert_error(result, AirdropError::SignerRequired);
+}
+
+#[tokio::test]
+async fn commit_draw_rejects_wrong_user_data() {
+    let airdrop = Airdrop::new();
+    let user = Pubkey::new_unique();
+    let mut env = start(&airdrop, &user, 0).await;
+
+    let mut instruction = airdrop.commit_draw(&user);
+    instruction.accounts[2].pubkey = Pubkey::new_unique();
+    let result = env.send(&[instruction], &[&airdrop.authority]).await;
+    assert_error(result, AirdropError::WrongRecipient);
+
+    let instruction = airdrop.commit_draw(&Pubkey::new_unique());
+    let result = env.send(&[instruction], &[&airdrop.authority]).await;
+    assert_error(result, AirdropError::WrongAccountOwner);
+}
+
+#[tokio::test]
+async fn commit_draw_rejects_locked_user() {
+    let airdrop = Airdrop::new();
+    let user = Pubkey::new_unique();
+    let mut env = start(&airdrop, &user, i64::MAX).await;
+
+    let instruction = airdrop.commit_draw(&user);
+    let result = env.send(&[instruction], &[&airdrop.authority]).await;
+
+    assert_error(result, AirdropError::UserTimeout);
+}
+
+#[tokio::test]
+async fn commit_draw_locks_user_till_mint() {
+    let airdrop = Airdrop::new();
+    let user = Pubkey::new_unique();
+    let mut env = start(&airdrop, &user, 0).await;
+
+    let instruction = airdrop.commit_draw(&user);
+    env.send(&[instruction], &[&airdrop.authority])
+        .await
+        .unwrap();
+
+    let (user_data, _) = find_airdrop_user_data(&airdrop.address(), &user);
+    let user_data = env.account(&user_data).await.unwrap();
+    let user_data = AirdropUserData::unpack(&user_data.data).unwrap();
+    assert!(user_data.draw_slot > 0);
+    assert!(user_data.locked_till > 0);
+
+    // The draw can not be replaced by another one while its slot hash is available
+    env.warp(1).await;
+    let instruction = airdrop.commit_draw(&user);
+    let result = env.send(&[instruction], &[&airdrop.authority]).await;
+    assert_error(result, AirdropError::UserTimeout);
+}
@@ -0,0 +1,568 @@
+//! Shared environment of the integration suite.
+//!
+//! Token and Associated Token programs are loaded by `ProgramTest` itself. Token Metadata only
+//! runs from its BPF build, which is not a part of the repository. Before `cargo test-bpf`, build
+//! it into `tests/fixtures/metaplex_token_metadata.so` with `tests/fixtures/build.sh`.
+//!
+//! Native processors are unable to create accounts through CPI, so tests of flows creating user
+//! data and mint accounts, as well as tests invoking Token Metadata, are gated on the `test-bpf`
+//! feature. Error paths are tested natively on accounts added at genesis.
+#![allow(dead_code)]
+
+use std::convert::TryFrom;
+
+use premint_airdrop::{
+    instruction::{
+        add_config_lines, commit_draw, end_sale, initialize_airdrop, mint_one, reveal, reveal_seed,
+        InitializeAirdropArgs,
+    },
+    pda::find_airdrop_user_data,
+    state::{AirdropConfig, AirdropUserData, ConfigLine, ConfigLines},
+};
+use solana_program_test::{
+    processor, BanksClient, BanksClientError, ProgramTest, ProgramTestBanksClientExt,
+    ProgramTestContext,
+};
+use solana_sdk::{
+    account::Account,
+    commitment_config::CommitmentLevel,
+    hash::{hash, Hash},
+    instruction::{Instruction, InstructionError},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+
+pub const PRICE: u64 = 1_000_000;
+pub const AIRDROP_AMOUNT: u64 = 10;
+/// Seed committed by `fair_mint_args`
+pub const SEED: [u8; 32] = [7; 32];
+
+/// Keys of an airdrop used by a test
+pub struct Airdrop {
+    pub keypair: Keypair,
+    pub authority: Keypair,
+    pub admin: Pubkey,
+    pub revenues_wallet: Pubkey,
+}
+
+impl Airdrop {
+    pub fn new() -> Airdrop {
+        Airdrop {
+            keypair: Keypair::new(),
+            authority: Keypair::new(),
+            admin: Pubkey::new_unique(),
+            revenues_wallet: Pubkey::new_unique(),
+        }
+    }
+
+    pub fn address(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+
+    pub fn args(&self) -> InitializeAirdropArgs {
+        InitializeAirdropArgs {
+            airdrop_amount: AIRDROP_AMOUNT,
+            price: PRICE,
+            index_offset: 0,
+            index_padding: 0,
+            shuffled: false,
+            provenance_hash: Hash::default(),
+            seed_commitment: Hash::default(),
+            config_lines: Pubkey::default(),
+            pda_mints: false,
+            require_user_signature: false,
+            require_user_payer: false,
+            base_uri: "https://example.com/".to_string(),
+            uri_extension: ".json".to_string(),
+            name_template: "Test #{}".to_string(),
+            symbol: "TEST".to_string(),
+            hidden_name_template: String::new(),
+            hidden_uri: String::new(),
+        }
+    }
+
+    /// Arguments of an airdrop minting tokens with hidden metadata until reveal
+    pub fn hidden_args(&self) -> InitializeAirdropArgs {
+        InitializeAirdropArgs {
+            hidden_name_template: "Hidden #{}".to_string(),
+            hidden_uri: "https://example.com/hidden.json".to_string(),
+            ..self.args()
+        }
+    }
+
+    /// Arguments of an airdrop drawing airdrop indices at random
+    pub fn shuffled_args(&self) -> InitializeAirdropArgs {
+        InitializeAirdropArgs {
+            shuffled: true,
+            ..self.args()
+        }
+    }
+
+    /// Arguments of a hidden airdrop committed to `SEED`
+    pub fn fair_mint_args(&self) -> InitializeAirdropArgs {
+        InitializeAirdropArgs {
+            provenance_hash: hash(b"metadata files"),
+            seed_commitment: hash(&SEED),
+            ..self.hidden_args()
+        }
+    }
+
+    /// Arguments of an airdrop reading token metadata from `config_lines`
+    pub fn config_lines_args(&self, config_lines: &Pubkey) -> InitializeAirdropArgs {
+        InitializeAirdropArgs {
+            config_lines: *config_lines,
+            base_uri: String::new(),
+            uri_extension: String::new(),
+            name_template: String::new(),
+            ..self.args()
+        }
+    }
+
+    /// Arguments of an airdrop requiring recipients to sign and pay themselves
+    pub fn consent_args(&self) -> InitializeAirdropArgs {
+        InitializeAirdropArgs {
+            require_user_signature: true,
+            require_user_payer: true,
+            ..self.args()
+        }
+    }
+
+    pub fn config(&self, airdrop_index: u64) -> AirdropConfig {
+        self.config_with(airdrop_index, self.args())
+    }
+
+    /// Config initialized with `args` and `airdrop_index` tokens already minted
+    pub fn config_with(&self, airdrop_index: u64, args: InitializeAirdropArgs) -> AirdropConfig {
+        AirdropConfig {
+            initialized: true,
+            airdrop_authority: self.authority.pubkey(),
+            airdrop_index,
+            airdrop_amount: args.airdrop_amount,
+            airdrop_users: 1,
+            revenues_wallet: self.revenues_wallet,
+            admin_account: self.admin,
+            price: args.price,
+            index_offset: args.index_offset,
+            index_padding: args.index_padding,
+            shuffled: args.shuffled,
+            provenance_hash: args.provenance_hash,
+            seed_commitment: args.seed_commitment,
+            seed_revealed: false,
+            sale_end_slot: 0,
+            starting_offset: 0,
+            config_lines: args.config_lines,
+            pda_mints: args.pda_mints,
+            require_user_signature: args.require_user_signature,
+            require_user_payer: args.require_user_payer,
+            base_uri: args.base_uri,
+            uri_extension: args.uri_extension,
+            name_template: args.name_template,
+            symbol: args.symbol,
+            hidden_name_template: args.hidden_name_template,
+            hidden_uri: args.hidden_uri,
+        }
+    }
+
+    /// Initialized config account with `airdrop_index` tokens already minted
+    pub fn config_account(&self, airdrop_index: u64) -> (Pubkey, Account) {
+        (
+            self.address(),
+            program_account(self.args().config_space(), |data| {
+                self.config(airdrop_index).pack_into_slice(data)
+            }),
+        )
+    }
+
+    /// Initialized config account of an airdrop minting hidden tokens
+    pub fn hidden_config_account(&self, airdrop_index: u64) -> (Pubkey, Account) {
+        self.config_account_with(airdrop_index, self.hidden_args())
+    }
+
+    /// Config account initialized with `args` and `airdrop_index` tokens already minted.
+    /// Index map of shuffled airdrops is empty
+    pub fn config_account_with(
+        &self,
+        airdrop_index: u64,
+        args: InitializeAirdropArgs,
+    ) -> (Pubkey, Account) {
+        self.config_account_from(&self.config_with(airdrop_index, args))
+    }
+
+    /// Account of the airdrop with `config` as its data
+    pub fn config_account_from(&self, config: &AirdropConfig) -> (Pubkey, Account) {
+        (
+            self.address(),
+            program_account(config.data_len(), |data| config.pack_into_slice(data)),
+        )
+    }
+
+    /// Config account created, but not initialized
+    pub fn uninitialized_config_account(&self) -> (Pubkey, Account) {
+        (
+            self.address(),
+            program_account(self.args().config_space(), |_| Ok(())),
+        )
+    }
+
+    /// Config lines account of the airdrop with `lines` uploaded from index 0.
+    /// Not initialized if `lines` is empty
+    pub fn config_lines_account(
+        &self,
+        address: &Pubkey,
+        lines: &[ConfigLine],
+    ) -> (Pubkey, Account) {
+        let account = program_account(ConfigLines::space(AIRDROP_AMOUNT), |data| {
+            if lines.is_empty() {
+                return Ok(());
+            }
+
+            let config_lines = ConfigLines {
+                initialized: true,
+                airdrop: self.address(),
+            };
+            config_lines.pack_into_slice(data)?;
+            for (index, line) in lines.iter().enumerate() {
+                ConfigLines::write_line(data, index as u64, line)?;
+            }
+            Ok(())
+        });
+
+        (*address, account)
+    }
+
+    /// User data account of a registered user locked till `locked_till`
+    pub fn user_data_account(&self, user: &Pubkey, locked_till: i64) -> (Pubkey, Account) {
+        let (user_data, _) = find_airdrop_user_data(&self.address(), user);
+        let state = AirdropUserData {
+            initialized: true,
+            airdrop: self.address(),
+            user: *user,
+            mints_amount: 0,
+            locked_till,
+            draw_slot: 0,
+        };
+
+        (
+            user_data,
+            program_account(AirdropUserData::LEN, |data| {
+                AirdropUserData::pack(state, data)
+            }),
+        )
+    }
+
+    pub fn mint_one(&self, mint: &Pubkey, user: &Pubkey, payer: &Pubkey) -> Instruction {
+        mint_one(
+            &self.address(),
+            mint,
+            user,
+            payer,
+            &self.authority.pubkey(),
+            &self.admin,
+            &self.revenues_wallet,
+            false,
+        )
+    }
+
+    /// `MintOne` of the airdrop created with `shuffled_args`
+    pub fn mint_one_shuffled(&self, mint: &Pubkey, user: &Pubkey, payer: &Pubkey) -> Instruction {
+        mint_one(
+            &self.address(),
+            mint,
+            user,
+            payer,
+            &self.authority.pubkey(),
+            &self.admin,
+            &self.revenues_wallet,
+            true,
+        )
+    }
+
+    /// `CommitDraw` of the next mint to `user` approved by airdrop authority
+    pub fn commit_draw(&self, user: &Pubkey) -> Instruction {
+        commit_draw(&self.address(), user, &self.authority.pubkey())
+    }
+}
+
+/// Airdrop with admin able to sign `Reveal`, `EndSale`, `RevealSeed` and `AddConfigLines`.
+/// `config_lines` is the config lines account of the airdrop created with `config_lines_args`
+pub struct AdminAirdrop {
+    pub airdrop: Airdrop,
+    pub admin: Keypair,
+    pub config_lines: Pubkey,
+}
+
+impl AdminAirdrop {
+    pub fn new() -> AdminAirdrop {
+        let admin = Keypair::new();
+
+        AdminAirdrop {
+            airdrop: Airdrop {
+                admin: admin.pubkey(),
+                ..Airdrop::new()
+            },
+            admin,
+            config_lines: Pubkey::new_unique(),
+        }
+    }
+
+    /// Config account of the airdrop reading metadata from `config_lines`
+    pub fn config_lines_config_account(&self, airdrop_index: u64) -> (Pubkey, Account) {
+        let args = self.airdrop.config_lines_args(&self.config_lines);
+        self.airdrop.config_account_with(airdrop_index, args)
+    }
+
+    pub fn reveal(&self, tokens: &[(u64, Pubkey)]) -> Instruction {
+        reveal(&self.airdrop.address(), &self.admin.pubkey(), tokens).unwrap()
+    }
+
+    pub fn end_sale(&self) -> Instruction {
+        end_sale(&self.airdrop.address(), &self.admin.pubkey())
+    }
+
+    pub fn reveal_seed(&self, seed: [u8; 32]) -> Instruction {
+        reveal_seed(&self.airdrop.address(), &self.admin.pubkey(), seed)
+    }
+
+    pub fn add_config_lines(&self, first_index: u64, lines: Vec<ConfigLine>) -> Instruction {
+        add_config_lines(
+            &self.airdrop.address(),
+            &self.config_lines,
+            &self.admin.pubkey(),
+            first_index,
+            lines,
+        )
+        .unwrap()
+    }
+
+    /// Sends `instruction` signed by the admin
+    pub async fn send(
+        &self,
+        env: &mut Env,
+        instruction: Instruction,
+    ) -> Result<(), TransactionError> {
+        env.send(&[instruction], &[&self.admin]).await
+    }
+}
+
+/// System account of a wallet able to pay for registration and mints
+pub fn wallet_account(address: &Pubkey) -> (Pubkey, Account) {
+    (
+        *address,
+        Account {
+            lamports: 10_000_000_000,
+            ..Account::default()
+        },
+    )
+}
+
+/// Account owned by the airdrop program with data written by `pack`
+pub fn program_account(
+    len: usize,
+    pack: impl FnOnce(&mut [u8]) -> Result<(), ProgramError>,
+) -> Account {
+    let mut data = vec![0; len];
+    pack(&mut data).unwrap();
+
+    Account {
+        lamports: 1_000_000_000,
+        data,
+        owner: premint_airdrop::id(),
+        ..Account::default()
+    }
+}
+
+/// Outcome of a simulated transaction
+pub struct Simulation {
+    pub result: Result<(), TransactionError>,
+    pub logs: Vec<String>,
+    pub units_consumed: u64,
+}
+
+pub struct Env {
+    pub banks_client: BanksClient,
+    pub payer: Keypair,
+    pub blockhash: Hash,
+    context: ProgramTestContext,
+}
+
+impl Env {
+    pub async fn start(accounts: Vec<(Pubkey, Account)>) -> Env {
+        let mut program_test = ProgramTest::new(
+            "premint_airdrop",
+            premint_airdrop::id(),
+            processor!(premint_airdrop::processor::process_instruction),
+        );
+        #[cfg(feature = "test-bpf")]
+        program_test.add_program(
+            "metaplex_token_metadata",
+            metaplex_token_metadata::id(),
+            None,
+        );
+        for (address, account) in accounts {
+            program_test.add_account(address, account);
+        }
+
+        let context = program_test.start_with_context().await;
+
+        Env {
+            banks_client: context.banks_client.clone(),
+            payer: Keypair::from_bytes(&context.payer.to_bytes()).unwrap(),
+            blockhash: context.last_blockhash,
+            context,
+        }
+    }
+
+    /// Waits for a new blockhash in the same slot, so a retried transaction gets a new signature
+    pub async fn refresh_blockhash(&mut self) {
+        self.blockhash = self
+            .banks_client
+            .get_new_latest_blockhash(&self.blockhash)
+            .await
+            .unwrap();
+    }
+
+    /// Moves the bank `slots` ahead of the current slot and refreshes the blockhash
+    pub async fn warp(&mut self, slots: u64) {
+        let slot = self
+            .banks_client
+            .get_slot_with_context(tarpc::context::current(), CommitmentLevel::Processed)
+            .await
+            .unwrap();
+        self.context.warp_to_slot(slot + slots).unwrap();
+        self.blockhash = self.banks_client.get_latest_blockhash().await.unwrap();
+    }
+
+    /// Creates airdrop account and initializes it
+    pub async fn create_airdrop(&mut self, airdrop: &Airdrop) -> Result<(), TransactionError> {
+        let instructions = self.create_airdrop_instructions(airdrop).await;
+        self.send(&instructions, &[&airdrop.keypair]).await
+    }
+
+    pub async fn create_airdrop_instructions(&mut self, airdrop: &Airdrop) -> Vec<Instruction> {
+        self.create_airdrop_instructions_with(airdrop, airdrop.args())
+            .await
+    }
+
+    /// Instructions creating airdrop account sized for `args` and initializing it with them
+    pub async fn create_airdrop_instructions_with(
+        &mut self,
+        airdrop: &Airdrop,
+        args: InitializeAirdropArgs,
+    ) -> Vec<Instruction> {
+        let rent = self.banks_client.get_rent().await.unwrap();
+
+        vec![
+            system_instruction::create_account(
+                &self.payer.pubkey(),
+                &airdrop.address(),
+                rent.minimum_balance(args.config_space()),
+                args.config_space() as u64,
+                &premint_airdrop::id(),
+            ),
+            initialize_airdrop(
+                &airdrop.address(),
+                &airdrop.authority.pubkey(),
+                &airdrop.revenues_wallet,
+                &airdrop.admin,
+                &self.payer.pubkey(),
+                args,
+            )
+            .unwrap(),
+        ]
+    }
+
+    /// Signs transaction with payer and `signers` and processes it
+    pub async fn send(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), TransactionError> {
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            self.blockhash,
+        );
+
+        self.banks_client
+            .process_transaction(transaction)
+            .await
+            .map_err(|error| match error {
+                BanksClientError::TransactionError(error) => error,
+                BanksClientError::SimulationError { err, .. } => err,
+                error => panic!("unexpected banks client error: {}", error),
+            })
+    }
+
+    /// Simulates transaction signed with payer and `signers` without committing it
+    pub async fn simulate(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Simulation {
+        let mut all_signers = vec![&self.payer];
+        all_signers.extend_from_slice(signers);
+
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &all_signers,
+            self.blockhash,
+        );
+
+        let result = self
+            .banks_client
+            .process_transaction_with_preflight_and_commitment_and_context(
+                tarpc::context::current(),
+                transaction,
+                CommitmentLevel::Processed,
+            )
+            .await
+            .unwrap();
+        let details = result.simulation_details.expect("transaction is simulated");
+
+        Simulation {
+            result: result.result.expect("transaction is processed"),
+            logs: details.logs,
+            units_consumed: details.units_consumed,
+        }
+    }
+
+    pub async fn account(&mut self, address: &Pubkey) -> Option<Account> {
+        self.banks_client.get_account(*address).await.unwrap()
+    }
+}
+
+/// Asserts that the last instruction of a transaction failed with `expected`
+pub fn assert_error(result: Result<(), TransactionError>, expected: impl Into<ProgramError>) {
+    let expected = expected.into();
+
+    match result {
+        Err(TransactionError::InstructionError(_, error)) => {
+            let error = match error {
+                InstructionError::Custom(code) => ProgramError::Custom(code),
+                error => ProgramError::try_from(error)
+                    .unwrap_or_else(|error| panic!("unexpected instruction error: {}", error)),
+            };
+            assert_eq!(error, expected);
+        }
+        result => panic!("expected {:?}, got {:?}", expected, result),
+    }
+}
+
+/// Return data of the airdrop program from transaction logs
+pub fn parse_return_data(logs: &[String]) -> Option<Vec<u8>> {
+    let prefix = format!("Program return: {} ", premint_airdrop::id());
+
+    logs.iter()
+        .rev()
+        .find_map(|log| log.strip_prefix(&prefix))
+        .map(|data| base64::decode(data).expect("return data is base64"))
+}
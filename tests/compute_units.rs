@@ -0,0 +1,332 @@
+//! Compute unit benchmark of every instruction. Native processors do not meter compute units,
+//! so the benchmark only runs on the BPF build of the program
+#![cfg(feature = "test-bpf")]
+
+mod common;
+
+use std::{collections::HashMap, fs};
+
+use common::{AdminAirdrop, Airdrop, Env, AIRDROP_AMOUNT, SEED};
+use premint_airdrop::{
+    instruction::{
+        initialize_airdrop_user, mint_one_with_pda_mint, quote, with_config_lines,
+        InitializeAirdropArgs,
+    },
+    pda::{find_airdrop_user_data, find_metadata_account, find_mint_authority},
+    state::ConfigLine,
+};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Keypair, signer::Signer};
+use spl_associated_token_account::get_associated_token_address;
+
+/// Limits parsed from `compute_budget.txt`
+struct Budget {
+    instructions: HashMap<String, u64>,
+    accounts: HashMap<String, usize>,
+}
+
+impl Budget {
+    fn load() -> Budget {
+        let mut budget = Budget {
+            instructions: HashMap::new(),
+            accounts: HashMap::new(),
+        };
+
+        let lines = include_str!("compute_budget.txt")
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        for line in lines {
+            match line.split_whitespace().collect::<Vec<_>>()[..] {
+                ["instruction", name, units] => {
+                    budget
+                        .instructions
+                        .insert(name.to_string(), units.parse().unwrap());
+                }
+                ["account", name, size] => {
+                    budget
+                        .accounts
+                        .insert(name.to_string(), size.parse().unwrap());
+                }
+                _ => panic!("malformed budget line: {}", line),
+            }
+        }
+
+        budget
+    }
+}
+
+/// Measurements compared against the budget
+struct Report {
+    budget: Budget,
+    lines: Vec<String>,
+    regressions: Vec<String>,
+}
+
+impl Report {
+    fn new() -> Report {
+        Report {
+            budget: Budget::load(),
+            lines: vec![],
+            regressions: vec![],
+        }
+    }
+
+    fn record(&mut self, kind: &str, name: &str, value: u64, limit: Option<u64>) {
+        let limit = limit.unwrap_or_else(|| panic!("no budget for {} {}", kind, name));
+        self.lines
+            .push(format!("{} {} {} of {}", kind, name, value, limit));
+
+        if value > limit {
+            self.regressions.push(format!(
+                "{} {} uses {}, budget is {}",
+                kind, name, value, limit
+            ));
+        }
+    }
+
+    /// Simulates transaction to record consumed units, then commits it
+    async fn run(
+        &mut self,
+        env: &mut Env,
+        name: &str,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) {
+        let simulation = env.simulate(instructions, signers).await;
+        simulation.result.unwrap();
+
+        let limit = self.budget.instructions.get(name).copied();
+        self.record("instruction", name, simulation.units_consumed, limit);
+
+        env.send(instructions, signers).await.unwrap();
+    }
+
+    async fn account(&mut self, env: &mut Env, name: &str, address: &Pubkey) {
+        let size = env.account(address).await.unwrap().data.len();
+
+        let limit = self.budget.accounts.get(name).map(|&size| size as u64);
+        self.record("account", name, size as u64, limit);
+    }
+}
+
+#[tokio::test]
+async fn compute_units_fit_budget() {
+    let mut report = Report::new();
+    let mut env = Env::start(vec![]).await;
+    let airdrop = Airdrop::new();
+    let user = Pubkey::new_unique();
+    let mint = Keypair::new();
+
+    let instructions = env.create_airdrop_instructions(&airdrop).await;
+    report
+        .run(
+            &mut env,
+            "InitializeAirdrop",
+            &instructions,
+            &[&airdrop.keypair],
+        )
+        .await;
+
+    let instruction = initialize_airdrop_user(&airdrop.address(), &user, &env.payer.pubkey());
+    report
+        .run(&mut env, "InitializeAirdropUser", &[instruction], &[])
+        .await;
+
+    let instruction = quote(&airdrop.address(), &user);
+    report.run(&mut env, "Quote", &[instruction], &[]).await;
+
+    let instruction = airdrop.mint_one(&mint.pubkey(), &user, &env.payer.pubkey());
+    report
+        .run(
+            &mut env,
+            "MintOne",
+            &[instruction],
+            &[&mint, &airdrop.authority],
+        )
+        .await;
+
+    let (mint_authority, _) = find_mint_authority(&airdrop.address());
+    let (user_data, _) = find_airdrop_user_data(&airdrop.address(), &user);
+    let (metadata, _) = find_metadata_account(&mint.pubkey());
+    let token_account = get_associated_token_address(&user, &mint.pubkey());
+
+    for (name, address) in [
+        ("AirdropConfig", airdrop.address()),
+        ("MintAuthority", mint_authority),
+        ("AirdropUserData", user_data),
+        ("Mint", mint.pubkey()),
+        ("TokenAccount", token_account),
+        ("Metadata", metadata),
+    ] {
+        report.account(&mut env, name, &address).await;
+    }
+
+    // Shuffled mint is measured with the draw committed in an earlier slot
+    let shuffled = Airdrop::new();
+    let mint = Keypair::new();
+
+    let mut instructions = env
+        .create_airdrop_instructions_with(&shuffled, shuffled.shuffled_args())
+        .await;
+    instructions.push(initialize_airdrop_user(
+        &shuffled.address(),
+        &user,
+        &env.payer.pubkey(),
+    ));
+    env.send(&instructions, &[&shuffled.keypair]).await.unwrap();
+
+    let instruction = shuffled.commit_draw(&user);
+    report
+        .run(
+            &mut env,
+            "CommitDraw",
+            &[instruction],
+            &[&shuffled.authority],
+        )
+        .await;
+    env.warp(1).await;
+
+    let instruction = shuffled.mint_one_shuffled(&mint.pubkey(), &user, &env.payer.pubkey());
+    report
+        .run(
+            &mut env,
+            "MintOneShuffled",
+            &[instruction],
+            &[&mint, &shuffled.authority],
+        )
+        .await;
+
+    // PDA mint is created by the program instead of a signing keypair
+    let pda_mints = Airdrop::new();
+    let args = InitializeAirdropArgs {
+        pda_mints: true,
+        ..pda_mints.args()
+    };
+
+    let mut instructions = env.create_airdrop_instructions_with(&pda_mints, args).await;
+    instructions.push(initialize_airdrop_user(
+        &pda_mints.address(),
+        &user,
+        &env.payer.pubkey(),
+    ));
+    env.send(&instructions, &[&pda_mints.keypair])
+        .await
+        .unwrap();
+
+    let instruction = mint_one_with_pda_mint(
+        &pda_mints.address(),
+        0,
+        &user,
+        &env.payer.pubkey(),
+        &pda_mints.authority.pubkey(),
+        &pda_mints.admin,
+        &pda_mints.revenues_wallet,
+        false,
+    );
+    report
+        .run(
+            &mut env,
+            "MintOnePdaMint",
+            &[instruction],
+            &[&pda_mints.authority],
+        )
+        .await;
+
+    // Reveal is measured on a separate airdrop minting hidden tokens
+    let revealed = AdminAirdrop::new();
+    let hidden = &revealed.airdrop;
+    let mint = Keypair::new();
+
+    let mut instructions = env
+        .create_airdrop_instructions_with(hidden, hidden.hidden_args())
+        .await;
+    instructions.push(initialize_airdrop_user(
+        &hidden.address(),
+        &user,
+        &env.payer.pubkey(),
+    ));
+    env.send(&instructions, &[&hidden.keypair]).await.unwrap();
+
+    let instruction = hidden.mint_one(&mint.pubkey(), &user, &env.payer.pubkey());
+    env.send(&[instruction], &[&mint, &hidden.authority])
+        .await
+        .unwrap();
+
+    let instruction = revealed.reveal(&[(0, mint.pubkey())]);
+    report
+        .run(&mut env, "Reveal", &[instruction], &[&revealed.admin])
+        .await;
+
+    // Sale end and seed reveal are measured on a sold out fair mint airdrop added at genesis
+    let fair_mint = AdminAirdrop::new();
+    // Config lines upload is measured on every line of an airdrop with them
+    let with_lines = AdminAirdrop::new();
+    let mut env = Env::start(vec![
+        fair_mint
+            .airdrop
+            .config_account_with(AIRDROP_AMOUNT, fair_mint.airdrop.fair_mint_args()),
+        with_lines.config_lines_config_account(0),
+        with_lines
+            .airdrop
+            .config_lines_account(&with_lines.config_lines, &[]),
+    ])
+    .await;
+
+    let instruction = fair_mint.end_sale();
+    report
+        .run(&mut env, "EndSale", &[instruction], &[&fair_mint.admin])
+        .await;
+    env.warp(1).await;
+
+    let instruction = fair_mint.reveal_seed(SEED);
+    report
+        .run(&mut env, "RevealSeed", &[instruction], &[&fair_mint.admin])
+        .await;
+
+    let lines = (0..AIRDROP_AMOUNT)
+        .map(|index| ConfigLine {
+            name: format!("Test #{}", index),
+            uri: format!("https://example.com/{}.json", index),
+        })
+        .collect();
+    let instruction = with_lines.add_config_lines(0, lines);
+    report
+        .run(
+            &mut env,
+            "AddConfigLines",
+            &[instruction],
+            &[&with_lines.admin],
+        )
+        .await;
+    report
+        .account(&mut env, "ConfigLines", &with_lines.config_lines)
+        .await;
+
+    let lines_airdrop = &with_lines.airdrop;
+    let mint = Keypair::new();
+    let instruction = initialize_airdrop_user(&lines_airdrop.address(), &user, &env.payer.pubkey());
+    env.send(&[instruction], &[]).await.unwrap();
+
+    let instruction = with_config_lines(
+        lines_airdrop.mint_one(&mint.pubkey(), &user, &env.payer.pubkey()),
+        &with_lines.config_lines,
+    );
+    report
+        .run(
+            &mut env,
+            "MintOneConfigLines",
+            &[instruction],
+            &[&mint, &lines_airdrop.authority],
+        )
+        .await;
+
+    let output = concat!(env!("CARGO_TARGET_TMPDIR"), "/bench_output.txt");
+    fs::write(output, report.lines.join("\n") + "\n").unwrap();
+
+    assert!(
+        report.regressions.is_empty(),
+        "compute budget exceeded:\n{}",
+        report.regressions.join("\n")
+    );
+}
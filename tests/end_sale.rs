@@ -0,0 +1,106 @@
+mod common;
+
+use common::{assert_error, AdminAirdrop, Env, AIRDROP_AMOUNT, SEED};
+use premint_airdrop::{error::AirdropError, instruction, state::AirdropConfig};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, slot_hashes::MAX_ENTRIES};
+
+/// Environment with fair mint airdrop, `minted` tokens already minted
+async fn start(reveal: &AdminAirdrop, minted: u64) -> Env {
+    let args = reveal.airdrop.fair_mint_args();
+    Env::start(vec![reveal.airdrop.config_account_with(minted, args)]).await
+}
+
+async fn config(env: &mut Env, airdrop: &Pubkey) -> AirdropConfig {
+    let account = env.account(airdrop).await.unwrap();
+    AirdropConfig::unpack_from_slice(&account.data).unwrap()
+}
+
+#[tokio::test]
+async fn end_sale_rejects_airdrop_without_commitment() {
+    let reveal = AdminAirdrop::new();
+    let mut env = Env::start(vec![reveal.airdrop.hidden_config_account(0)]).await;
+
+    let instruction = reveal.end_sale();
+    let result = reveal.send(&mut env, instruction).await;
+
+    assert_error(result, AirdropError::NoSeedCommitment);
+}
+
+#[tokio::test]
+async fn end_sale_requires_admin_signature() {
+    let reveal = AdminAirdrop::new();
+    let mut env = start(&reveal, 0).await;
+
+    let other_admin = Keypair::new();
+    let instruction = instruction::end_sale(&reveal.airdrop.address(), &other_admin.pubkey());
+    let result = env.send(&[instruction], &[&other_admin]).await;
+    assert_error(result, AirdropError::WrongAccountAddress);
+
+    let mut instruction = reveal.end_sale();
+    instruction.accounts[1].is_signer = false;
+    let result = env.send(&[instruction], &[]).await;
+    assert_error(result, AirdropError::SignerRequired);
+
+    let mut instruction = reveal.end_sale();
+    instruction.accounts[0].is_writable = false;
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::WriteableRequired);
+}
+
+#[tokio::test]
+async fn end_sale_stops_unfinished_sale() {
+    let reveal = AdminAirdrop::new();
+    let user = Pubkey::new_unique();
+    let args = reveal.airdrop.fair_mint_args();
+    let mut env = Env::start(vec![
+        reveal.airdrop.config_account_with(AIRDROP_AMOUNT - 1, args),
+        reveal.airdrop.user_data_account(&user, 0),
+    ])
+    .await;
+
+    let instruction = reveal.end_sale();
+    reveal.send(&mut env, instruction).await.unwrap();
+
+    let config = config(&mut env, &reveal.airdrop.address()).await;
+    assert!(config.is_sale_ended());
+    assert!(!config.seed_revealed);
+
+    let mint = Keypair::new();
+    let instruction = reveal
+        .airdrop
+        .mint_one(&mint.pubkey(), &user, &env.payer.pubkey());
+    let result = env
+        .send(&[instruction], &[&mint, &reveal.airdrop.authority])
+        .await;
+    assert_error(result, AirdropError::SaleEnded);
+}
+
+#[tokio::test]
+async fn end_sale_fixes_slot_until_its_hash_expires() {
+    let reveal = AdminAirdrop::new();
+    let mut env = start(&reveal, AIRDROP_AMOUNT).await;
+    let address = reveal.airdrop.address();
+
+    let instruction = reveal.end_sale();
+    reveal.send(&mut env, instruction).await.unwrap();
+    let sale_end_slot = config(&mut env, &address).await.sale_end_slot;
+
+    env.warp(MAX_ENTRIES as u64).await;
+    let instruction = reveal.end_sale();
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::SaleEnded);
+
+    // Seed can not be revealed with the expired hash, so the sale is ended again
+    env.warp(1).await;
+    let instruction = reveal.end_sale();
+    reveal.send(&mut env, instruction).await.unwrap();
+    assert!(config(&mut env, &address).await.sale_end_slot > sale_end_slot);
+
+    env.warp(1).await;
+    let instruction = reveal.reveal_seed(SEED);
+    reveal.send(&mut env, instruction).await.unwrap();
+
+    let instruction = reveal.end_sale();
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::SeedAlreadyRevealed);
+}
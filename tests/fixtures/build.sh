@@ -0,0 +1,35 @@
+#!/usr/bin/env bash
+# Builds programs loaded by `cargo test-bpf` that are not part of this workspace
+# into this directory, where solana-program-test looks for them:
+#
+# - metaplex_token_metadata.so: Metaplex Token Metadata at the version the airdrop
+#   program depends on. Mainnet Token Metadata no longer supports the v1 instructions
+#   used by the program, so it is built from the crate source instead of dumped.
+#
+# Requires the Solana 1.10 tool suite for `cargo build-bpf`.
+set -euo pipefail
+
+fixtures="$(cd "$(dirname "$0")" && pwd)"
+root="$(cd "$fixtures/../.." && pwd)"
+work="$root/target/fixtures"
+
+# Resolves dependencies and writes `Cargo.lock` of the workspace if it is missing
+manifest="$(cargo metadata --format-version 1 --manifest-path "$root/Cargo.toml" |
+    grep -o '"manifest_path":"[^"]*/metaplex-token-metadata-0\.0\.1/Cargo\.toml"' |
+    cut -d '"' -f 4)"
+if [ -z "$manifest" ]; then
+    echo "metaplex-token-metadata 0.0.1 source not found" >&2
+    exit 1
+fi
+
+# Crate is built from a copy locked to the dependency versions of the workspace
+rm -rf "$work/metaplex-token-metadata"
+mkdir -p "$work"
+cp -r "$(dirname "$manifest")" "$work/metaplex-token-metadata"
+cp "$root/Cargo.lock" "$work/metaplex-token-metadata/Cargo.lock"
+# Copy is a workspace of its own rather than an unlisted package under this one
+printf '\n[workspace]\n' >> "$work/metaplex-token-metadata/Cargo.toml"
+
+cargo build-bpf \
+    --manifest-path "$work/metaplex-token-metadata/Cargo.toml" \
+    --bpf-out-dir "$fixtures"
@@ -0,0 +1,103 @@
+use premint_airdrop::{
+    error::AirdropError,
+    state::AirdropConfig,
+    util::{committed_slot_hash, draw_index},
+};
+use proptest::prelude::*;
+
+/// Draws every index of an airdrop of `airdrop_amount` tokens with `seeds`
+fn draw_all(airdrop_amount: u64, seeds: &[u64]) -> (Vec<u8>, Vec<u64>) {
+    let mut index_map = vec![0; AirdropConfig::index_map_len(airdrop_amount, true)];
+    let indices = (0..airdrop_amount)
+        .map(|minted| {
+            let seed = seeds[minted as usize % seeds.len()];
+            draw_index(
+                &mut index_map,
+                airdrop_amount,
+                airdrop_amount - minted,
+                seed,
+            )
+            .unwrap()
+        })
+        .collect();
+
+    (index_map, indices)
+}
+
+#[test]
+fn draw_index_rejects_exhausted_map() {
+    let mut index_map = vec![0xff, 0x03];
+    assert_eq!(draw_index(&mut index_map, 10, 0, 0), None);
+    assert_eq!(draw_index(&mut index_map, 10, 1, 0), None);
+    assert_eq!(index_map, [0xff, 0x03]);
+}
+
+#[test]
+fn draw_index_skips_minted_indices() {
+    let mut index_map = vec![0b1111_0101];
+    assert_eq!(draw_index(&mut index_map, 8, 2, 0), Some(1));
+    assert_eq!(draw_index(&mut index_map, 8, 1, 7), Some(3));
+    assert_eq!(index_map, [0xff]);
+}
+
+/// Slot hashes sysvar data of `entries`, most recent first
+fn slot_hashes(entries: &[(u64, [u8; 32])]) -> Vec<u8> {
+    let mut data = (entries.len() as u64).to_le_bytes().to_vec();
+    for (slot, hash) in entries {
+        data.extend_from_slice(&slot.to_le_bytes());
+        data.extend_from_slice(hash);
+    }
+    data
+}
+
+#[test]
+fn committed_slot_hash_finds_hash_of_draw_slot() {
+    let data = slot_hashes(&[(12, [3; 32]), (10, [2; 32]), (9, [1; 32])]);
+
+    assert_eq!(committed_slot_hash(&data, 10, 13).unwrap(), [2; 32]);
+    assert_eq!(committed_slot_hash(&data, 9, 13).unwrap(), [1; 32]);
+    // Skipped slot has no hash
+    assert!(matches!(
+        committed_slot_hash(&data, 11, 13),
+        Err(AirdropError::SlotHashExpired)
+    ));
+}
+
+#[test]
+fn committed_slot_hash_rejects_unavailable_slots() {
+    let data = slot_hashes(&[(600, [2; 32]), (10, [1; 32])]);
+
+    assert!(matches!(
+        committed_slot_hash(&data, 600, 600),
+        Err(AirdropError::SlotHashNotReady)
+    ));
+    assert!(matches!(
+        committed_slot_hash(&data, 10, 523),
+        Err(AirdropError::SlotHashExpired)
+    ));
+    assert_eq!(committed_slot_hash(&data, 10, 522).unwrap(), [1; 32]);
+    assert!(matches!(
+        committed_slot_hash(&[], 10, 11),
+        Err(AirdropError::SlotHashExpired)
+    ));
+}
+
+proptest! {
+    #[test]
+    fn draw_index_mints_every_index_once(
+        airdrop_amount in 1..300u64,
+        seeds in proptest::collection::vec(any::<u64>(), 1..16),
+    ) {
+        let (index_map, mut indices) = draw_all(airdrop_amount, &seeds);
+
+        indices.sort_unstable();
+        prop_assert_eq!(indices, (0..airdrop_amount).collect::<Vec<_>>());
+
+        // Padding bits of the last byte stay free
+        let mut expected = vec![0xff; index_map.len()];
+        if airdrop_amount % 8 > 0 {
+            *expected.last_mut().unwrap() = (1 << (airdrop_amount % 8)) - 1;
+        }
+        prop_assert_eq!(index_map, expected);
+    }
+}
@@ -0,0 +1,452 @@
+mod common;
+
+use common::{assert_error, program_account, Airdrop, Env, AIRDROP_AMOUNT, PRICE};
+use metaplex_token_metadata::state::{MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH};
+use premint_airdrop::{
+    error::AirdropError,
+    instruction::{AirdropInstruction, InitializeAirdropArgs, InitializeAirdropUserDataArgs},
+    pda::find_mint_authority,
+    state::AirdropConfig,
+};
+use solana_sdk::{
+    account::Account, hash::Hash, instruction::AccountMeta, program_error::ProgramError,
+    pubkey::Pubkey, signer::Signer,
+};
+
+#[tokio::test]
+async fn initialize_airdrop_stores_config() {
+    let airdrop = Airdrop::new();
+    let mut env = Env::start(vec![]).await;
+
+    env.create_airdrop(&airdrop).await.unwrap();
+
+    let account = env.account(&airdrop.address()).await.unwrap();
+    assert_eq!(account.owner, premint_airdrop::id());
+
+    assert_eq!(account.data.len(), airdrop.args().config_space());
+
+    let config = AirdropConfig::unpack_from_slice(&account.data).unwrap();
+    assert!(config.initialized);
+    assert_eq!(config.airdrop_authority, airdrop.authority.pubkey());
+    assert_eq!(config.admin_account, airdrop.admin);
+    assert_eq!(config.revenues_wallet, airdrop.revenues_wallet);
+    assert_eq!(config.base_uri, airdrop.args().base_uri);
+    assert_eq!(config.uri_extension, airdrop.args().uri_extension);
+    assert_eq!(config.name_template, airdrop.args().name_template);
+    assert_eq!(config.symbol, airdrop.args().symbol);
+    assert_eq!(config.airdrop_index, 0);
+    assert_eq!(config.airdrop_users, 0);
+    assert_eq!(config.airdrop_amount, AIRDROP_AMOUNT);
+    assert_eq!(config.price, PRICE);
+
+    let (mint_authority, _) = find_mint_authority(&airdrop.address());
+    let mint_authority = env.account(&mint_authority).await.unwrap();
+    assert_eq!(mint_authority.owner, premint_airdrop::id());
+}
+
+#[tokio::test]
+async fn initialize_airdrop_stores_shuffled_config() {
+    let airdrop = Airdrop::new();
+    let mut env = Env::start(vec![]).await;
+    let args = InitializeAirdropArgs {
+        shuffled: true,
+        ..airdrop.args()
+    };
+
+    let instructions = env
+        .create_airdrop_instructions_with(&airdrop, args.clone())
+        .await;
+    env.send(&instructions, &[&airdrop.keypair]).await.unwrap();
+
+    let mut account = env.account(&airdrop.address()).await.unwrap();
+    assert_eq!(account.data.len(), args.config_space());
+    assert_eq!(account.data.len(), airdrop.args().config_space() + 2);
+
+    let config = AirdropConfig::unpack_from_slice(&account.data).unwrap();
+    assert!(config.shuffled);
+    assert_eq!(config.index_map(&mut account.data).unwrap(), &[0, 0]);
+}
+
+#[tokio::test]
+async fn initialize_airdrop_stores_fair_mint_config() {
+    let airdrop = Airdrop::new();
+    let mut env = Env::start(vec![]).await;
+    let args = airdrop.fair_mint_args();
+
+    let instructions = env
+        .create_airdrop_instructions_with(&airdrop, args.clone())
+        .await;
+    env.send(&instructions, &[&airdrop.keypair]).await.unwrap();
+
+    let account = env.account(&airdrop.address()).await.unwrap();
+    let config = AirdropConfig::unpack_from_slice(&account.data).unwrap();
+    assert!(config.is_fair_mint());
+    assert_eq!(config.provenance_hash, args.provenance_hash);
+    assert_eq!(config.seed_commitment, args.seed_commitment);
+    assert!(!config.seed_revealed);
+    assert_eq!(config.starting_offset, 0);
+}
+
+#[tokio::test]
+async fn initialize_airdrop_stores_config_lines_address() {
+    let airdrop = Airdrop::new();
+    let mut env = Env::start(vec![]).await;
+    let config_lines = Pubkey::new_unique();
+
+    // Templates are not used and may be empty
+    let instructions = env
+        .create_airdrop_instructions_with(&airdrop, airdrop.config_lines_args(&config_lines))
+        .await;
+    env.send(&instructions, &[&airdrop.keypair]).await.unwrap();
+
+    let account = env.account(&airdrop.address()).await.unwrap();
+    let config = AirdropConfig::unpack_from_slice(&account.data).unwrap();
+    assert!(config.has_config_lines());
+    assert_eq!(config.config_lines, config_lines);
+    assert!(config.base_uri.is_empty());
+    assert!(config.name_template.is_empty());
+}
+
+#[tokio::test]
+async fn initialize_airdrop_stores_pda_mints_flag() {
+    let airdrop = Airdrop::new();
+    let mut env = Env::start(vec![]).await;
+    let args = InitializeAirdropArgs {
+        pda_mints: true,
+        ..airdrop.args()
+    };
+
+    let instructions = env.create_airdrop_instructions_with(&airdrop, args).await;
+    env.send(&instructions, &[&airdrop.keypair]).await.unwrap();
+
+    let account = env.account(&airdrop.address()).await.unwrap();
+    let config = AirdropConfig::unpack_from_slice(&account.data).unwrap();
+    assert!(config.pda_mints);
+    assert!(!config.shuffled);
+}
+
+#[tokio::test]
+async fn initialize_airdrop_rejects_initialized_config() {
+    let airdrop = Airdrop::new();
+    let mut env = Env::start(vec![airdrop.config_account(0)]).await;
+
+    let instructions = env.create_airdrop_instructions(&airdrop).await;
+    let result = env.send(&instructions[1..], &[]).await;
+
+    assert_error(result, AirdropError::AlreadyInitialized);
+}
+
+#[tokio::test]
+async fn initialize_airdrop_rejects_readonly_config() {
+    let airdrop = Airdrop::new();
+    let mut env = Env::start(vec![airdrop.uninitialized_config_account()]).await;
+
+    // Writeable flags are merged over the transaction, so `CreateAccount` is not sent
+    let mut instructions = env.create_airdrop_instructions(&airdrop).await;
+    instructions[1].accounts[0].is_writable = false;
+    let result = env.send(&instructions[1..], &[]).await;
+
+    assert_error(result, AirdropError::WriteableRequired);
+}
+
+#[tokio::test]
+async fn initialize_airdrop_rejects_foreign_config() {
+    let airdrop = Airdrop::new();
+    let foreign = Account {
+        lamports: 1_000_000_000,
+        data: vec![0; airdrop.args().config_space()],
+        owner: Pubkey::new_unique(),
+        ..Account::default()
+    };
+    let mut env = Env::start(vec![(airdrop.address(), foreign)]).await;
+
+    let instructions = env.create_airdrop_instructions(&airdrop).await;
+    let result = env.send(&instructions[1..], &[]).await;
+
+    assert_error(result, AirdropError::WrongAccountOwner);
+}
+
+#[tokio::test]
+async fn initialize_airdrop_rejects_wrong_mint_authority() {
+    let airdrop = Airdrop::new();
+    let mut env = Env::start(vec![]).await;
+
+    let mut instructions = env.create_airdrop_instructions(&airdrop).await;
+    instructions[1].accounts[2] = AccountMeta::new(Pubkey::new_unique(), false);
+    let result = env.send(&instructions, &[&airdrop.keypair]).await;
+
+    assert_error(result, AirdropError::PdaCheckFailed);
+}
+
+#[tokio::test]
+async fn initialize_airdrop_requires_fee_payer_signature() {
+    let airdrop = Airdrop::new();
+    let mut env = Env::start(vec![]).await;
+
+    let mut instructions = env.create_airdrop_instructions(&airdrop).await;
+    instructions[1].accounts[6] = AccountMeta::new(Pubkey::new_unique(), false);
+    let result = env.send(&instructions, &[&airdrop.keypair]).await;
+
+    assert_error(result, AirdropError::SignerRequired);
+}
+
+#[tokio::test]
+async fn initialize_airdrop_rejects_bad_instruction_data() {
+    let airdrop = Airdrop::new();
+    let mut env = Env::start(vec![]).await;
+
+    let mut instructions = env.create_airdrop_instructions(&airdrop).await;
+    instructions[1].data.pop();
+    let result = env.send(&instructions, &[&airdrop.keypair]).await;
+    assert_error(result, AirdropError::BadInstructionArgument);
+
+    // Shuffled flag follows amount, price, index offset and padding
+    let mut instructions = env.create_airdrop_instructions(&airdrop).await;
+    instructions[1].data[1 + 25] = 2;
+    let result = env.send(&instructions, &[&airdrop.keypair]).await;
+    assert_error(result, AirdropError::BadInstructionArgument);
+
+    let mut instructions = env.create_airdrop_instructions(&airdrop).await;
+    instructions[1].data[0] = 0;
+    let result = env.send(&instructions, &[&airdrop.keypair]).await;
+    assert_error(result, AirdropError::BadInstructionId);
+
+    let mut instructions = env.create_airdrop_instructions(&airdrop).await;
+    instructions[1].data = vec![];
+    let result = env.send(&instructions, &[&airdrop.keypair]).await;
+    assert_error(result, ProgramError::InvalidInstructionData);
+
+    // Accounts of `InitializeAirdrop` do not fit `InitializeAirdropUser`
+    let mut instructions = env.create_airdrop_instructions(&airdrop).await;
+    instructions[1].data =
+        AirdropInstruction::InitializeAirdropUser(InitializeAirdropUserDataArgs {})
+            .pack()
+            .unwrap();
+    let result = env.send(&instructions, &[&airdrop.keypair]).await;
+    assert_error(result, AirdropError::PdaCheckFailed);
+}
+
+#[tokio::test]
+async fn initialize_airdrop_rejects_wrong_config_size() {
+    let airdrop = Airdrop::new();
+    let config = program_account(airdrop.args().config_space() + 1, |_| Ok(()));
+    let mut env = Env::start(vec![(airdrop.address(), config)]).await;
+
+    let instructions = env.create_airdrop_instructions(&airdrop).await;
+    let result = env.send(&instructions[1..], &[]).await;
+    assert_error(result, AirdropError::WrongConfigSize);
+
+    // Index map of shuffled airdrop is missing
+    let shuffled = Airdrop::new();
+    let config = program_account(shuffled.args().config_space(), |_| Ok(()));
+    let mut env = Env::start(vec![(shuffled.address(), config)]).await;
+
+    let args = InitializeAirdropArgs {
+        shuffled: true,
+        ..shuffled.args()
+    };
+    let instructions = env.create_airdrop_instructions_with(&shuffled, args).await;
+    let result = env.send(&instructions[1..], &[]).await;
+    assert_error(result, AirdropError::WrongConfigSize);
+}
+
+#[tokio::test]
+async fn initialize_airdrop_accepts_metaplex_limits() {
+    let airdrop = Airdrop::new();
+    let mut env = Env::start(vec![]).await;
+
+    // Last token is `nnn #010` with `uuu010.json`
+    let args = InitializeAirdropArgs {
+        index_offset: 1,
+        index_padding: 3,
+        base_uri: "u".repeat(MAX_URI_LENGTH - "010.json".len()),
+        name_template: "n".repeat(MAX_NAME_LENGTH - " #010".len()) + " #{}",
+        symbol: "s".repeat(MAX_SYMBOL_LENGTH),
+        ..airdrop.args()
+    };
+
+    let instructions = env
+        .create_airdrop_instructions_with(&airdrop, args.clone())
+        .await;
+    env.send(&instructions, &[&airdrop.keypair]).await.unwrap();
+
+    let account = env.account(&airdrop.address()).await.unwrap();
+    let config = AirdropConfig::unpack_from_slice(&account.data).unwrap();
+    assert_eq!(config.base_uri, args.base_uri);
+    assert_eq!(config.uri_extension, args.uri_extension);
+    assert_eq!(config.name_template, args.name_template);
+    assert_eq!(config.symbol, args.symbol);
+    assert_eq!(config.index_offset, 1);
+    assert_eq!(config.index_padding, 3);
+}
+
+#[tokio::test]
+async fn initialize_airdrop_rejects_invalid_arguments() {
+    let airdrop = Airdrop::new();
+    let mut env = Env::start(vec![]).await;
+
+    let cases = [
+        (
+            InitializeAirdropArgs {
+                airdrop_amount: 0,
+                ..airdrop.args()
+            },
+            AirdropError::InvalidAirdropAmount,
+        ),
+        (
+            InitializeAirdropArgs {
+                base_uri: String::new(),
+                ..airdrop.args()
+            },
+            AirdropError::InvalidBaseUri,
+        ),
+        (
+            InitializeAirdropArgs {
+                base_uri: "u".repeat(MAX_URI_LENGTH - "9.json".len() + 1),
+                ..airdrop.args()
+            },
+            AirdropError::InvalidBaseUri,
+        ),
+        // Number of the last token grows with the amount, offset and padding
+        (
+            InitializeAirdropArgs {
+                airdrop_amount: 11,
+                base_uri: "u".repeat(MAX_URI_LENGTH - "9.json".len()),
+                ..airdrop.args()
+            },
+            AirdropError::InvalidBaseUri,
+        ),
+        (
+            InitializeAirdropArgs {
+                index_offset: 1,
+                base_uri: "u".repeat(MAX_URI_LENGTH - "9.json".len()),
+                ..airdrop.args()
+            },
+            AirdropError::InvalidBaseUri,
+        ),
+        (
+            InitializeAirdropArgs {
+                index_padding: 2,
+                name_template: "n".repeat(MAX_NAME_LENGTH - " #9".len()) + " #{}",
+                ..airdrop.args()
+            },
+            AirdropError::InvalidNameTemplate,
+        ),
+        (
+            InitializeAirdropArgs {
+                index_offset: u64::MAX,
+                ..airdrop.args()
+            },
+            AirdropError::InvalidIndexOffset,
+        ),
+        (
+            InitializeAirdropArgs {
+                name_template: "Test".to_string(),
+                ..airdrop.args()
+            },
+            AirdropError::InvalidNameTemplate,
+        ),
+        (
+            InitializeAirdropArgs {
+                name_template: "Test {} #{}".to_string(),
+                ..airdrop.args()
+            },
+            AirdropError::InvalidNameTemplate,
+        ),
+        (
+            InitializeAirdropArgs {
+                name_template: "n".repeat(MAX_NAME_LENGTH - " #9".len() + 1) + " #{}",
+                ..airdrop.args()
+            },
+            AirdropError::InvalidNameTemplate,
+        ),
+        (
+            InitializeAirdropArgs {
+                symbol: "s".repeat(MAX_SYMBOL_LENGTH + 1),
+                ..airdrop.args()
+            },
+            AirdropError::InvalidSymbol,
+        ),
+        (
+            InitializeAirdropArgs {
+                hidden_name_template: "Hidden #{}".to_string(),
+                ..airdrop.args()
+            },
+            AirdropError::InvalidHiddenMetadata,
+        ),
+        (
+            InitializeAirdropArgs {
+                hidden_uri: "https://example.com/hidden.json".to_string(),
+                ..airdrop.args()
+            },
+            AirdropError::InvalidHiddenMetadata,
+        ),
+        (
+            InitializeAirdropArgs {
+                hidden_name_template: "Hidden".to_string(),
+                ..airdrop.hidden_args()
+            },
+            AirdropError::InvalidHiddenMetadata,
+        ),
+        (
+            InitializeAirdropArgs {
+                hidden_name_template: "n".repeat(MAX_NAME_LENGTH - " #9".len() + 1) + " #{}",
+                ..airdrop.hidden_args()
+            },
+            AirdropError::InvalidHiddenMetadata,
+        ),
+        (
+            InitializeAirdropArgs {
+                hidden_uri: "u".repeat(MAX_URI_LENGTH + 1),
+                ..airdrop.hidden_args()
+            },
+            AirdropError::InvalidHiddenMetadata,
+        ),
+        (
+            InitializeAirdropArgs {
+                config_lines: Pubkey::new_unique(),
+                ..airdrop.hidden_args()
+            },
+            AirdropError::InvalidConfigLines,
+        ),
+        (
+            InitializeAirdropArgs {
+                provenance_hash: Hash::default(),
+                ..airdrop.fair_mint_args()
+            },
+            AirdropError::InvalidProvenance,
+        ),
+        (
+            InitializeAirdropArgs {
+                seed_commitment: Hash::default(),
+                ..airdrop.fair_mint_args()
+            },
+            AirdropError::InvalidProvenance,
+        ),
+        (
+            InitializeAirdropArgs {
+                hidden_name_template: String::new(),
+                hidden_uri: String::new(),
+                ..airdrop.fair_mint_args()
+            },
+            AirdropError::InvalidProvenance,
+        ),
+    ];
+
+    for (args, error) in cases {
+        let instructions = env.create_airdrop_instructions_with(&airdrop, args).await;
+        let result = env.send(&instructions, &[&airdrop.keypair]).await;
+
+        assert_error(result, error);
+    }
+
+    // Hidden URI is the last string of instruction data
+    let mut instructions = env
+        .create_airdrop_instructions_with(&airdrop, airdrop.hidden_args())
+        .await;
+    let len = instructions[1].data.len();
+    instructions[1].data[len - 2..].copy_from_slice(&[0xff, 0xfe]);
+    let result = env.send(&instructions, &[&airdrop.keypair]).await;
+
+    assert_error(result, AirdropError::BadMetadataString);
+}
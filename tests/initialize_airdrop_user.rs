@@ -0,0 +1,215 @@
+mod common;
+
+use common::{assert_error, Airdrop, Env};
+use premint_airdrop::{
+    error::AirdropError,
+    instruction::{initialize_airdrop_user, with_user_signer, InitializeAirdropArgs},
+    pda::find_airdrop_user_data,
+};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+};
+#[cfg(feature = "test-bpf")]
+use {
+    common::{program_account, wallet_account},
+    premint_airdrop::state::{AirdropConfig, AirdropUserData},
+    solana_sdk::program_pack::Pack,
+};
+
+fn register(env: &Env, airdrop: &Airdrop, user: &Pubkey) -> Instruction {
+    initialize_airdrop_user(&airdrop.address(), user, &env.payer.pubkey())
+}
+
+#[tokio::test]
+async fn initialize_airdrop_user_rejects_wrong_user_data_address() {
+    let airdrop = Airdrop::new();
+    let user = Pubkey::new_unique();
+    let mut env = Env::start(vec![airdrop.config_account(0)]).await;
+
+    let (other_user_data, _) = find_airdrop_user_data(&airdrop.address(), &Pubkey::new_unique());
+    let mut instruction = register(&env, &airdrop, &user);
+    instruction.accounts[0] = AccountMeta::new(other_user_data, false);
+    let result = env.send(&[instruction], &[]).await;
+
+    assert_error(result, AirdropError::PdaCheckFailed);
+}
+
+#[tokio::test]
+async fn initialize_airdrop_user_rejects_registered_user() {
+    let airdrop = Airdrop::new();
+    let user = Pubkey::new_unique();
+    let mut env = Env::start(vec![
+        airdrop.config_account(0),
+        airdrop.user_data_account(&user, 0),
+    ])
+    .await;
+
+    let instruction = register(&env, &airdrop, &user);
+    let result = env.send(&[instruction], &[]).await;
+
+    assert_error(result, AirdropError::UserAlreadyRegistered);
+}
+
+#[tokio::test]
+async fn initialize_airdrop_user_rejects_readonly_accounts() {
+    let airdrop = Airdrop::new();
+    let mut env = Env::start(vec![airdrop.config_account(0)]).await;
+
+    for index in [0, 2] {
+        let mut instruction = register(&env, &airdrop, &Pubkey::new_unique());
+        instruction.accounts[index].is_writable = false;
+        let result = env.send(&[instruction], &[]).await;
+
+        assert_error(result, AirdropError::WriteableRequired);
+    }
+}
+
+#[tokio::test]
+async fn initialize_airdrop_user_rejects_bad_config() {
+    let airdrop = Airdrop::new();
+    let uninitialized = Airdrop::new();
+    let user_data = Airdrop::new();
+    let user = Pubkey::new_unique();
+    // User data account of a registered user passed as the config
+    let (_, user_data_account) = airdrop.user_data_account(&user, 0);
+    let mut env = Env::start(vec![
+        uninitialized.uninitialized_config_account(),
+        (user_data.address(), user_data_account),
+    ])
+    .await;
+
+    let instruction = register(&env, &airdrop, &user);
+    let result = env.send(&[instruction], &[]).await;
+    assert_error(result, AirdropError::WrongAccountOwner);
+
+    let instruction = register(&env, &uninitialized, &user);
+    let result = env.send(&[instruction], &[]).await;
+    assert_error(result, AirdropError::Uninitialized);
+
+    let instruction = register(&env, &user_data, &user);
+    let result = env.send(&[instruction], &[]).await;
+    assert_error(result, AirdropError::NotAirdropConfig);
+}
+
+#[tokio::test]
+async fn initialize_airdrop_user_requires_fee_payer_signature() {
+    let airdrop = Airdrop::new();
+    let mut env = Env::start(vec![airdrop.config_account(0)]).await;
+
+    let mut instruction = register(&env, &airdrop, &Pubkey::new_unique());
+    instruction.accounts[4] = AccountMeta::new(Pubkey::new_unique(), false);
+    let result = env.send(&[instruction], &[]).await;
+
+    assert_error(result, AirdropError::SignerRequired);
+}
+
+#[tokio::test]
+async fn initialize_airdrop_user_requires_user_consent() {
+    let airdrop = Airdrop::new();
+    let user = Keypair::new();
+    let args = InitializeAirdropArgs {
+        require_user_payer: false,
+        ..airdrop.consent_args()
+    };
+    let mut env = Env::start(vec![airdrop.config_account_with(0, args)]).await;
+
+    let instruction = register(&env, &airdrop, &user.pubkey());
+    let result = env.send(&[instruction], &[]).await;
+    assert_error(result, AirdropError::SignerRequired);
+
+    let other = Airdrop::new();
+    let mut env = Env::start(vec![other.config_account_with(0, other.consent_args())]).await;
+
+    let instruction = with_user_signer(register(&env, &other, &user.pubkey()), &user.pubkey());
+    let result = env.send(&[instruction], &[&user]).await;
+    assert_error(result, AirdropError::UserMustPay);
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn initialize_airdrop_user_accepts_consenting_user() {
+    let airdrop = Airdrop::new();
+    let user = Keypair::new();
+    let mut env = Env::start(vec![
+        airdrop.config_account_with(0, airdrop.consent_args()),
+        wallet_account(&user.pubkey()),
+    ])
+    .await;
+
+    let instruction = initialize_airdrop_user(&airdrop.address(), &user.pubkey(), &user.pubkey());
+    env.send(&[instruction], &[&user]).await.unwrap();
+
+    let (user_data, _) = find_airdrop_user_data(&airdrop.address(), &user.pubkey());
+    let account = env.account(&user_data).await.unwrap();
+    let user_data = AirdropUserData::unpack(&account.data).unwrap();
+    assert_eq!(user_data.user, user.pubkey());
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn initialize_airdrop_user_accepts_signing_user_paid_by_another_wallet() {
+    let airdrop = Airdrop::new();
+    let user = Keypair::new();
+    let args = InitializeAirdropArgs {
+        require_user_payer: false,
+        ..airdrop.consent_args()
+    };
+    let mut env = Env::start(vec![airdrop.config_account_with(0, args)]).await;
+
+    let instruction = with_user_signer(register(&env, &airdrop, &user.pubkey()), &user.pubkey());
+    env.send(&[instruction], &[&user]).await.unwrap();
+
+    let (user_data, _) = find_airdrop_user_data(&airdrop.address(), &user.pubkey());
+    let account = env.account(&user_data).await.unwrap();
+    let user_data = AirdropUserData::unpack(&account.data).unwrap();
+    assert_eq!(user_data.user, user.pubkey());
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn initialize_airdrop_user_creates_user_data() {
+    let airdrop = Airdrop::new();
+    let user = Pubkey::new_unique();
+    let mut env = Env::start(vec![]).await;
+    env.create_airdrop(&airdrop).await.unwrap();
+
+    let instruction = register(&env, &airdrop, &user);
+    env.send(&[instruction], &[]).await.unwrap();
+
+    let (user_data, _) = find_airdrop_user_data(&airdrop.address(), &user);
+    let account = env.account(&user_data).await.unwrap();
+    assert_eq!(account.owner, premint_airdrop::id());
+
+    let user_data = AirdropUserData::unpack(&account.data).unwrap();
+    assert_eq!(user_data.airdrop, airdrop.address());
+    assert_eq!(user_data.user, user);
+    assert_eq!(user_data.mints_amount, 0);
+    assert_eq!(user_data.locked_till, 0);
+
+    let config = env.account(&airdrop.address()).await.unwrap();
+    let config = AirdropConfig::unpack_from_slice(&config.data).unwrap();
+    assert_eq!(config.airdrop_users, 1);
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn initialize_airdrop_user_rejects_user_counter_overflow() {
+    let airdrop = Airdrop::new();
+    let config = AirdropConfig {
+        airdrop_users: u64::MAX,
+        ..airdrop.config(0)
+    };
+    let mut env = Env::start(vec![(
+        airdrop.address(),
+        program_account(config.data_len(), |data| config.pack_into_slice(data)),
+    )])
+    .await;
+
+    let instruction = register(&env, &airdrop, &Pubkey::new_unique());
+    let result = env.send(&[instruction], &[]).await;
+
+    assert_error(result, AirdropError::ArithmeticOverflow);
+}
@@ -0,0 +1,66 @@
+use premint_airdrop::util::{
+    metadata_file_index, metadata_name, metadata_uri, starting_offset, token_number,
+};
+use solana_sdk::pubkey::Pubkey;
+
+#[test]
+fn token_number_applies_offset_and_padding() {
+    assert_eq!(token_number(0, 0, 0).unwrap(), "0");
+    assert_eq!(token_number(0, 1, 0).unwrap(), "1");
+    assert_eq!(token_number(41, 1, 4).unwrap(), "0042");
+    assert_eq!(token_number(12345, 0, 3).unwrap(), "12345");
+    assert_eq!(token_number(u64::MAX, 0, 0).unwrap(), u64::MAX.to_string());
+    assert_eq!(token_number(u64::MAX, 1, 0), None);
+}
+
+#[test]
+fn metadata_name_replaces_placeholder() {
+    assert_eq!(metadata_name("Test #{}", "007"), "Test #007");
+    assert_eq!(metadata_name("{}: Test", "1"), "1: Test");
+}
+
+#[test]
+fn metadata_uri_appends_number_and_extension() {
+    assert_eq!(
+        metadata_uri("https://example.com/", "1", ".json"),
+        "https://example.com/1.json"
+    );
+    assert_eq!(metadata_uri("ipfs://cid/", "0001", ""), "ipfs://cid/0001");
+}
+
+#[test]
+fn metadata_file_index_rotates_by_starting_offset() {
+    assert_eq!(metadata_file_index(0, 0, 10), 0);
+    assert_eq!(metadata_file_index(0, 3, 10), 3);
+    assert_eq!(metadata_file_index(7, 3, 10), 0);
+    assert_eq!(metadata_file_index(9, 3, 10), 2);
+    assert_eq!(
+        metadata_file_index(u64::MAX - 1, u64::MAX - 1, u64::MAX),
+        u64::MAX - 2
+    );
+}
+
+#[test]
+fn starting_offset_depends_on_seed_airdrop_and_slot_hash() {
+    let airdrop = Pubkey::new_unique();
+    let offset = starting_offset(&[1; 32], &airdrop, &[3; 32], 1_000_000);
+
+    assert!(offset < 1_000_000);
+    assert_eq!(
+        starting_offset(&[1; 32], &airdrop, &[3; 32], 1_000_000),
+        offset
+    );
+    assert_ne!(
+        starting_offset(&[2; 32], &airdrop, &[3; 32], 1_000_000),
+        offset
+    );
+    assert_ne!(
+        starting_offset(&[1; 32], &Pubkey::new_unique(), &[3; 32], 1_000_000),
+        offset
+    );
+    assert_ne!(
+        starting_offset(&[1; 32], &airdrop, &[4; 32], 1_000_000),
+        offset
+    );
+    assert_eq!(starting_offset(&[1; 32], &airdrop, &[3; 32], 1), 0);
+}
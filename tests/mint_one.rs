@@ -0,0 +1,844 @@
+mod common;
+
+use common::{assert_error, program_account, Airdrop, Env, AIRDROP_AMOUNT};
+use premint_airdrop::{
+    error::AirdropError,
+    instruction::{
+        mint_one, mint_one_with_config_lines, mint_one_with_pda_mint, with_user_signer,
+        InitializeAirdropArgs,
+    },
+    pda::{find_airdrop_user_data, find_mint},
+    state::{AirdropUserData, ConfigLine},
+};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    slot_hashes::MAX_ENTRIES,
+    sysvar,
+    transaction::TransactionError,
+};
+#[cfg(feature = "test-bpf")]
+use {
+    common::{wallet_account, PRICE},
+    metaplex_token_metadata::{
+        state::{Key, Metadata, MAX_METADATA_LEN},
+        utils::try_from_slice_checked,
+    },
+    premint_airdrop::{
+        instruction::initialize_airdrop_user, pda::find_metadata_account, state::AirdropConfig,
+    },
+    spl_associated_token_account::get_associated_token_address,
+};
+
+/// Registered user able to mint
+struct User {
+    address: Pubkey,
+    mint: Keypair,
+}
+
+impl User {
+    fn new() -> User {
+        User {
+            address: Pubkey::new_unique(),
+            mint: Keypair::new(),
+        }
+    }
+
+    fn mint_one(&self, env: &Env, airdrop: &Airdrop) -> Instruction {
+        airdrop.mint_one(&self.mint.pubkey(), &self.address, &env.payer.pubkey())
+    }
+
+    fn mint_one_shuffled(&self, env: &Env, airdrop: &Airdrop) -> Instruction {
+        airdrop.mint_one_shuffled(&self.mint.pubkey(), &self.address, &env.payer.pubkey())
+    }
+
+    fn mint_one_with_config_lines(
+        &self,
+        env: &Env,
+        airdrop: &Airdrop,
+        config_lines: &Pubkey,
+    ) -> Instruction {
+        mint_one_with_config_lines(
+            &airdrop.address(),
+            &self.mint.pubkey(),
+            &self.address,
+            &env.payer.pubkey(),
+            &airdrop.authority.pubkey(),
+            &airdrop.admin,
+            &airdrop.revenues_wallet,
+            false,
+            config_lines,
+        )
+    }
+
+    async fn send(
+        &self,
+        env: &mut Env,
+        airdrop: &Airdrop,
+        instruction: Instruction,
+    ) -> Result<(), TransactionError> {
+        env.send(&[instruction], &[&self.mint, &airdrop.authority])
+            .await
+    }
+}
+
+/// Environment with minted `airdrop_index` tokens and `user` registered with `locked_till`
+async fn start(airdrop: &Airdrop, airdrop_index: u64, user: &User, locked_till: i64) -> Env {
+    Env::start(vec![
+        airdrop.config_account(airdrop_index),
+        airdrop.user_data_account(&user.address, locked_till),
+    ])
+    .await
+}
+
+#[tokio::test]
+async fn mint_one_rejects_exhausted_supply() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let mut env = start(&airdrop, AIRDROP_AMOUNT, &user, 0).await;
+
+    let instruction = user.mint_one(&env, &airdrop);
+    let result = user.send(&mut env, &airdrop, instruction).await;
+
+    assert_error(result, AirdropError::OutOfSupply);
+}
+
+#[tokio::test]
+async fn mint_one_rejects_locked_user() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let mut env = start(&airdrop, 0, &user, i64::MAX).await;
+
+    let instruction = user.mint_one(&env, &airdrop);
+    let result = user.send(&mut env, &airdrop, instruction).await;
+
+    assert_error(result, AirdropError::UserTimeout);
+}
+
+#[tokio::test]
+async fn mint_one_requires_committed_draw_of_shuffled_airdrop() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let mut env = Env::start(vec![
+        airdrop.config_account_with(0, airdrop.shuffled_args()),
+        airdrop.user_data_account(&user.address, 0),
+    ])
+    .await;
+
+    let instruction = user.mint_one_shuffled(&env, &airdrop);
+    let result = user.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::DrawNotCommitted);
+
+    let instruction = airdrop.commit_draw(&user.address);
+    env.send(&[instruction], &[&airdrop.authority])
+        .await
+        .unwrap();
+
+    // Hash of the committed slot is known only once the slot is over
+    env.refresh_blockhash().await;
+    let instruction = user.mint_one_shuffled(&env, &airdrop);
+    let result = user.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::SlotHashNotReady);
+
+    // Expired draw no longer lifts the user timeout
+    env.warp(MAX_ENTRIES as u64 + 1).await;
+    let instruction = user.mint_one_shuffled(&env, &airdrop);
+    let result = user.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::UserTimeout);
+}
+
+#[tokio::test]
+async fn mint_one_requires_slot_hashes_of_shuffled_airdrop() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let mut env = Env::start(vec![
+        airdrop.config_account_with(0, airdrop.shuffled_args()),
+        airdrop.user_data_account(&user.address, 0),
+    ])
+    .await;
+
+    let instruction = user.mint_one(&env, &airdrop);
+    let result = user.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, ProgramError::NotEnoughAccountKeys);
+
+    let mut instruction = user.mint_one_shuffled(&env, &airdrop);
+    instruction.accounts[17].pubkey = sysvar::rent::id();
+    let result = user.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::WrongSlotHashesSysvar);
+}
+
+#[tokio::test]
+async fn mint_one_rejects_bad_config() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let mut env = Env::start(vec![
+        airdrop.uninitialized_config_account(),
+        airdrop.user_data_account(&user.address, 0),
+    ])
+    .await;
+
+    let instruction = user.mint_one(&env, &airdrop);
+    let result = user.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::Uninitialized);
+
+    let mut instruction = user.mint_one(&env, &airdrop);
+    instruction.accounts[0].is_writable = false;
+    let result = user.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::WriteableRequired);
+
+    let other = Airdrop::new();
+    let instruction = user.mint_one(&env, &other);
+    let result = env
+        .send(&[instruction], &[&user.mint, &other.authority])
+        .await;
+    assert_error(result, AirdropError::WrongAccountOwner);
+}
+
+/// User data at the address derived for `airdrop`, but filled for another airdrop
+fn other_airdrop_user_data(airdrop: &Airdrop, user: &User) -> (Pubkey, Account) {
+    let (user_data, _) = find_airdrop_user_data(&airdrop.address(), &user.address);
+    let (_, account) = Airdrop::new().user_data_account(&user.address, 0);
+    (user_data, account)
+}
+
+#[tokio::test]
+async fn mint_one_rejects_bad_user_data() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let unregistered = User::new();
+    let uninitialized = User::new();
+    let config_as_user_data = User::new();
+    let other_airdrop_user = User::new();
+    let (uninitialized_data, _) =
+        find_airdrop_user_data(&airdrop.address(), &uninitialized.address);
+    let (config_data, _) = find_airdrop_user_data(&airdrop.address(), &config_as_user_data.address);
+
+    let mut env = Env::start(vec![
+        airdrop.config_account(0),
+        airdrop.user_data_account(&user.address, 0),
+        (
+            uninitialized_data,
+            program_account(AirdropUserData::LEN, |_| Ok(())),
+        ),
+        (config_data, airdrop.config_account(0).1),
+        other_airdrop_user_data(&airdrop, &other_airdrop_user),
+    ])
+    .await;
+
+    let instruction = unregistered.mint_one(&env, &airdrop);
+    let result = unregistered.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::WrongAccountOwner);
+
+    let instruction = uninitialized.mint_one(&env, &airdrop);
+    let result = uninitialized.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::Uninitialized);
+
+    let instruction = config_as_user_data.mint_one(&env, &airdrop);
+    let result = config_as_user_data
+        .send(&mut env, &airdrop, instruction)
+        .await;
+    assert_error(result, AirdropError::NotAirdropUserData);
+
+    // User data of `user` passed for another recipient
+    let mut instruction = unregistered.mint_one(&env, &airdrop);
+    instruction.accounts[1] = user.mint_one(&env, &airdrop).accounts[1].clone();
+    let result = unregistered.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::WrongRecipient);
+
+    let instruction = other_airdrop_user.mint_one(&env, &airdrop);
+    let result = other_airdrop_user
+        .send(&mut env, &airdrop, instruction)
+        .await;
+    assert_error(result, AirdropError::WrongAirdrop);
+
+    let mut instruction = user.mint_one(&env, &airdrop);
+    instruction.accounts[1].is_writable = false;
+    let result = user.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::WriteableRequired);
+}
+
+#[tokio::test]
+async fn mint_one_requires_signatures() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let mut env = start(&airdrop, 0, &user, 0).await;
+
+    let mut instruction = user.mint_one(&env, &airdrop);
+    instruction.accounts[14].is_signer = false;
+    let result = env.send(&[instruction], &[&user.mint]).await;
+    assert_error(result, AirdropError::SignerRequired);
+
+    let mut instruction = user.mint_one(&env, &airdrop);
+    instruction.accounts[2].is_signer = false;
+    let result = env.send(&[instruction], &[&airdrop.authority]).await;
+    assert_error(result, AirdropError::SignerRequired);
+
+    let mut instruction = user.mint_one(&env, &airdrop);
+    instruction.accounts[13] = AccountMeta::new(Pubkey::new_unique(), false);
+    let result = user.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::SignerRequired);
+}
+
+#[tokio::test]
+async fn mint_one_rejects_wrong_accounts() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let mut env = start(&airdrop, 0, &user, 0).await;
+
+    let cases = [
+        (4, AirdropError::WrongRecipientTokenAccount),
+        (5, AirdropError::WrongTokenMetadataAccount),
+        (6, AirdropError::PdaCheckFailed),
+        (14, AirdropError::WrongAccountAddress),
+        (15, AirdropError::WrongAccountAddress),
+        (16, AirdropError::WrongAccountAddress),
+        (7, AirdropError::WrongSystemProgram),
+        (10, AirdropError::WrongTokenProgram),
+        (11, AirdropError::WrongAssociatedTokenProgram),
+        (12, AirdropError::WrongTokenMetadataProgram),
+        (9, AirdropError::WrongRentSysvar),
+    ];
+
+    for (index, error) in cases {
+        let wrong = Keypair::new();
+        let mut instruction = user.mint_one(&env, &airdrop);
+        instruction.accounts[index].pubkey = wrong.pubkey();
+        let signers: &[&Keypair] = match instruction.accounts[index].is_signer {
+            // Wrong airdrop authority signs in place of the configured one
+            true => &[&user.mint, &wrong],
+            false => &[&user.mint, &airdrop.authority],
+        };
+        let result = env.send(&[instruction], signers).await;
+
+        assert_error(result, error);
+    }
+
+    // Clock is read before eligibility checks, so it is replaced with a readable sysvar
+    let mut instruction = user.mint_one(&env, &airdrop);
+    instruction.accounts[8].pubkey = sysvar::rent::id();
+    let result = user.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::WrongClockSysvar);
+}
+
+#[tokio::test]
+async fn mint_one_rejects_unconfigured_airdrop_authority() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let mut env = start(&airdrop, 0, &user, 0).await;
+
+    // Minter approving the mint with their own key instead of the co-signer
+    let minter_authority = Keypair::new();
+    let instruction = mint_one(
+        &airdrop.address(),
+        &user.mint.pubkey(),
+        &user.address,
+        &env.payer.pubkey(),
+        &minter_authority.pubkey(),
+        &airdrop.admin,
+        &airdrop.revenues_wallet,
+        false,
+    );
+    let result = env
+        .send(&[instruction], &[&user.mint, &minter_authority])
+        .await;
+
+    assert_error(result, AirdropError::WrongAccountAddress);
+}
+
+#[tokio::test]
+async fn mint_one_requires_user_consent() {
+    let airdrop = Airdrop::new();
+    let recipient = Keypair::new();
+    let user = User {
+        address: recipient.pubkey(),
+        mint: Keypair::new(),
+    };
+    let args = InitializeAirdropArgs {
+        require_user_payer: false,
+        ..airdrop.consent_args()
+    };
+    let mut env = Env::start(vec![
+        airdrop.config_account_with(0, args),
+        airdrop.user_data_account(&user.address, 0),
+    ])
+    .await;
+
+    let instruction = user.mint_one(&env, &airdrop);
+    let result = user.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::SignerRequired);
+
+    let other = Airdrop::new();
+    let mut env = Env::start(vec![
+        other.config_account_with(0, other.consent_args()),
+        other.user_data_account(&user.address, 0),
+    ])
+    .await;
+
+    let instruction = with_user_signer(user.mint_one(&env, &other), &user.address);
+    let result = env
+        .send(&[instruction], &[&user.mint, &other.authority, &recipient])
+        .await;
+    assert_error(result, AirdropError::UserMustPay);
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn mint_one_accepts_consenting_user() {
+    let airdrop = Airdrop::new();
+    let recipient = Keypair::new();
+    let mint = Keypair::new();
+    let mut env = Env::start(vec![
+        airdrop.config_account_with(0, airdrop.consent_args()),
+        airdrop.user_data_account(&recipient.pubkey(), 0),
+        wallet_account(&recipient.pubkey()),
+    ])
+    .await;
+
+    let instruction = airdrop.mint_one(&mint.pubkey(), &recipient.pubkey(), &recipient.pubkey());
+    env.send(&[instruction], &[&mint, &airdrop.authority, &recipient])
+        .await
+        .unwrap();
+
+    let token_account = get_associated_token_address(&recipient.pubkey(), &mint.pubkey());
+    let token_account = env.account(&token_account).await.unwrap();
+    let token_account = spl_token::state::Account::unpack(&token_account.data).unwrap();
+    assert_eq!(token_account.amount, 1);
+}
+
+/// `MintOne` of an airdrop with `pda_mints`, minting the token after `minted` ones
+fn mint_one_with_pda_mint_after(
+    env: &Env,
+    airdrop: &Airdrop,
+    user: &User,
+    minted: u64,
+) -> Instruction {
+    mint_one_with_pda_mint(
+        &airdrop.address(),
+        minted,
+        &user.address,
+        &env.payer.pubkey(),
+        &airdrop.authority.pubkey(),
+        &airdrop.admin,
+        &airdrop.revenues_wallet,
+        false,
+    )
+}
+
+#[tokio::test]
+async fn mint_one_rejects_wrong_pda_mint() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let args = InitializeAirdropArgs {
+        pda_mints: true,
+        ..airdrop.args()
+    };
+    let mut env = Env::start(vec![
+        airdrop.config_account_with(1, args),
+        airdrop.user_data_account(&user.address, 0),
+    ])
+    .await;
+
+    // Mint derived before another token was minted
+    let instruction = mint_one_with_pda_mint_after(&env, &airdrop, &user, 0);
+    let result = env.send(&[instruction], &[&airdrop.authority]).await;
+    assert_error(result, AirdropError::WrongMintAccount);
+
+    let instruction = user.mint_one(&env, &airdrop);
+    let result = user.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::WrongMintAccount);
+}
+
+#[tokio::test]
+async fn mint_one_derives_pda_mint_from_minted_amount() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let env = Env::start(vec![]).await;
+
+    let instruction = mint_one_with_pda_mint_after(&env, &airdrop, &user, 3);
+    let (mint, _) = find_mint(&airdrop.address(), 3);
+    assert_eq!(instruction.accounts[2].pubkey, mint);
+    assert!(!instruction.accounts[2].is_signer);
+    assert_ne!(find_mint(&airdrop.address(), 4).0, mint);
+    assert_ne!(find_mint(&Airdrop::new().address(), 3).0, mint);
+}
+
+fn config_line(index: u64) -> ConfigLine {
+    ConfigLine {
+        name: format!("Line #{}", index),
+        uri: format!("https://example.com/line/{}.json", index),
+    }
+}
+
+/// Environment with config lines airdrop, `user` registered and `lines` uploaded from index 0
+async fn start_with_config_lines(
+    airdrop: &Airdrop,
+    config_lines: &Pubkey,
+    user: &User,
+    lines: &[ConfigLine],
+) -> Env {
+    let args = airdrop.config_lines_args(config_lines);
+    Env::start(vec![
+        airdrop.config_account_with(0, args),
+        airdrop.config_lines_account(config_lines, lines),
+        airdrop.user_data_account(&user.address, 0),
+    ])
+    .await
+}
+
+#[tokio::test]
+async fn mint_one_rejects_wrong_config_lines() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let config_lines = Pubkey::new_unique();
+    let mut env = start_with_config_lines(&airdrop, &config_lines, &user, &[config_line(0)]).await;
+
+    let instruction = user.mint_one(&env, &airdrop);
+    let result = user.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, ProgramError::NotEnoughAccountKeys);
+
+    let instruction = user.mint_one_with_config_lines(&env, &airdrop, &Pubkey::new_unique());
+    let result = user.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::WrongConfigLinesAccount);
+}
+
+#[tokio::test]
+async fn mint_one_rejects_config_lines_of_another_airdrop() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let config_lines = Pubkey::new_unique();
+    let args = airdrop.config_lines_args(&config_lines);
+    let mut env = Env::start(vec![
+        airdrop.config_account_with(0, args),
+        Airdrop::new().config_lines_account(&config_lines, &[config_line(0)]),
+        airdrop.user_data_account(&user.address, 0),
+    ])
+    .await;
+
+    let instruction = user.mint_one_with_config_lines(&env, &airdrop, &config_lines);
+    let result = user.send(&mut env, &airdrop, instruction).await;
+
+    assert_error(result, AirdropError::WrongConfigLinesAccount);
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn mint_one_mints_token_from_config_line() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let config_lines = Pubkey::new_unique();
+    let mut env = start_with_config_lines(&airdrop, &config_lines, &user, &[config_line(0)]).await;
+
+    let instruction = user.mint_one_with_config_lines(&env, &airdrop, &config_lines);
+    user.send(&mut env, &airdrop, instruction).await.unwrap();
+
+    let (metadata, _) = find_metadata_account(&user.mint.pubkey());
+    let metadata = env.account(&metadata).await.unwrap();
+    let metadata: Metadata =
+        try_from_slice_checked(&metadata.data, Key::MetadataV1, MAX_METADATA_LEN).unwrap();
+    assert_eq!(
+        metadata.data.name.trim_end_matches('\0'),
+        config_line(0).name
+    );
+    assert_eq!(metadata.data.uri.trim_end_matches('\0'), config_line(0).uri);
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn mint_one_rejects_missing_config_line() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let config_lines = Pubkey::new_unique();
+    let mut env = start_with_config_lines(&airdrop, &config_lines, &user, &[]).await;
+
+    let instruction = user.mint_one_with_config_lines(&env, &airdrop, &config_lines);
+    let result = user.send(&mut env, &airdrop, instruction).await;
+
+    assert_error(result, AirdropError::ConfigLineMissing);
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn mint_one_mints_token_and_locks_user() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let mut env = Env::start(vec![]).await;
+    env.create_airdrop(&airdrop).await.unwrap();
+
+    let instruction =
+        initialize_airdrop_user(&airdrop.address(), &user.address, &env.payer.pubkey());
+    env.send(&[instruction], &[]).await.unwrap();
+
+    let instruction = user.mint_one(&env, &airdrop);
+    user.send(&mut env, &airdrop, instruction).await.unwrap();
+
+    let config = env.account(&airdrop.address()).await.unwrap();
+    let config = AirdropConfig::unpack_from_slice(&config.data).unwrap();
+    assert_eq!(config.airdrop_index, 1);
+
+    let (user_data, _) = find_airdrop_user_data(&airdrop.address(), &user.address);
+    let user_data = env.account(&user_data).await.unwrap();
+    let user_data = AirdropUserData::unpack(&user_data.data).unwrap();
+    assert_eq!(user_data.mints_amount, 1);
+    assert!(user_data.locked_till > 0);
+
+    let token_account = get_associated_token_address(&user.address, &user.mint.pubkey());
+    let token_account = env.account(&token_account).await.unwrap();
+    let token_account = spl_token::state::Account::unpack(&token_account.data).unwrap();
+    assert_eq!(token_account.amount, 1);
+    assert_eq!(token_account.owner, user.address);
+
+    let (metadata, _) = find_metadata_account(&user.mint.pubkey());
+    let metadata = env.account(&metadata).await.unwrap();
+    assert_eq!(metadata.owner, metaplex_token_metadata::id());
+
+    // Metaplex pads metadata strings with zeros
+    let metadata: Metadata =
+        try_from_slice_checked(&metadata.data, Key::MetadataV1, MAX_METADATA_LEN).unwrap();
+    assert_eq!(metadata.data.name.trim_end_matches('\0'), "Test #0");
+    assert_eq!(metadata.data.symbol.trim_end_matches('\0'), "TEST");
+    assert_eq!(
+        metadata.data.uri.trim_end_matches('\0'),
+        "https://example.com/0.json"
+    );
+
+    let revenues_wallet = env.account(&airdrop.revenues_wallet).await.unwrap();
+    assert_eq!(revenues_wallet.lamports, PRICE);
+
+    let next = User {
+        address: user.address,
+        mint: Keypair::new(),
+    };
+    let instruction = next.mint_one(&env, &airdrop);
+    let result = next.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::UserTimeout);
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn mint_one_creates_pda_mints() {
+    let airdrop = Airdrop::new();
+    let args = InitializeAirdropArgs {
+        pda_mints: true,
+        ..airdrop.args()
+    };
+    let users = [User::new(), User::new()];
+    let mut env = Env::start(vec![
+        airdrop.config_account_with(0, args),
+        airdrop.user_data_account(&users[0].address, 0),
+        airdrop.user_data_account(&users[1].address, 0),
+    ])
+    .await;
+
+    for (minted, user) in users.iter().enumerate() {
+        let minted = minted as u64;
+        let instruction = mint_one_with_pda_mint_after(&env, &airdrop, user, minted);
+        env.send(&[instruction], &[&airdrop.authority])
+            .await
+            .unwrap();
+
+        let (mint, _) = find_mint(&airdrop.address(), minted);
+        let mint = env.account(&mint).await.unwrap();
+        assert_eq!(mint.owner, spl_token::id());
+        let mint = spl_token::state::Mint::unpack(&mint.data).unwrap();
+        assert_eq!(mint.supply, 1);
+    }
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn mint_one_creates_prefunded_pda_mint() {
+    let airdrop = Airdrop::new();
+    let args = InitializeAirdropArgs {
+        pda_mints: true,
+        ..airdrop.args()
+    };
+    let user = User::new();
+    let (mint, _) = find_mint(&airdrop.address(), 0);
+    let mut env = Env::start(vec![
+        airdrop.config_account_with(0, args),
+        airdrop.user_data_account(&user.address, 0),
+        // Anyone may fund the next mint address before the mint
+        (
+            mint,
+            Account {
+                lamports: 1,
+                ..Account::default()
+            },
+        ),
+    ])
+    .await;
+
+    let instruction = mint_one_with_pda_mint_after(&env, &airdrop, &user, 0);
+    env.send(&[instruction], &[&airdrop.authority])
+        .await
+        .unwrap();
+
+    let mint = env.account(&mint).await.unwrap();
+    assert_eq!(mint.owner, spl_token::id());
+    assert_eq!(
+        mint.lamports,
+        env.banks_client
+            .get_rent()
+            .await
+            .unwrap()
+            .minimum_balance(spl_token::state::Mint::LEN)
+    );
+    let mint = spl_token::state::Mint::unpack(&mint.data).unwrap();
+    assert_eq!(mint.supply, 1);
+}
+
+/// Token account at the associated address of `user` and `mint`, created before the mint
+#[cfg(feature = "test-bpf")]
+fn token_account(user: &User, mint: &Pubkey, owner: &Pubkey) -> (Pubkey, Account) {
+    let state = spl_token::state::Account {
+        mint: *mint,
+        owner: *owner,
+        state: spl_token::state::AccountState::Initialized,
+        ..spl_token::state::Account::default()
+    };
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account::pack(state, &mut data).unwrap();
+
+    (
+        get_associated_token_address(&user.address, &user.mint.pubkey()),
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: spl_token::id(),
+            ..Account::default()
+        },
+    )
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn mint_one_accepts_existing_token_account() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let mut env = Env::start(vec![
+        airdrop.config_account(0),
+        airdrop.user_data_account(&user.address, 0),
+        token_account(&user, &user.mint.pubkey(), &user.address),
+    ])
+    .await;
+
+    let instruction = user.mint_one(&env, &airdrop);
+    user.send(&mut env, &airdrop, instruction).await.unwrap();
+
+    let token_account = get_associated_token_address(&user.address, &user.mint.pubkey());
+    let token_account = env.account(&token_account).await.unwrap();
+    let token_account = spl_token::state::Account::unpack(&token_account.data).unwrap();
+    assert_eq!(token_account.amount, 1);
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn mint_one_rejects_invalid_existing_token_account() {
+    let airdrop = Airdrop::new();
+    let foreign_owner = User::new();
+    let foreign_mint = User::new();
+    let cases = [
+        (
+            &foreign_owner,
+            token_account(
+                &foreign_owner,
+                &foreign_owner.mint.pubkey(),
+                &Pubkey::new_unique(),
+            ),
+        ),
+        (
+            &foreign_mint,
+            token_account(&foreign_mint, &Pubkey::new_unique(), &foreign_mint.address),
+        ),
+    ];
+
+    for (user, token_account) in cases {
+        let mut env = Env::start(vec![
+            airdrop.config_account(0),
+            airdrop.user_data_account(&user.address, 0),
+            token_account,
+        ])
+        .await;
+
+        let instruction = user.mint_one(&env, &airdrop);
+        let result = user.send(&mut env, &airdrop, instruction).await;
+
+        assert_error(result, AirdropError::InvalidRecipientTokenAccount);
+    }
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn mint_one_mints_last_token() {
+    let airdrop = Airdrop::new();
+    let user = User::new();
+    let late_user = User::new();
+    let mut env = Env::start(vec![
+        airdrop.config_account(AIRDROP_AMOUNT - 1),
+        airdrop.user_data_account(&user.address, 0),
+        airdrop.user_data_account(&late_user.address, 0),
+    ])
+    .await;
+
+    let instruction = user.mint_one(&env, &airdrop);
+    user.send(&mut env, &airdrop, instruction).await.unwrap();
+
+    let instruction = late_user.mint_one(&env, &airdrop);
+    let result = late_user.send(&mut env, &airdrop, instruction).await;
+    assert_error(result, AirdropError::OutOfSupply);
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn mint_one_draws_every_shuffled_index_once() {
+    let airdrop = Airdrop::new();
+    let users: Vec<User> = (0..AIRDROP_AMOUNT).map(|_| User::new()).collect();
+
+    let mut accounts = vec![airdrop.config_account_with(0, airdrop.shuffled_args())];
+    accounts.extend(
+        users
+            .iter()
+            .map(|user| airdrop.user_data_account(&user.address, 0)),
+    );
+    let mut env = Env::start(accounts).await;
+
+    for user in &users {
+        let instruction = airdrop.commit_draw(&user.address);
+        env.send(&[instruction], &[&airdrop.authority])
+            .await
+            .unwrap();
+        env.warp(1).await;
+
+        let instruction = user.mint_one_shuffled(&env, &airdrop);
+        user.send(&mut env, &airdrop, instruction).await.unwrap();
+    }
+
+    let mut names = vec![];
+    for user in &users {
+        let (metadata, _) = find_metadata_account(&user.mint.pubkey());
+        let metadata = env.account(&metadata).await.unwrap();
+        let metadata: Metadata =
+            try_from_slice_checked(&metadata.data, Key::MetadataV1, MAX_METADATA_LEN).unwrap();
+        names.push(metadata.data.name.trim_end_matches('\0').to_string());
+    }
+
+    let mut expected: Vec<String> = (0..AIRDROP_AMOUNT)
+        .map(|airdrop_index| format!("Test #{}", airdrop_index))
+        .collect();
+    names.sort_unstable();
+    expected.sort_unstable();
+    assert_eq!(names, expected);
+
+    let mut config = env.account(&airdrop.address()).await.unwrap();
+    let airdrop_data = AirdropConfig::unpack_from_slice(&config.data).unwrap();
+    assert_eq!(airdrop_data.airdrop_index, AIRDROP_AMOUNT);
+    assert_eq!(
+        airdrop_data.index_map(&mut config.data).unwrap(),
+        &[0xff, 0x03]
+    );
+}
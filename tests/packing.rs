@@ -0,0 +1,499 @@
+use premint_airdrop::{
+    error::AirdropError,
+    instruction::{
+        add_config_lines, deserialize_instruction_data, initialize_airdrop, reveal,
+        AddConfigLinesArgs, AirdropInstruction, CommitDrawArgs, EndSaleArgs, InitializeAirdropArgs,
+        InitializeAirdropUserDataArgs, MintOneArgs, QuoteArgs, RevealArgs, RevealSeedArgs,
+    },
+    state::{AirdropConfig, AirdropUserData, ConfigLine, ConfigLines},
+};
+use proptest::prelude::*;
+use solana_sdk::{hash::Hash, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+
+fn pubkey() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+fn hash() -> impl Strategy<Value = Hash> {
+    any::<[u8; 32]>().prop_map(Hash::new_from_array)
+}
+
+/// Metadata string, up to 200 bytes so its length fits the one byte prefix
+fn metadata_string() -> impl Strategy<Value = String> {
+    "\\PC{0,50}"
+}
+
+/// Shuffled flag and airdrop amount, small for shuffled airdrops to keep their index map small
+fn index_assignment() -> impl Strategy<Value = (bool, u64)> {
+    prop_oneof![(Just(false), any::<u64>()), (Just(true), 0..4096u64)]
+}
+
+prop_compose! {
+    fn airdrop_config()(
+        initialized in any::<bool>(),
+        airdrop_authority in pubkey(),
+        airdrop_index in any::<u64>(),
+        (shuffled, airdrop_amount) in index_assignment(),
+        airdrop_users in any::<u64>(),
+        revenues_wallet in pubkey(),
+        admin_account in pubkey(),
+        price in any::<u64>(),
+        index_offset in any::<u64>(),
+        index_padding in any::<u8>(),
+        provenance_hash in hash(),
+        seed_commitment in hash(),
+        seed_revealed in any::<bool>(),
+        sale_end_slot in any::<u64>(),
+        starting_offset in any::<u64>(),
+        config_lines in pubkey(),
+        pda_mints in any::<bool>(),
+        require_user_signature in any::<bool>(),
+        require_user_payer in any::<bool>(),
+        base_uri in metadata_string(),
+        uri_extension in metadata_string(),
+        name_template in metadata_string(),
+        symbol in metadata_string(),
+        hidden_name_template in metadata_string(),
+        hidden_uri in metadata_string(),
+    ) -> AirdropConfig {
+        AirdropConfig {
+            initialized,
+            airdrop_authority,
+            airdrop_index,
+            airdrop_amount,
+            airdrop_users,
+            revenues_wallet,
+            admin_account,
+            price,
+            index_offset,
+            index_padding,
+            shuffled,
+            provenance_hash,
+            seed_commitment,
+            seed_revealed,
+            sale_end_slot,
+            starting_offset,
+            config_lines,
+            pda_mints,
+            require_user_signature,
+            require_user_payer,
+            base_uri,
+            uri_extension,
+            name_template,
+            symbol,
+            hidden_name_template,
+            hidden_uri,
+        }
+    }
+}
+
+prop_compose! {
+    fn airdrop_user_data()(
+        initialized in any::<bool>(),
+        airdrop in pubkey(),
+        user in pubkey(),
+        mints_amount in any::<u64>(),
+        locked_till in any::<i64>(),
+        draw_slot in any::<u64>(),
+    ) -> AirdropUserData {
+        AirdropUserData {
+            initialized,
+            airdrop,
+            user,
+            mints_amount,
+            locked_till,
+            draw_slot,
+        }
+    }
+}
+
+fn instruction() -> impl Strategy<Value = AirdropInstruction> {
+    prop_oneof![
+        (
+            (
+                any::<u64>(),
+                any::<u64>(),
+                any::<u64>(),
+                any::<u8>(),
+                any::<bool>()
+            ),
+            (
+                metadata_string(),
+                metadata_string(),
+                metadata_string(),
+                metadata_string(),
+            ),
+            (metadata_string(), metadata_string()),
+            (hash(), hash(), pubkey()),
+            (any::<bool>(), any::<bool>(), any::<bool>()),
+        )
+            .prop_map(
+                |(
+                    (airdrop_amount, price, index_offset, index_padding, shuffled),
+                    (base_uri, uri_extension, name_template, symbol),
+                    (hidden_name_template, hidden_uri),
+                    (provenance_hash, seed_commitment, config_lines),
+                    (pda_mints, require_user_signature, require_user_payer),
+                )| {
+                    AirdropInstruction::InitializeAirdrop(InitializeAirdropArgs {
+                        airdrop_amount,
+                        price,
+                        index_offset,
+                        index_padding,
+                        shuffled,
+                        provenance_hash,
+                        seed_commitment,
+                        config_lines,
+                        pda_mints,
+                        require_user_signature,
+                        require_user_payer,
+                        base_uri,
+                        uri_extension,
+                        name_template,
+                        symbol,
+                        hidden_name_template,
+                        hidden_uri,
+                    })
+                },
+            ),
+        Just(AirdropInstruction::InitializeAirdropUser(
+            InitializeAirdropUserDataArgs {}
+        )),
+        Just(AirdropInstruction::MintOne(MintOneArgs {})),
+        Just(AirdropInstruction::Quote(QuoteArgs {})),
+        proptest::collection::vec(any::<u64>(), 1..=u8::MAX as usize)
+            .prop_map(|airdrop_indices| AirdropInstruction::Reveal(RevealArgs { airdrop_indices })),
+        any::<[u8; 32]>().prop_map(|seed| AirdropInstruction::RevealSeed(RevealSeedArgs { seed })),
+        (
+            any::<u64>(),
+            proptest::collection::vec(
+                (metadata_string(), metadata_string())
+                    .prop_map(|(name, uri)| ConfigLine { name, uri }),
+                1..=8,
+            ),
+        )
+            .prop_map(|(first_index, lines)| {
+                AirdropInstruction::AddConfigLines(AddConfigLinesArgs { first_index, lines })
+            }),
+        Just(AirdropInstruction::CommitDraw(CommitDrawArgs {})),
+        Just(AirdropInstruction::EndSale(EndSaleArgs {})),
+    ]
+}
+
+/// Error `unpack_unchecked` is documented to return for `data`, `None` if data is valid
+/// Account type is checked before data length
+fn expected_unpack_error(data: &[u8], len: usize) -> Option<ProgramError> {
+    match data.first() {
+        Some(1 | 3) => Some(AirdropError::NotAirdropUserData.into()),
+        Some(0 | 2) if data.len() == len => None,
+        _ => Some(ProgramError::InvalidAccountData),
+    }
+}
+
+#[test]
+fn unpack_reports_accounts_of_another_type() {
+    let config = AirdropConfig {
+        initialized: true,
+        base_uri: "https://example.com/".to_string(),
+        name_template: "Test #{}".to_string(),
+        symbol: "TEST".to_string(),
+        ..AirdropConfig::unpack_from_slice(&[0; AirdropConfig::HEADER_LEN]).unwrap()
+    };
+    let mut config_data = vec![0; config.data_len()];
+    config.pack_into_slice(&mut config_data).unwrap();
+
+    let user_data = AirdropUserData {
+        initialized: true,
+        airdrop: Pubkey::new_unique(),
+        user: Pubkey::new_unique(),
+        mints_amount: 1,
+        locked_till: 0,
+        draw_slot: 0,
+    };
+    let mut user_data_data = vec![0; AirdropUserData::LEN];
+    AirdropUserData::pack(user_data, &mut user_data_data).unwrap();
+
+    let config_lines = ConfigLines {
+        initialized: true,
+        airdrop: Pubkey::new_unique(),
+    };
+    let mut config_lines_data = vec![0; ConfigLines::space(2)];
+    config_lines
+        .pack_into_slice(&mut config_lines_data)
+        .unwrap();
+
+    // Accounts differ in size, the account type is reported nevertheless
+    for data in [&user_data_data, &config_lines_data] {
+        assert_eq!(
+            AirdropConfig::unpack_from_slice(data).unwrap_err(),
+            AirdropError::NotAirdropConfig.into()
+        );
+    }
+    for data in [&config_data, &config_lines_data] {
+        assert_eq!(
+            AirdropUserData::unpack_unchecked(data).unwrap_err(),
+            AirdropError::NotAirdropUserData.into()
+        );
+    }
+    for data in [&config_data, &user_data_data] {
+        assert_eq!(
+            ConfigLines::unpack_from_slice(data).unwrap_err(),
+            AirdropError::NotConfigLines.into()
+        );
+    }
+}
+
+#[test]
+fn initialize_airdrop_rejects_strings_over_one_byte() {
+    let args = InitializeAirdropArgs {
+        airdrop_amount: 10,
+        price: 1,
+        index_offset: 0,
+        index_padding: 0,
+        shuffled: false,
+        provenance_hash: Hash::default(),
+        seed_commitment: Hash::default(),
+        config_lines: Pubkey::default(),
+        pda_mints: false,
+        require_user_signature: false,
+        require_user_payer: false,
+        base_uri: String::new(),
+        uri_extension: String::new(),
+        name_template: String::new(),
+        symbol: String::new(),
+        hidden_name_template: String::new(),
+        hidden_uri: String::new(),
+    };
+    let fields: [fn(&mut InitializeAirdropArgs) -> &mut String; 6] = [
+        |args| &mut args.base_uri,
+        |args| &mut args.uri_extension,
+        |args| &mut args.name_template,
+        |args| &mut args.symbol,
+        |args| &mut args.hidden_name_template,
+        |args| &mut args.hidden_uri,
+    ];
+    let bad_argument: ProgramError = AirdropError::BadInstructionArgument.into();
+
+    for field in fields {
+        let mut args = args.clone();
+        *field(&mut args) = "a".repeat(255);
+        let instruction = AirdropInstruction::InitializeAirdrop(args.clone());
+        let data = instruction.pack().unwrap();
+        assert_eq!(deserialize_instruction_data(&data).unwrap(), instruction);
+
+        field(&mut args).push('a');
+        let result = initialize_airdrop(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+            args,
+        );
+        assert_eq!(result.unwrap_err(), bad_argument);
+    }
+}
+
+#[test]
+fn reveal_rejects_more_than_255_tokens() {
+    let tokens: Vec<_> = (0..256)
+        .map(|index| (index, Pubkey::new_unique()))
+        .collect();
+    let airdrop = Pubkey::new_unique();
+    let admin = Pubkey::new_unique();
+
+    let instruction = reveal(&airdrop, &admin, &tokens[..255]).unwrap();
+    assert_eq!(
+        deserialize_instruction_data(&instruction.data).unwrap(),
+        AirdropInstruction::Reveal(RevealArgs {
+            airdrop_indices: (0..255).collect(),
+        })
+    );
+    assert_eq!(
+        reveal(&airdrop, &admin, &tokens).unwrap_err(),
+        AirdropError::BadInstructionArgument.into()
+    );
+}
+
+#[test]
+fn add_config_lines_rejects_lengths_over_one_byte() {
+    let (airdrop, config_lines, admin) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let add_lines = |lines| add_config_lines(&airdrop, &config_lines, &admin, 0, lines);
+    let bad_argument: ProgramError = AirdropError::BadInstructionArgument.into();
+
+    let line = ConfigLine {
+        name: "a".repeat(255),
+        uri: "b".repeat(255),
+    };
+    let instruction = add_lines(vec![line.clone(); 255]).unwrap();
+    assert_eq!(
+        deserialize_instruction_data(&instruction.data).unwrap(),
+        AirdropInstruction::AddConfigLines(AddConfigLinesArgs {
+            first_index: 0,
+            lines: vec![line.clone(); 255],
+        })
+    );
+    assert_eq!(
+        add_lines(vec![line.clone(); 256]).unwrap_err(),
+        bad_argument
+    );
+
+    let long_name = ConfigLine {
+        name: "a".repeat(256),
+        ..line.clone()
+    };
+    let long_uri = ConfigLine {
+        uri: "b".repeat(256),
+        ..line
+    };
+    for line in [long_name, long_uri] {
+        assert_eq!(add_lines(vec![line]).unwrap_err(), bad_argument);
+    }
+}
+
+proptest! {
+    #[test]
+    fn airdrop_config_round_trips(config in airdrop_config()) {
+        let mut data = vec![0; config.data_len()];
+        config.pack_into_slice(&mut data).unwrap();
+
+        // Flags and strings of uninitialized config are not read
+        let expected = match config.initialized {
+            true => config.clone(),
+            false => AirdropConfig {
+                shuffled: false,
+                seed_revealed: false,
+                pda_mints: false,
+                require_user_signature: false,
+                require_user_payer: false,
+                base_uri: String::new(),
+                uri_extension: String::new(),
+                name_template: String::new(),
+                symbol: String::new(),
+                hidden_name_template: String::new(),
+                hidden_uri: String::new(),
+                ..config.clone()
+            },
+        };
+
+        prop_assert_eq!(data[0], if config.initialized { 1 } else { 0 });
+        prop_assert_eq!(AirdropConfig::unpack_from_slice(&data).unwrap(), expected);
+        prop_assert_eq!(
+            config.pack_into_slice(&mut data[..config.data_len() - 1]).unwrap_err(),
+            ProgramError::InvalidAccountData
+        );
+    }
+
+    #[test]
+    fn airdrop_user_data_round_trips(user_data in airdrop_user_data()) {
+        let mut data = vec![0; AirdropUserData::LEN];
+        AirdropUserData::pack(user_data, &mut data).unwrap();
+
+        prop_assert_eq!(data[0], if user_data.initialized { 2 } else { 0 });
+        prop_assert_eq!(AirdropUserData::unpack_unchecked(&data).unwrap(), user_data);
+    }
+
+    #[test]
+    fn instruction_round_trips(instruction in instruction()) {
+        prop_assert_eq!(deserialize_instruction_data(&instruction.pack().unwrap()).unwrap(), instruction);
+    }
+
+    #[test]
+    fn airdrop_config_unpack_rejects_arbitrary_data(
+        data in prop_oneof![
+            proptest::collection::vec(any::<u8>(), 0..512),
+            (airdrop_config(), any::<prop::sample::Index>()).prop_map(|(config, cut)| {
+                let mut data = vec![0; config.data_len()];
+                config.pack_into_slice(&mut data).unwrap();
+                data.truncate(cut.index(data.len() + 1));
+                data
+            }),
+        ],
+    ) {
+        let result = AirdropConfig::unpack_from_slice(&data);
+
+        // Account type is checked before data length
+        match data.first() {
+            Some(2 | 3) => prop_assert_eq!(result.unwrap_err(), AirdropError::NotAirdropConfig.into()),
+            Some(0 | 1) if data.len() < AirdropConfig::HEADER_LEN => {
+                prop_assert_eq!(result.unwrap_err(), ProgramError::InvalidAccountData)
+            }
+            Some(0) => prop_assert!(!result.unwrap().initialized),
+            // Strings may be truncated or not UTF-8, valid config must pack back to the same bytes.
+            // Index map is not packed
+            Some(1) => match result {
+                Ok(config) => {
+                    let mut packed = vec![0; config.data_len()];
+                    config.pack_into_slice(&mut packed).unwrap();
+                    let len = packed.len() - config.index_map(&mut packed).unwrap().len();
+                    prop_assert_eq!(&data[..len], &packed[..len]);
+                }
+                Err(error) => prop_assert_eq!(error, ProgramError::InvalidAccountData),
+            },
+            _ => prop_assert_eq!(result.unwrap_err(), ProgramError::InvalidAccountData),
+        }
+    }
+
+    #[test]
+    fn airdrop_user_data_unpack_rejects_arbitrary_data(
+        data in prop_oneof![
+            proptest::collection::vec(any::<u8>(), 0..256),
+            proptest::collection::vec(any::<u8>(), AirdropUserData::LEN),
+        ],
+    ) {
+        let result = AirdropUserData::unpack_unchecked(&data);
+
+        match expected_unpack_error(&data, AirdropUserData::LEN) {
+            Some(error) => prop_assert_eq!(result.unwrap_err(), error),
+            None => prop_assert!(result.is_ok()),
+        }
+    }
+
+    #[test]
+    fn instruction_parsing_rejects_arbitrary_data(
+        data in proptest::collection::vec(any::<u8>(), 0..128),
+    ) {
+        let result = deserialize_instruction_data(&data);
+
+        match data.split_first() {
+            None => prop_assert_eq!(result.unwrap_err(), ProgramError::InvalidInstructionData),
+            Some((1, body)) if body.len() < 125 => {
+                prop_assert_eq!(result.unwrap_err(), AirdropError::BadInstructionArgument.into())
+            }
+            // Strings may be truncated or not UTF-8, valid arguments must pack back to the same bytes
+            Some((1, _)) => match result {
+                Ok(instruction) => prop_assert_eq!(instruction.pack().unwrap(), data),
+                Err(error) => prop_assert!(
+                    error == AirdropError::BadInstructionArgument.into()
+                        || error == AirdropError::BadMetadataString.into()
+                ),
+            },
+            Some((1..=4 | 8 | 9, _)) => prop_assert!(result.is_ok()),
+            // Reveal takes a non-empty list of indices prefixed with its length
+            Some((5, body)) => match body.split_first() {
+                Some((&count, indices)) if count > 0 && indices.len() == count as usize * 8 => {
+                    prop_assert_eq!(result.unwrap().pack().unwrap(), data)
+                }
+                _ => prop_assert_eq!(result.unwrap_err(), AirdropError::BadInstructionArgument.into()),
+            },
+            // RevealSeed takes exactly the seed
+            Some((6, body)) if body.len() == 32 => prop_assert_eq!(result.unwrap().pack().unwrap(), data),
+            Some((6, _)) => {
+                prop_assert_eq!(result.unwrap_err(), AirdropError::BadInstructionArgument.into())
+            }
+            // AddConfigLines takes the first index and a non-empty list of name and uri pairs
+            Some((7, _)) => match result {
+                Ok(instruction) => prop_assert_eq!(instruction.pack().unwrap(), data),
+                Err(error) => prop_assert!(
+                    error == AirdropError::BadInstructionArgument.into()
+                        || error == AirdropError::BadMetadataString.into()
+                ),
+            },
+            Some(_) => prop_assert_eq!(result.unwrap_err(), AirdropError::BadInstructionId.into()),
+        }
+    }
+}
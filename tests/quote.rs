@@ -0,0 +1,149 @@
+mod common;
+
+use common::{assert_error, Airdrop, Env};
+use premint_airdrop::{error::AirdropError, instruction::quote, pda::find_airdrop_user_data};
+use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey};
+#[cfg(feature = "test-bpf")]
+use {
+    common::{parse_return_data, AIRDROP_AMOUNT, PRICE},
+    premint_airdrop::return_data::{QuoteResult, QuoteStatus, UNKNOWN_INDEX},
+};
+
+#[tokio::test]
+async fn quote_rejects_bad_config() {
+    let airdrop = Airdrop::new();
+    let uninitialized = Airdrop::new();
+    let user = Pubkey::new_unique();
+    let mut env = Env::start(vec![uninitialized.uninitialized_config_account()]).await;
+
+    let result = env.send(&[quote(&airdrop.address(), &user)], &[]).await;
+    assert_error(result, AirdropError::WrongAccountOwner);
+
+    let result = env
+        .send(&[quote(&uninitialized.address(), &user)], &[])
+        .await;
+    assert_error(result, AirdropError::Uninitialized);
+}
+
+#[tokio::test]
+async fn quote_rejects_wrong_accounts() {
+    let airdrop = Airdrop::new();
+    let user = Pubkey::new_unique();
+    let mut env = Env::start(vec![airdrop.config_account(0)]).await;
+
+    let (other_user_data, _) = find_airdrop_user_data(&airdrop.address(), &Pubkey::new_unique());
+    let mut instruction = quote(&airdrop.address(), &user);
+    instruction.accounts[1] = AccountMeta::new_readonly(other_user_data, false);
+    let result = env.send(&[instruction], &[]).await;
+    assert_error(result, AirdropError::PdaCheckFailed);
+
+    let mut instruction = quote(&airdrop.address(), &user);
+    instruction.accounts[3] = AccountMeta::new_readonly(Pubkey::new_unique(), false);
+    let result = env.send(&[instruction], &[]).await;
+    assert_error(result, AirdropError::WrongClockSysvar);
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn quote_reports_eligibility() {
+    let airdrop = Airdrop::new();
+    let exhausted = Airdrop::new();
+    let eligible = Pubkey::new_unique();
+    let locked = Pubkey::new_unique();
+    let mut env = Env::start(vec![
+        airdrop.config_account(1),
+        airdrop.user_data_account(&eligible, 0),
+        airdrop.user_data_account(&locked, i64::MAX),
+        exhausted.config_account(AIRDROP_AMOUNT),
+        exhausted.user_data_account(&eligible, 0),
+    ])
+    .await;
+
+    let cases = [
+        (&airdrop, eligible, QuoteStatus::Eligible),
+        (&airdrop, locked, QuoteStatus::UserTimeout),
+        (
+            &airdrop,
+            Pubkey::new_unique(),
+            QuoteStatus::UserNotRegistered,
+        ),
+        (&exhausted, eligible, QuoteStatus::OutOfSupply),
+    ];
+
+    for (airdrop, user, status) in cases {
+        let logs = env
+            .simulate(&[quote(&airdrop.address(), &user)], &[])
+            .await
+            .logs;
+        let data = parse_return_data(&logs).expect("quote sets return data");
+        let result = QuoteResult::unpack(&data).unwrap();
+
+        assert_eq!(result.status, status);
+        assert_eq!(result.price, PRICE);
+    }
+
+    let logs = env
+        .simulate(&[quote(&airdrop.address(), &eligible)], &[])
+        .await
+        .logs;
+    let result = QuoteResult::unpack(&parse_return_data(&logs).unwrap()).unwrap();
+    assert_eq!(result.next_index, 1);
+    assert_eq!(result.remaining_supply, AIRDROP_AMOUNT - 1);
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn quote_hides_next_index_of_shuffled_airdrop() {
+    let airdrop = Airdrop::new();
+    let user = Pubkey::new_unique();
+    let mut env = Env::start(vec![
+        airdrop.config_account_with(1, airdrop.shuffled_args()),
+        airdrop.user_data_account(&user, 0),
+    ])
+    .await;
+
+    let instruction = airdrop.commit_draw(&user);
+    env.send(&[instruction], &[&airdrop.authority])
+        .await
+        .unwrap();
+    env.warp(1).await;
+
+    let logs = env
+        .simulate(&[quote(&airdrop.address(), &user)], &[])
+        .await
+        .logs;
+    let result = QuoteResult::unpack(&parse_return_data(&logs).unwrap()).unwrap();
+    assert_eq!(result.status, QuoteStatus::Eligible);
+    assert_eq!(result.next_index, UNKNOWN_INDEX);
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn quote_reports_pending_draw_of_shuffled_airdrop() {
+    let airdrop = Airdrop::new();
+    let user = Pubkey::new_unique();
+    let mut env = Env::start(vec![
+        airdrop.config_account_with(1, airdrop.shuffled_args()),
+        airdrop.user_data_account(&user, 0),
+    ])
+    .await;
+
+    let logs = env
+        .simulate(&[quote(&airdrop.address(), &user)], &[])
+        .await
+        .logs;
+    let result = QuoteResult::unpack(&parse_return_data(&logs).unwrap()).unwrap();
+    assert_eq!(result.status, QuoteStatus::DrawNotCommitted);
+
+    let instruction = airdrop.commit_draw(&user);
+    env.send(&[instruction], &[&airdrop.authority])
+        .await
+        .unwrap();
+
+    let logs = env
+        .simulate(&[quote(&airdrop.address(), &user)], &[])
+        .await
+        .logs;
+    let result = QuoteResult::unpack(&parse_return_data(&logs).unwrap()).unwrap();
+    assert_eq!(result.status, QuoteStatus::DrawNotReady);
+}
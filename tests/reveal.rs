@@ -0,0 +1,287 @@
+mod common;
+
+use borsh::BorshSerialize;
+use common::{assert_error, AdminAirdrop, Airdrop, Env};
+use metaplex_token_metadata::{
+    state::{Creator, Data, Key, Metadata, MAX_METADATA_LEN},
+    utils::try_from_slice_checked,
+};
+use premint_airdrop::{
+    error::AirdropError,
+    instruction,
+    pda::{find_metadata_account, find_mint_authority},
+};
+use solana_sdk::{
+    account::Account, program_error::ProgramError, pubkey::Pubkey, signature::Keypair,
+    signer::Signer,
+};
+#[cfg(feature = "test-bpf")]
+use {common::AIRDROP_AMOUNT, premint_airdrop::state::AirdropConfig};
+
+const HIDDEN_URI: &str = "https://example.com/hidden.json";
+
+/// Metadata account of `mint` created by the airdrop mint authority
+fn metadata_account(airdrop: &Airdrop, mint: &Pubkey, name: &str, uri: &str) -> (Pubkey, Account) {
+    let (mint_authority, _) = find_mint_authority(&airdrop.address());
+    let (metadata, _) = find_metadata_account(mint);
+
+    let state = Metadata {
+        key: Key::MetadataV1,
+        update_authority: mint_authority,
+        mint: *mint,
+        data: Data {
+            name: name.to_string(),
+            symbol: airdrop.args().symbol,
+            uri: uri.to_string(),
+            seller_fee_basis_points: 0,
+            creators: Some(vec![Creator {
+                address: mint_authority,
+                verified: true,
+                share: 100,
+            }]),
+        },
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+    };
+    let mut data = vec![0; MAX_METADATA_LEN];
+    state.serialize(&mut data.as_mut_slice()).unwrap();
+
+    (
+        metadata,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: metaplex_token_metadata::id(),
+            ..Account::default()
+        },
+    )
+}
+/// Environment with hidden airdrop, minted `mints` in order of airdrop index
+async fn start(reveal: &AdminAirdrop, mints: &[Pubkey]) -> Env {
+    let mut accounts = vec![reveal.airdrop.hidden_config_account(mints.len() as u64)];
+    accounts.extend(mints.iter().enumerate().map(|(airdrop_index, mint)| {
+        let name = format!("Hidden #{}", airdrop_index);
+        metadata_account(&reveal.airdrop, mint, &name, HIDDEN_URI)
+    }));
+
+    Env::start(accounts).await
+}
+
+#[cfg(feature = "test-bpf")]
+async fn metadata(env: &mut Env, mint: &Pubkey) -> Metadata {
+    let (metadata, _) = find_metadata_account(mint);
+    let metadata = env.account(&metadata).await.unwrap();
+
+    try_from_slice_checked(&metadata.data, Key::MetadataV1, MAX_METADATA_LEN).unwrap()
+}
+
+#[tokio::test]
+async fn reveal_rejects_not_hidden_airdrop() {
+    let reveal = AdminAirdrop::new();
+    let mint = Pubkey::new_unique();
+    let mut env = Env::start(vec![
+        reveal.airdrop.config_account(1),
+        metadata_account(
+            &reveal.airdrop,
+            &mint,
+            "Test #0",
+            "https://example.com/0.json",
+        ),
+    ])
+    .await;
+
+    let instruction = reveal.reveal(&[(0, mint)]);
+    let result = reveal.send(&mut env, instruction).await;
+
+    assert_error(result, AirdropError::NotHidden);
+}
+
+#[tokio::test]
+async fn reveal_requires_admin_signature() {
+    let reveal = AdminAirdrop::new();
+    let mint = Pubkey::new_unique();
+    let mut env = start(&reveal, &[mint]).await;
+
+    let other_admin = Keypair::new();
+    let instruction = instruction::reveal(
+        &reveal.airdrop.address(),
+        &other_admin.pubkey(),
+        &[(0, mint)],
+    )
+    .unwrap();
+    let result = env.send(&[instruction], &[&other_admin]).await;
+    assert_error(result, AirdropError::WrongAccountAddress);
+
+    let mut instruction = reveal.reveal(&[(0, mint)]);
+    instruction.accounts[1].is_signer = false;
+    let result = env.send(&[instruction], &[]).await;
+    assert_error(result, AirdropError::SignerRequired);
+}
+
+#[tokio::test]
+async fn reveal_rejects_wrong_accounts() {
+    let reveal = AdminAirdrop::new();
+    let mint = Pubkey::new_unique();
+    let mut env = start(&reveal, &[mint]).await;
+
+    let cases = [
+        (2, AirdropError::PdaCheckFailed),
+        (3, AirdropError::WrongTokenMetadataProgram),
+    ];
+
+    for (index, error) in cases {
+        let mut instruction = reveal.reveal(&[(0, mint)]);
+        instruction.accounts[index].pubkey = Pubkey::new_unique();
+        let result = reveal.send(&mut env, instruction).await;
+
+        assert_error(result, error);
+    }
+}
+
+#[tokio::test]
+async fn reveal_rejects_bad_metadata() {
+    let reveal = AdminAirdrop::new();
+    let mints = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let mut env = start(&reveal, &mints).await;
+
+    let instruction = reveal.reveal(&[]);
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::BadInstructionArgument);
+
+    let mut instruction = reveal.reveal(&[(0, mints[0])]);
+    instruction.accounts[4].is_writable = false;
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::WriteableRequired);
+
+    let instruction = reveal.reveal(&[(0, Pubkey::new_unique())]);
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::WrongAccountOwner);
+
+    let mut instruction = reveal.reveal(&[(0, mints[0])]);
+    instruction.accounts.pop();
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, ProgramError::NotEnoughAccountKeys);
+
+    // Metadata of token 1 passed for token 0
+    let instruction = reveal.reveal(&[(0, mints[1])]);
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::WrongTokenMetadataAccount);
+
+    // Metadata of the same token created by another airdrop
+    let other = AdminAirdrop::new();
+    let other_mint = Pubkey::new_unique();
+    let (address, account) = metadata_account(&other.airdrop, &other_mint, "Hidden #0", HIDDEN_URI);
+    let mut env = Env::start(vec![
+        reveal.airdrop.hidden_config_account(1),
+        (address, account),
+    ])
+    .await;
+    let instruction = reveal.reveal(&[(0, other_mint)]);
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::WrongTokenMetadataAccount);
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn reveal_reveals_tokens() {
+    let reveal = AdminAirdrop::new();
+    let mints = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let mut env = start(&reveal, &mints).await;
+
+    let instruction = reveal.reveal(&[(0, mints[0]), (1, mints[1])]);
+    reveal.send(&mut env, instruction).await.unwrap();
+
+    for (airdrop_index, mint) in mints.iter().enumerate() {
+        // Metaplex pads metadata strings with zeros
+        let metadata = metadata(&mut env, mint).await;
+        assert_eq!(
+            metadata.data.name.trim_end_matches('\0'),
+            format!("Test #{}", airdrop_index)
+        );
+        assert_eq!(metadata.data.symbol.trim_end_matches('\0'), "TEST");
+        assert_eq!(
+            metadata.data.uri.trim_end_matches('\0'),
+            format!("https://example.com/{}.json", airdrop_index)
+        );
+        assert_eq!(metadata.update_authority, reveal.admin.pubkey());
+    }
+
+    let instruction = reveal.reveal(&[(1, mints[1])]);
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::AlreadyRevealed);
+}
+
+#[tokio::test]
+async fn reveal_rejects_moved_update_authority() {
+    let reveal = AdminAirdrop::new();
+    let mint = Pubkey::new_unique();
+    let (address, mut account) = metadata_account(&reveal.airdrop, &mint, "Hidden #0", HIDDEN_URI);
+    let mut state: Metadata =
+        try_from_slice_checked(&account.data, Key::MetadataV1, MAX_METADATA_LEN).unwrap();
+    state.update_authority = Pubkey::new_unique();
+    state.serialize(&mut account.data.as_mut_slice()).unwrap();
+
+    let mut env = Env::start(vec![
+        reveal.airdrop.hidden_config_account(1),
+        (address, account),
+    ])
+    .await;
+
+    let instruction = reveal.reveal(&[(0, mint)]);
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::WrongTokenMetadataAccount);
+}
+
+#[tokio::test]
+async fn reveal_requires_revealed_seed() {
+    let reveal = AdminAirdrop::new();
+    let mint = Pubkey::new_unique();
+    let args = reveal.airdrop.fair_mint_args();
+    let mut env = Env::start(vec![
+        reveal.airdrop.config_account_with(1, args),
+        metadata_account(&reveal.airdrop, &mint, "Hidden #0", HIDDEN_URI),
+    ])
+    .await;
+
+    let instruction = reveal.reveal(&[(0, mint)]);
+    let result = reveal.send(&mut env, instruction).await;
+
+    assert_error(result, AirdropError::SeedNotRevealed);
+}
+
+#[cfg(feature = "test-bpf")]
+#[tokio::test]
+async fn reveal_shifts_uri_by_starting_offset() {
+    let reveal = AdminAirdrop::new();
+    let mints = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let config = AirdropConfig {
+        seed_revealed: true,
+        starting_offset: AIRDROP_AMOUNT - 1,
+        ..reveal
+            .airdrop
+            .config_with(2, reveal.airdrop.fair_mint_args())
+    };
+    let mut accounts = vec![reveal.airdrop.config_account_from(&config)];
+    accounts.extend(mints.iter().enumerate().map(|(airdrop_index, mint)| {
+        let name = format!("Hidden #{}", airdrop_index);
+        metadata_account(&reveal.airdrop, mint, &name, HIDDEN_URI)
+    }));
+    let mut env = Env::start(accounts).await;
+
+    let instruction = reveal.reveal(&[(0, mints[0]), (1, mints[1])]);
+    reveal.send(&mut env, instruction).await.unwrap();
+
+    // Name keeps the airdrop index, files wrap around the end of the collection
+    for (airdrop_index, file_index) in [(0, 9), (1, 0)] {
+        let metadata = metadata(&mut env, &mints[airdrop_index]).await;
+        assert_eq!(
+            metadata.data.name.trim_end_matches('\0'),
+            format!("Test #{}", airdrop_index)
+        );
+        assert_eq!(
+            metadata.data.uri.trim_end_matches('\0'),
+            format!("https://example.com/{}.json", file_index)
+        );
+    }
+}
@@ -0,0 +1,155 @@
+mod common;
+
+use common::{assert_error, AdminAirdrop, Env, AIRDROP_AMOUNT, SEED};
+use premint_airdrop::{
+    error::AirdropError,
+    instruction,
+    state::AirdropConfig,
+    util::{committed_slot_hash, starting_offset},
+};
+use solana_sdk::{
+    pubkey::Pubkey, signature::Keypair, signer::Signer, slot_hashes::MAX_ENTRIES, sysvar,
+};
+
+/// Environment with fair mint airdrop, `minted` tokens already minted and the sale
+/// ended at slot 1
+async fn start(reveal: &AdminAirdrop, minted: u64) -> Env {
+    let args = reveal.airdrop.fair_mint_args();
+    let config = AirdropConfig {
+        sale_end_slot: 1,
+        ..reveal.airdrop.config_with(minted, args)
+    };
+    Env::start(vec![reveal.airdrop.config_account_from(&config)]).await
+}
+
+/// Environment with fair mint airdrop, `minted` tokens already minted and the sale going on
+async fn start_sale(reveal: &AdminAirdrop, minted: u64) -> Env {
+    let args = reveal.airdrop.fair_mint_args();
+    Env::start(vec![reveal.airdrop.config_account_with(minted, args)]).await
+}
+
+async fn config(env: &mut Env, airdrop: &Pubkey) -> AirdropConfig {
+    let account = env.account(airdrop).await.unwrap();
+    AirdropConfig::unpack_from_slice(&account.data).unwrap()
+}
+
+#[tokio::test]
+async fn reveal_seed_rejects_airdrop_without_commitment() {
+    let reveal = AdminAirdrop::new();
+    let mut env = Env::start(vec![reveal.airdrop.hidden_config_account(AIRDROP_AMOUNT)]).await;
+
+    let instruction = reveal.reveal_seed(SEED);
+    let result = reveal.send(&mut env, instruction).await;
+
+    assert_error(result, AirdropError::NoSeedCommitment);
+}
+
+#[tokio::test]
+async fn reveal_seed_requires_ended_sale() {
+    let reveal = AdminAirdrop::new();
+    let mut env = start_sale(&reveal, AIRDROP_AMOUNT).await;
+
+    let instruction = reveal.reveal_seed(SEED);
+    let result = reveal.send(&mut env, instruction).await;
+
+    assert_error(result, AirdropError::SaleNotFinished);
+}
+
+#[tokio::test]
+async fn reveal_seed_requires_admin_signature() {
+    let reveal = AdminAirdrop::new();
+    let mut env = start(&reveal, AIRDROP_AMOUNT).await;
+
+    let other_admin = Keypair::new();
+    let instruction =
+        instruction::reveal_seed(&reveal.airdrop.address(), &other_admin.pubkey(), SEED);
+    let result = env.send(&[instruction], &[&other_admin]).await;
+    assert_error(result, AirdropError::WrongAccountAddress);
+
+    let mut instruction = reveal.reveal_seed(SEED);
+    instruction.accounts[1].is_signer = false;
+    let result = env.send(&[instruction], &[]).await;
+    assert_error(result, AirdropError::SignerRequired);
+}
+
+#[tokio::test]
+async fn reveal_seed_rejects_bad_accounts_and_arguments() {
+    let reveal = AdminAirdrop::new();
+    let mut env = start(&reveal, AIRDROP_AMOUNT).await;
+
+    let mut instruction = reveal.reveal_seed(SEED);
+    instruction.accounts[0].is_writable = false;
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::WriteableRequired);
+
+    let mut instruction = reveal.reveal_seed(SEED);
+    instruction.accounts[2].pubkey = Pubkey::new_unique();
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::WrongSlotHashesSysvar);
+
+    let mut instruction = reveal.reveal_seed(SEED);
+    instruction.data.pop();
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::BadInstructionArgument);
+
+    let instruction = reveal.reveal_seed([8; 32]);
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::WrongSeed);
+}
+
+#[tokio::test]
+async fn reveal_seed_requires_hash_of_sale_end_slot() {
+    let reveal = AdminAirdrop::new();
+    let mut env = start_sale(&reveal, AIRDROP_AMOUNT).await;
+
+    let instruction = reveal.end_sale();
+    reveal.send(&mut env, instruction).await.unwrap();
+
+    // Hash of the sale end slot is known only once the slot is over
+    let instruction = reveal.reveal_seed(SEED);
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::SlotHashNotReady);
+
+    env.warp(MAX_ENTRIES as u64 + 1).await;
+    let instruction = reveal.reveal_seed(SEED);
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::SlotHashExpired);
+}
+
+#[tokio::test]
+async fn reveal_seed_stores_starting_offset() {
+    let reveal = AdminAirdrop::new();
+    let mut env = start_sale(&reveal, AIRDROP_AMOUNT).await;
+
+    let instruction = reveal.end_sale();
+    reveal.send(&mut env, instruction).await.unwrap();
+
+    env.warp(1).await;
+    let instruction = reveal.reveal_seed(SEED);
+    reveal.send(&mut env, instruction).await.unwrap();
+
+    let address = reveal.airdrop.address();
+    let config = config(&mut env, &address).await;
+    let slot_hashes = env.account(&sysvar::slot_hashes::id()).await.unwrap();
+    let slot_hash = committed_slot_hash(
+        &slot_hashes.data,
+        config.sale_end_slot,
+        config.sale_end_slot + 1,
+    )
+    .unwrap();
+
+    assert!(config.seed_revealed);
+    assert_eq!(
+        config.starting_offset,
+        starting_offset(&SEED, &address, &slot_hash, AIRDROP_AMOUNT)
+    );
+    assert_eq!(
+        config.seed_commitment,
+        reveal.airdrop.fair_mint_args().seed_commitment
+    );
+
+    // Seed is checked after the reveal flag, so the instruction differs from the first one
+    let instruction = reveal.reveal_seed([8; 32]);
+    let result = reveal.send(&mut env, instruction).await;
+    assert_error(result, AirdropError::SeedAlreadyRevealed);
+}